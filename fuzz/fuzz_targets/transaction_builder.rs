@@ -4,7 +4,7 @@ use {
   arbitrary::Arbitrary,
   bitcoin::{Amount, OutPoint},
   libfuzzer_sys::fuzz_target,
-  ord::{FeeRate, SatPoint, TransactionBuilder},
+  ord::{CoinSelection, FeeRate, SatPoint, TransactionBuilder},
   std::collections::BTreeMap,
 };
 
@@ -12,6 +12,7 @@ use {
 struct Input {
   output_value: Option<u64>,
   fee_rate: f64,
+  largest_first: bool,
   utxos: Vec<u64>,
 }
 
@@ -58,6 +59,12 @@ fuzz_target!(|input: Input| {
 
   let Ok(fee_rate) = FeeRate::try_from(input.fee_rate) else { return; };
 
+  let coin_selection = if input.largest_first {
+    CoinSelection::LargestFirst
+  } else {
+    CoinSelection::BranchAndBound
+  };
+
   match input.output_value {
     Some(output_value) => {
       let _ = TransactionBuilder::build_transaction_with_value(
@@ -68,6 +75,7 @@ fuzz_target!(|input: Input| {
         change,
         fee_rate,
         Amount::from_sat(output_value),
+        coin_selection,
       );
     }
     None => {
@@ -78,6 +86,7 @@ fuzz_target!(|input: Input| {
         recipient,
         change,
         fee_rate,
+        coin_selection,
       );
     }
   }
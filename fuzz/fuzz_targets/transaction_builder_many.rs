@@ -0,0 +1,92 @@
+#![no_main]
+
+use {
+  arbitrary::Arbitrary,
+  bitcoin::{Address, Amount, Network, OutPoint, ScriptBuf},
+  libfuzzer_sys::fuzz_target,
+  ord::{CoinSelection, FeeRate, SatPoint, TransactionBuilder},
+  std::collections::BTreeMap,
+};
+
+#[derive(Clone, Debug, Arbitrary)]
+struct Send {
+  value: u64,
+  offset: u64,
+  inscribed: bool,
+  recipient: u8,
+}
+
+#[derive(Clone, Debug, Arbitrary)]
+struct Input {
+  sends: Vec<Send>,
+  fee_rate: f64,
+  largest_first: bool,
+  utxos: Vec<u64>,
+}
+
+fn address(tag: u8, i: usize) -> Address {
+  let mut script = vec![tag];
+  script.extend_from_slice(&i.to_le_bytes());
+  Address::p2wsh(&ScriptBuf::from(script), Network::Bitcoin)
+}
+
+fuzz_target!(|input: Input| {
+  let mut sends = Vec::new();
+  let mut inscriptions = BTreeMap::new();
+  let mut amounts = BTreeMap::new();
+
+  for (i, send) in input.sends.into_iter().enumerate() {
+    let outpoint = format!("1111111111111111111111111111111111111111111111111111111111111111:{i}")
+      .parse::<OutPoint>()
+      .unwrap();
+
+    let satpoint = SatPoint {
+      outpoint,
+      offset: send.offset,
+    };
+
+    if send.inscribed {
+      inscriptions.insert(
+        satpoint,
+        format!("1111111111111111111111111111111111111111111111111111111111111111i{i}")
+          .parse()
+          .unwrap(),
+      );
+    }
+
+    amounts.insert(
+      outpoint,
+      Amount::from_sat(send.value.min(Amount::MAX_MONEY.to_sat())),
+    );
+
+    sends.push((satpoint, address(0, send.recipient.into())));
+  }
+
+  for (i, value) in input.utxos.into_iter().enumerate() {
+    amounts.insert(
+      format!("0000000000000000000000000000000000000000000000000000000000000000:{i}",)
+        .parse()
+        .unwrap(),
+      Amount::from_sat(value.min(Amount::MAX_MONEY.to_sat())),
+    );
+  }
+
+  let change = (0..=sends.len()).map(|i| address(1, i)).collect();
+
+  let Ok(fee_rate) = FeeRate::try_from(input.fee_rate) else { return; };
+
+  let coin_selection = if input.largest_first {
+    CoinSelection::LargestFirst
+  } else {
+    CoinSelection::BranchAndBound
+  };
+
+  let _ = TransactionBuilder::build_transaction_with_postages(
+    sends,
+    inscriptions,
+    amounts,
+    change,
+    fee_rate,
+    coin_selection,
+  );
+});
@@ -41,4 +41,28 @@ pub struct InscriptionFirstOwner {
 pub struct InscriptionContentType {
     pub content_type: String,
     pub address: String,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct InscriptionDetail {
+    pub inscription_id: String,
+    pub number: i64,
+    pub genesis_height: u64,
+    pub genesis_fee: u64,
+    pub timestamp: u32,
+    pub content_type: Option<String>,
+    pub content_length: Option<usize>,
+    pub delegate: Option<String>,
+    pub metaprotocol: Option<String>,
+    pub sat: Option<u64>,
+    pub height: u64,
+    pub satpoint: String,
+    pub address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct InscriptionList {
+    pub inscriptions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<i64>,
+}
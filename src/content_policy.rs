@@ -0,0 +1,194 @@
+use super::*;
+
+#[derive(Debug, Default, Parser)]
+pub(crate) struct ContentPolicy {
+  #[clap(
+    long,
+    help = "Use <CONTENT_TYPE> instead of the content type implied by the file extension."
+  )]
+  pub(crate) content_type: Option<String>,
+  #[clap(
+    long,
+    help = "Refuse to inscribe content larger than <MAX_BODY_SIZE> bytes, after compression."
+  )]
+  pub(crate) max_body_size: Option<usize>,
+}
+
+impl ContentPolicy {
+  pub(crate) fn content_type(&self, path: &Path, body: &[u8]) -> Result<String> {
+    let content_type = match &self.content_type {
+      Some(content_type) => content_type.clone(),
+      None => {
+        let content_type = Media::content_type_for_path(path)?;
+
+        if let Some(sniffed) = Media::sniff(body) {
+          if !sniffed.contains(&content_type) {
+            bail!(
+              "{} looks like {} but its extension implies {content_type}, use --content-type to override",
+              path.display(),
+              sniffed[0],
+            );
+          }
+        }
+
+        content_type.into()
+      }
+    };
+
+    if content_type.starts_with("text/plain") {
+      if let Ok(text) = std::str::from_utf8(body) {
+        Self::check_name(text)?;
+      }
+    }
+
+    Ok(content_type)
+  }
+
+  pub(crate) fn check_body_size(&self, len: usize) -> Result {
+    if let Some(limit) = self.max_body_size {
+      if len > limit {
+        bail!("content size of {len} bytes exceeds {limit} byte maximum body size");
+      }
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn check_name(text: &str) -> Result {
+    let trimmed = text.trim();
+
+    // only text that looks like a single name is checked, so prose mentioning a name is fine
+    if !trimmed.ends_with(".btc") || trimmed.contains(char::is_whitespace) {
+      return Ok(());
+    }
+
+    let label = trimmed.strip_suffix(".btc").unwrap();
+
+    let reason = if text != trimmed {
+      "has leading or trailing whitespace"
+    } else if label.is_empty() {
+      "name is empty"
+    } else if label.contains('.') {
+      "contains more than one `.`"
+    } else if text.to_lowercase() != text {
+      "contains uppercase characters"
+    } else if label.starts_with('-') || label.ends_with('-') {
+      "starts or ends with `-`"
+    } else if label
+      .chars()
+      .any(|c| c.is_control() || (c.is_ascii_punctuation() && c != '-'))
+    {
+      "contains punctuation or control characters"
+    } else {
+      return Ok(());
+    };
+
+    bail!("refusing to inscribe malformed .btc name {text:?}: {reason}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn extension_is_used_by_default() {
+    assert_eq!(
+      ContentPolicy::default()
+        .content_type(Path::new("foo.txt"), b"hello")
+        .unwrap(),
+      "text/plain;charset=utf-8"
+    );
+  }
+
+  #[test]
+  fn content_type_can_be_overridden() {
+    assert_eq!(
+      ContentPolicy {
+        content_type: Some("application/x-foo".into()),
+        ..Default::default()
+      }
+      .content_type(Path::new("foo.bar"), b"hello")
+      .unwrap(),
+      "application/x-foo"
+    );
+  }
+
+  #[test]
+  fn mismatched_magic_bytes_are_rejected() {
+    assert_regex_match!(
+      ContentPolicy::default()
+        .content_type(Path::new("foo.txt"), b"\x89PNG\r\n\x1a\n")
+        .unwrap_err(),
+      "foo.txt looks like image/png but its extension implies text/plain;charset=utf-8, use --content-type to override"
+    );
+  }
+
+  #[test]
+  fn compatible_magic_bytes_are_accepted() {
+    assert_eq!(
+      ContentPolicy::default()
+        .content_type(Path::new("foo.apng"), b"\x89PNG\r\n\x1a\n")
+        .unwrap(),
+      "image/apng"
+    );
+  }
+
+  #[test]
+  fn body_size() {
+    let policy = ContentPolicy {
+      max_body_size: Some(3),
+      ..Default::default()
+    };
+
+    assert!(policy.check_body_size(3).is_ok());
+    assert_eq!(
+      policy.check_body_size(4).unwrap_err().to_string(),
+      "content size of 4 bytes exceeds 3 byte maximum body size"
+    );
+    assert!(ContentPolicy::default().check_body_size(4).is_ok());
+  }
+
+  #[test]
+  fn valid_names_are_accepted() {
+    for name in ["foo.btc", "foo-bar.btc", "123.btc", "ünicode.btc", "🍊.btc"] {
+      assert!(ContentPolicy::check_name(name).is_ok(), "{name}");
+    }
+  }
+
+  #[test]
+  fn text_that_is_not_a_name_is_ignored() {
+    for text in ["hello", "i love foo.btc", "foo.btc is great", "foo.sats"] {
+      assert!(ContentPolicy::check_name(text).is_ok(), "{text}");
+    }
+  }
+
+  #[test]
+  fn malformed_names_are_rejected() {
+    #[track_caller]
+    fn case(name: &str, reason: &str) {
+      assert_eq!(
+        ContentPolicy::check_name(name).unwrap_err().to_string(),
+        format!("refusing to inscribe malformed .btc name {name:?}: {reason}")
+      );
+    }
+
+    case("foo.btc\n", "has leading or trailing whitespace");
+    case(".btc", "name is empty");
+    case("foo.bar.btc", "contains more than one `.`");
+    case("Foo.btc", "contains uppercase characters");
+    case("-foo.btc", "starts or ends with `-`");
+    case("foo-.btc", "starts or ends with `-`");
+    case("foo_bar.btc", "contains punctuation or control characters");
+  }
+
+  #[test]
+  fn names_are_only_checked_for_plain_text() {
+    assert!(ContentPolicy::default()
+      .content_type(Path::new("foo.txt"), b"Foo.btc")
+      .is_err());
+    assert!(ContentPolicy::default()
+      .content_type(Path::new("foo.json"), b"Foo.btc")
+      .is_ok());
+  }
+}
@@ -1,8 +1,8 @@
 use {
   self::{
     entry::{
-      BlockHashValue, Entry, InscriptionEntry, InscriptionEntryValue, InscriptionIdValue,
-      OutPointValue, SatPointValue, SatRange,
+      BlockHashValue, Entry, InscriptionEntry, InscriptionEntryValue, InscriptionIdHeightValue,
+      InscriptionIdValue, OutPointValue, SatPointValue, SatRange,
     },
     updater::Updater,
   },
@@ -14,10 +14,11 @@ use {
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
   redb::{
-    Database, MultimapTable, MultimapTableDefinition, ReadableMultimapTable, ReadableTable, Table,
-    TableDefinition, WriteTransaction,
+    Database, MultimapTable, MultimapTableDefinition, MultimapTableHandle, ReadTransaction,
+    ReadableMultimapTable, ReadableTable, RedbKey, RedbValue, StorageError, Table, TableDefinition,
+    TableError, TableHandle, WriteTransaction,
   },
-  std::collections::HashMap,
+  std::collections::{BTreeSet, HashMap},
   std::io::{BufWriter, Write},
   std::sync::atomic::{self, AtomicBool},
 };
@@ -27,7 +28,7 @@ mod fetcher;
 mod rtx;
 mod updater;
 
-const SCHEMA_VERSION: u64 = 5;
+const SCHEMA_VERSION: u64 = 10;
 
 macro_rules! define_table {
   ($name:ident, $key:ty, $value:ty) => {
@@ -45,13 +46,18 @@ macro_rules! define_multimap_table {
 define_table! { HEIGHT_TO_BLOCK_HASH, u64, &BlockHashValue }
 define_table! { INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, &InscriptionIdValue, InscriptionEntryValue }
 define_table! { INSCRIPTION_ID_TO_SATPOINT, &InscriptionIdValue, &SatPointValue }
+define_table! { INSCRIPTION_ID_AND_HEIGHT_TO_SATPOINT, &InscriptionIdHeightValue, &SatPointValue }
 define_table! { INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, i64, &InscriptionIdValue }
+define_table! { METAPROTOCOL_AND_NUMBER_TO_INSCRIPTION_ID, (&str, i64), &InscriptionIdValue }
 define_table! { OUTPOINT_TO_SAT_RANGES, &OutPointValue, &[u8] }
+define_table! { OUTPOINT_TO_TXOUT, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_VALUE, &OutPointValue, u64}
+define_multimap_table! { PARENT_TO_CHILDREN, &InscriptionIdValue, &InscriptionIdValue }
 define_table! { REINSCRIPTION_ID_TO_SEQUENCE_NUMBER, &InscriptionIdValue, u64 }
 define_multimap_table! { SATPOINT_TO_INSCRIPTION_ID, &SatPointValue, &InscriptionIdValue }
 define_multimap_table! { SAT_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], &OutPointValue }
 define_table! { STATISTIC_TO_COUNT, u64, u64 }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u64, u128 }
 
@@ -65,6 +71,14 @@ pub(crate) struct Index {
   height_limit: Option<u64>,
   options: Options,
   reorged: AtomicBool,
+  throughput: Mutex<Throughput>,
+}
+
+#[derive(Default)]
+struct Throughput {
+  blocks_indexed: u64,
+  blocks_per_second: f64,
+  last_commit_duration: Duration,
 }
 
 #[derive(Debug, PartialEq)]
@@ -73,6 +87,29 @@ pub(crate) enum List {
   Unspent(Vec<(u64, u64)>),
 }
 
+#[derive(Debug, PartialEq)]
+pub(crate) enum SatIndexMode {
+  Disabled,
+  Full,
+  Selective(Rarity),
+}
+
+impl Display for SatIndexMode {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Disabled => write!(f, "sats are not indexed"),
+      Self::Full => write!(f, "all sats are indexed"),
+      Self::Selective(rarity) => write!(f, "only sats of rarity {rarity} or higher are indexed"),
+    }
+  }
+}
+
+/// Sat ranges of outputs that hold no tracked sats are collapsed into
+/// placeholder ranges starting at `Sat::SUPPLY`, which only record size.
+pub(crate) fn is_tracked_sat_range((start, _end): &(u64, u64)) -> bool {
+  *start < Sat::SUPPLY
+}
+
 #[derive(Copy, Clone)]
 #[repr(u64)]
 pub(crate) enum Statistic {
@@ -82,14 +119,43 @@ pub(crate) enum Statistic {
   OutputsTraversed = 3,
   SatRanges = 4,
   UnboundInscriptions = 5,
+  SatIndexMinRarity = 6,
 }
 
 impl Statistic {
+  const ALL: [Statistic; 7] = [
+    Self::Schema,
+    Self::Commits,
+    Self::LostSats,
+    Self::OutputsTraversed,
+    Self::SatRanges,
+    Self::UnboundInscriptions,
+    Self::SatIndexMinRarity,
+  ];
+
   fn key(self) -> u64 {
     self.into()
   }
 }
 
+impl Display for Statistic {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Schema => "schema",
+        Self::Commits => "commits",
+        Self::LostSats => "lost_sats",
+        Self::OutputsTraversed => "outputs_traversed",
+        Self::SatRanges => "sat_ranges",
+        Self::UnboundInscriptions => "unbound_inscriptions",
+        Self::SatIndexMinRarity => "sat_index_min_rarity",
+      }
+    )
+  }
+}
+
 impl From<Statistic> for u64 {
   fn from(statistic: Statistic) -> Self {
     statistic as u64
@@ -114,6 +180,76 @@ pub(crate) struct Info {
   pub(crate) utxos_indexed: u64,
 }
 
+pub(crate) struct Metrics {
+  pub(crate) blocks_indexed: u64,
+  pub(crate) blocks_indexed_total: u64,
+  pub(crate) blocks_per_second: f64,
+  pub(crate) chain_blocks: u64,
+  pub(crate) index_file_size: u64,
+  pub(crate) last_commit_duration: Duration,
+  pub(crate) statistics: Vec<(Statistic, u64)>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Discrepancy {
+  EntryMissingNumber {
+    inscription_id: InscriptionId,
+    number: i64,
+  },
+  EntryMissingSat {
+    inscription_id: InscriptionId,
+    sat: Sat,
+  },
+  InscriptionMissingEntry {
+    inscription_id: InscriptionId,
+  },
+  InscriptionMissingSatpoint {
+    inscription_id: InscriptionId,
+    satpoint: SatPoint,
+  },
+  NumberMismatch {
+    number: i64,
+    inscription_id: InscriptionId,
+    entry_number: Option<i64>,
+  },
+  OutpointMissingTxOut {
+    outpoint: OutPoint,
+    script_pubkey: ScriptBuf,
+  },
+  OverlappingSatRanges {
+    first: OutPoint,
+    second: OutPoint,
+    start: u64,
+    end: u64,
+  },
+  SatMismatch {
+    sat: Sat,
+    inscription_id: InscriptionId,
+    entry_sat: Option<Sat>,
+  },
+  SatpointMissingInscription {
+    satpoint: SatPoint,
+    inscription_id: InscriptionId,
+  },
+  ScriptPubkeyMissingOutpoint {
+    script_pubkey: ScriptBuf,
+    outpoint: OutPoint,
+  },
+  SupplyMismatch {
+    expected: u64,
+    actual: u64,
+  },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct TableInfo {
+  pub(crate) name: String,
+  pub(crate) rows: u64,
+  pub(crate) key_bytes: u64,
+  pub(crate) value_bytes: u64,
+}
+
 #[derive(Serialize)]
 pub(crate) struct TransactionInfo {
   pub(crate) starting_block_count: u64,
@@ -198,6 +334,33 @@ impl Index {
           }
         }
 
+        if let Some(min_rarity) = options.index_sats_min_rarity {
+          let index_min_rarity = database
+            .begin_read()?
+            .open_table(STATISTIC_TO_COUNT)?
+            .get(&Statistic::SatIndexMinRarity.key())?
+            .map(|x| x.value());
+
+          if index_min_rarity != Some(min_rarity.into()) {
+            bail!(
+              "index at `{}` was not created with `--index-sats-min-rarity {min_rarity}`, consider deleting and rebuilding the index",
+              path.display()
+            );
+          }
+        }
+
+        if options.index_addresses
+          && database
+            .begin_read()?
+            .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)
+            .is_err()
+        {
+          bail!(
+            "index at `{}` was not created with `--index-addresses`, consider deleting and rebuilding the index",
+            path.display()
+          );
+        }
+
         database
       }
       Err(_) => {
@@ -216,8 +379,11 @@ impl Index {
         tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
         tx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+        tx.open_table(INSCRIPTION_ID_AND_HEIGHT_TO_SATPOINT)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+        tx.open_table(METAPROTOCOL_AND_NUMBER_TO_INSCRIPTION_ID)?;
         tx.open_table(OUTPOINT_TO_VALUE)?;
+        tx.open_multimap_table(PARENT_TO_CHILDREN)?;
         tx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
         tx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
         tx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
@@ -230,6 +396,16 @@ impl Index {
         if options.index_sats {
           tx.open_table(OUTPOINT_TO_SAT_RANGES)?
             .insert(&OutPoint::null().store(), [].as_slice())?;
+
+          if let Some(min_rarity) = options.index_sats_min_rarity {
+            tx.open_table(STATISTIC_TO_COUNT)?
+              .insert(&Statistic::SatIndexMinRarity.key(), &min_rarity.into())?;
+          }
+        }
+
+        if options.index_addresses {
+          tx.open_table(OUTPOINT_TO_TXOUT)?;
+          tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
         }
 
         tx.commit()?;
@@ -251,17 +427,77 @@ impl Index {
       height_limit: options.height_limit,
       reorged: AtomicBool::new(false),
       options: options.clone(),
+      throughput: Mutex::new(Throughput::default()),
     })
   }
 
-  pub(crate) fn get_unspent_outputs(&self, _wallet: Wallet) -> Result<BTreeMap<OutPoint, Amount>> {
+  pub(crate) fn get_unspent_outputs(&self, wallet: Wallet) -> Result<BTreeMap<OutPoint, Amount>> {
+    Ok(Self::without_exclusions(
+      self.get_unspent_outputs_with_exclusions(wallet, false)?,
+    ))
+  }
+
+  pub(crate) fn get_pending_unspent_outputs(
+    &self,
+    wallet: Wallet,
+  ) -> Result<BTreeMap<OutPoint, Amount>> {
+    Ok(Self::without_exclusions(
+      self.get_unspent_outputs_with_exclusions(wallet, true)?,
+    ))
+  }
+
+  /// Every unspent output of `wallet`, each with the reason it is left out of
+  /// `get_unspent_outputs`, if any. Outputs holding inscriptions are never left
+  /// out, however small.
+  pub(crate) fn get_unspent_outputs_with_exclusions(
+    &self,
+    wallet: Wallet,
+    include_unsafe: bool,
+  ) -> Result<BTreeMap<OutPoint, (Amount, Option<String>)>> {
+    // watch-only wallets only see outputs that have been indexed
+    let utxos = match wallet.watch_only() {
+      Some(script_pubkeys) => self.get_watched_outputs(script_pubkeys)?,
+      None => self.get_wallet_outputs(include_unsafe)?,
+    };
+
+    let min_cardinal_value = wallet.min_cardinal_value();
+
+    utxos
+      .into_iter()
+      .map(|(outpoint, amount)| {
+        let exclusion = if amount.to_sat() <= min_cardinal_value
+          && self.get_inscriptions_on_output(outpoint)?.is_empty()
+        {
+          Some(format!(
+            "cardinal value of {} sats is not above --min-cardinal-value of {min_cardinal_value} sats",
+            amount.to_sat()
+          ))
+        } else {
+          None
+        };
+
+        Ok((outpoint, (amount, exclusion)))
+      })
+      .collect()
+  }
+
+  fn without_exclusions(
+    utxos: BTreeMap<OutPoint, (Amount, Option<String>)>,
+  ) -> BTreeMap<OutPoint, Amount> {
+    utxos
+      .into_iter()
+      .filter(|(_, (_, exclusion))| exclusion.is_none())
+      .map(|(outpoint, (amount, _))| (outpoint, amount))
+      .collect()
+  }
+
+  fn get_wallet_outputs(&self, include_unsafe: bool) -> Result<BTreeMap<OutPoint, Amount>> {
     let mut utxos = BTreeMap::new();
     utxos.extend(
       self
         .client
-        .list_unspent(None, None, None, None, None)?
+        .list_unspent(None, None, None, include_unsafe.then_some(true), None)?
         .into_iter()
-        .filter(|utxo| utxo.amount.to_sat() > 2000)
         .map(|utxo| {
           let outpoint = OutPoint::new(utxo.txid, utxo.vout);
           let amount = utxo.amount;
@@ -285,50 +521,52 @@ impl Index {
         Amount::from_sat(self.client.get_raw_transaction(&txid, None)?.output[vout as usize].value),
       );
     }
-    let rtx = self.database.begin_read()?;
-    let outpoint_to_value = rtx.open_table(OUTPOINT_TO_VALUE)?;
-    for outpoint in utxos.keys() {
-      if outpoint_to_value.get(&outpoint.store())?.is_none() {
-        return Err(anyhow!(
-          "output in Bitcoin Core wallet but not in ord index: {outpoint}"
-        ));
+
+    // unsafe outputs may not have been mined, and so not yet indexed
+    if !include_unsafe {
+      let rtx = self.database.begin_read()?;
+      let outpoint_to_value = rtx.open_table(OUTPOINT_TO_VALUE)?;
+      for outpoint in utxos.keys() {
+        if outpoint_to_value.get(&outpoint.store())?.is_none() {
+          return Err(anyhow!(
+            "output in Bitcoin Core wallet but not in ord index: {outpoint}"
+          ));
+        }
       }
     }
 
     Ok(utxos)
   }
 
+  fn get_watched_outputs(
+    &self,
+    script_pubkeys: &BTreeSet<ScriptBuf>,
+  ) -> Result<BTreeMap<OutPoint, Amount>> {
+    if !self.has_address_index()? {
+      bail!("watch-only wallet requires index created with `--index-addresses` flag");
+    }
+
+    let rtx = self.database.begin_read()?;
+    let outpoint_to_txout = rtx.open_table(OUTPOINT_TO_TXOUT)?;
+    let script_pubkey_to_outpoint = rtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
 
-  pub(crate) fn get_pending_unspent_outputs(&self, _wallet: Wallet) -> Result<BTreeMap<OutPoint, Amount>> {
     let mut utxos = BTreeMap::new();
-    utxos.extend(
-      self
-        .client
-        .list_unspent(None, None, None, Some(true), None)?
-        .into_iter()
-        .filter(|utxo| utxo.amount.to_sat() > 2000)
-        .map(|utxo| {
-          let outpoint = OutPoint::new(utxo.txid, utxo.vout);
-          let amount = utxo.amount;
-          (outpoint, amount)
-        }),
-    );
 
-    #[derive(Deserialize)]
-    pub(crate) struct JsonOutPoint {
-      txid: bitcoin::Txid,
-      vout: u32,
-    }
+    for script_pubkey in script_pubkeys {
+      for outpoint in script_pubkey_to_outpoint.get(script_pubkey.as_bytes())? {
+        let outpoint = OutPoint::load(*outpoint?.value());
 
-    for JsonOutPoint { txid, vout } in self
-      .client
-      .call::<Vec<JsonOutPoint>>("listlockunspent", &[])?
-    {
-      utxos.insert(
-        OutPoint { txid, vout },
-        Amount::from_sat(self.client.get_raw_transaction(&txid, None)?.output[vout as usize].value),
-      );
+        let tx_out = consensus::deserialize::<TxOut>(
+          outpoint_to_txout
+            .get(&outpoint.store())?
+            .ok_or_else(|| anyhow!("address index is missing output {outpoint}"))?
+            .value(),
+        )?;
+
+        utxos.insert(outpoint, Amount::from_sat(tx_out.value));
+      }
     }
+
     Ok(utxos)
   }
 
@@ -355,12 +593,45 @@ impl Index {
     }
   }
 
-  fn require_sat_index(&self, feature: &str) -> Result {
+  pub(crate) fn has_address_index(&self) -> Result<bool> {
+    match self
+      .begin_read()?
+      .0
+      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)
+    {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn sat_index_mode(&self) -> Result<SatIndexMode> {
     if !self.has_sat_index()? {
-      bail!("{feature} requires index created with `--index-sats` flag")
+      return Ok(SatIndexMode::Disabled);
     }
 
-    Ok(())
+    let min_rarity = self
+      .begin_read()?
+      .0
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::SatIndexMinRarity.key())?
+      .map(|x| x.value())
+      .unwrap_or_default();
+
+    Ok(match Rarity::try_from(min_rarity)? {
+      Rarity::Common => SatIndexMode::Full,
+      rarity => SatIndexMode::Selective(rarity),
+    })
+  }
+
+  fn require_sat_index(&self, feature: &str) -> Result<SatIndexMode> {
+    let mode = self.sat_index_mode()?;
+
+    if mode == SatIndexMode::Disabled {
+      bail!("{feature} requires index created with `--index-sats` flag, but {mode}")
+    }
+
+    Ok(mode)
   }
 
   pub(crate) fn info(&self) -> Result<Info> {
@@ -416,6 +687,341 @@ impl Index {
     Ok(info)
   }
 
+  pub(crate) fn table_info(&self) -> Result<Vec<TableInfo>> {
+    let rtx = self.database.begin_read()?;
+
+    let tables = [
+      Self::table_info_for(&rtx, HEIGHT_TO_BLOCK_HASH)?,
+      Self::table_info_for(&rtx, INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?,
+      Self::table_info_for(&rtx, INSCRIPTION_ID_TO_SATPOINT)?,
+      Self::table_info_for(&rtx, INSCRIPTION_ID_AND_HEIGHT_TO_SATPOINT)?,
+      Self::table_info_for(&rtx, INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?,
+      Self::table_info_for(&rtx, METAPROTOCOL_AND_NUMBER_TO_INSCRIPTION_ID)?,
+      Self::table_info_for(&rtx, OUTPOINT_TO_SAT_RANGES)?,
+      Self::table_info_for(&rtx, OUTPOINT_TO_TXOUT)?,
+      Self::table_info_for(&rtx, OUTPOINT_TO_VALUE)?,
+      Self::multimap_table_info_for(&rtx, PARENT_TO_CHILDREN)?,
+      Self::table_info_for(&rtx, REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?,
+      Self::multimap_table_info_for(&rtx, SATPOINT_TO_INSCRIPTION_ID)?,
+      Self::multimap_table_info_for(&rtx, SAT_TO_INSCRIPTION_ID)?,
+      Self::table_info_for(&rtx, SAT_TO_SATPOINT)?,
+      Self::multimap_table_info_for(&rtx, SCRIPT_PUBKEY_TO_OUTPOINT)?,
+      Self::table_info_for(&rtx, STATISTIC_TO_COUNT)?,
+      Self::table_info_for(&rtx, WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?,
+    ];
+
+    Ok(tables.into_iter().flatten().collect())
+  }
+
+  fn table_info_for<K: RedbKey + 'static, V: RedbValue + 'static>(
+    rtx: &ReadTransaction,
+    definition: TableDefinition<K, V>,
+  ) -> Result<Option<TableInfo>> {
+    let table = match rtx.open_table(definition) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut info = TableInfo {
+      name: definition.name().to_lowercase(),
+      rows: 0,
+      key_bytes: 0,
+      value_bytes: 0,
+    };
+
+    for result in table.iter()? {
+      let (key, value) = result?;
+      info.rows += 1;
+      info.key_bytes += u64::try_from(K::as_bytes(&key.value()).as_ref().len())?;
+      info.value_bytes += u64::try_from(V::as_bytes(&value.value()).as_ref().len())?;
+    }
+
+    Ok(Some(info))
+  }
+
+  fn multimap_table_info_for<K: RedbKey + 'static, V: RedbKey + 'static>(
+    rtx: &ReadTransaction,
+    definition: MultimapTableDefinition<K, V>,
+  ) -> Result<Option<TableInfo>> {
+    let table = match rtx.open_multimap_table(definition) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut info = TableInfo {
+      name: definition.name().to_lowercase(),
+      rows: 0,
+      key_bytes: 0,
+      value_bytes: 0,
+    };
+
+    for result in table.iter()? {
+      let (key, values) = result?;
+      let key_bytes = u64::try_from(K::as_bytes(&key.value()).as_ref().len())?;
+      for value in values {
+        info.rows += 1;
+        info.key_bytes += key_bytes;
+        info.value_bytes += u64::try_from(V::as_bytes(&value?.value()).as_ref().len())?;
+      }
+    }
+
+    Ok(Some(info))
+  }
+
+  pub(crate) fn compact(&mut self) -> Result<bool> {
+    Ok(self.database.compact()?)
+  }
+
+  pub(crate) fn verify(&self) -> Result<Vec<Discrepancy>> {
+    let rtx = self.begin_read()?;
+
+    let id_to_entry = rtx.0.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+    let id_to_satpoint = rtx.0.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+    let number_to_id = rtx.0.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+    let sat_to_id = rtx.0.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
+    let satpoint_to_id = rtx.0.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+
+    let mut discrepancies = Vec::new();
+
+    for result in id_to_satpoint.iter()? {
+      let (id, satpoint) = result?;
+      let inscription_id = InscriptionId::load(*id.value());
+      let satpoint = SatPoint::load(*satpoint.value());
+
+      if !satpoint_to_id.get(&satpoint.store())?.any(|result| {
+        result.map(|id| InscriptionId::load(*id.value())).ok() == Some(inscription_id)
+      }) {
+        discrepancies.push(Discrepancy::SatpointMissingInscription {
+          satpoint,
+          inscription_id,
+        });
+      }
+
+      if id_to_entry.get(&inscription_id.store())?.is_none() {
+        discrepancies.push(Discrepancy::InscriptionMissingEntry { inscription_id });
+      }
+    }
+
+    for result in satpoint_to_id.iter()? {
+      let (satpoint, ids) = result?;
+      let satpoint = SatPoint::load(*satpoint.value());
+      for id in ids {
+        let inscription_id = InscriptionId::load(*id?.value());
+        let actual = id_to_satpoint
+          .get(&inscription_id.store())?
+          .map(|satpoint| SatPoint::load(*satpoint.value()));
+        if actual != Some(satpoint) {
+          discrepancies.push(Discrepancy::InscriptionMissingSatpoint {
+            inscription_id,
+            satpoint,
+          });
+        }
+      }
+    }
+
+    for result in number_to_id.iter()? {
+      let (number, id) = result?;
+      let number = number.value();
+      let inscription_id = InscriptionId::load(*id.value());
+      let entry_number = id_to_entry
+        .get(&inscription_id.store())?
+        .map(|entry| InscriptionEntry::load(entry.value()).number);
+      if entry_number != Some(number) {
+        discrepancies.push(Discrepancy::NumberMismatch {
+          number,
+          inscription_id,
+          entry_number,
+        });
+      }
+    }
+
+    for result in id_to_entry.iter()? {
+      let (id, entry) = result?;
+      let inscription_id = InscriptionId::load(*id.value());
+      let entry = InscriptionEntry::load(entry.value());
+
+      if number_to_id
+        .get(&entry.number)?
+        .map(|id| InscriptionId::load(*id.value()))
+        != Some(inscription_id)
+      {
+        discrepancies.push(Discrepancy::EntryMissingNumber {
+          inscription_id,
+          number: entry.number,
+        });
+      }
+
+      if let Some(sat) = entry.sat {
+        if !sat_to_id.get(&sat.n())?.any(|result| {
+          result.map(|id| InscriptionId::load(*id.value())).ok() == Some(inscription_id)
+        }) {
+          discrepancies.push(Discrepancy::EntryMissingSat {
+            inscription_id,
+            sat,
+          });
+        }
+      }
+    }
+
+    for result in sat_to_id.iter()? {
+      let (sat, ids) = result?;
+      let sat = Sat(sat.value());
+      for id in ids {
+        let inscription_id = InscriptionId::load(*id?.value());
+        let entry_sat = id_to_entry
+          .get(&inscription_id.store())?
+          .and_then(|entry| InscriptionEntry::load(entry.value()).sat);
+        if entry_sat != Some(sat) {
+          discrepancies.push(Discrepancy::SatMismatch {
+            sat,
+            inscription_id,
+            entry_sat,
+          });
+        }
+      }
+    }
+
+    if self.has_sat_index()? {
+      let mut ranges = Vec::new();
+
+      for result in rtx.0.open_table(OUTPOINT_TO_SAT_RANGES)?.iter()? {
+        let (outpoint, sat_ranges) = result?;
+        let outpoint = OutPoint::load(*outpoint.value());
+        for chunk in sat_ranges.value().chunks_exact(11) {
+          let (start, end) = SatRange::load(chunk.try_into().unwrap());
+          ranges.push((start, end, outpoint));
+        }
+      }
+
+      ranges.sort_unstable_by_key(|(start, end, _outpoint)| (*start, *end));
+
+      let mut actual = 0;
+      let mut previous: Option<(u64, OutPoint)> = None;
+      for (start, end, outpoint) in ranges {
+        if !is_tracked_sat_range(&(start, end)) {
+          actual += end - start;
+          continue;
+        }
+
+        if let Some((previous_end, previous_outpoint)) = previous {
+          if start < previous_end {
+            discrepancies.push(Discrepancy::OverlappingSatRanges {
+              first: previous_outpoint,
+              second: outpoint,
+              start,
+              end: previous_end.min(end),
+            });
+          }
+        }
+        actual += end - start;
+        match previous {
+          Some((previous_end, _)) if end <= previous_end => {}
+          _ => previous = Some((end, outpoint)),
+        }
+      }
+
+      let expected = Height(rtx.block_count()?).starting_sat().n();
+
+      if actual != expected {
+        discrepancies.push(Discrepancy::SupplyMismatch { expected, actual });
+      }
+    }
+
+    if self.has_address_index()? {
+      let outpoint_to_txout = rtx.0.open_table(OUTPOINT_TO_TXOUT)?;
+      let script_pubkey_to_outpoint = rtx.0.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+
+      for result in outpoint_to_txout.iter()? {
+        let (outpoint, tx_out) = result?;
+        let outpoint = OutPoint::load(*outpoint.value());
+        let script_pubkey = consensus::deserialize::<TxOut>(tx_out.value())?.script_pubkey;
+
+        if !script_pubkey_to_outpoint
+          .get(script_pubkey.as_bytes())?
+          .any(|result| {
+            result
+              .map(|outpoint| OutPoint::load(*outpoint.value()))
+              .ok()
+              == Some(outpoint)
+          })
+        {
+          discrepancies.push(Discrepancy::ScriptPubkeyMissingOutpoint {
+            script_pubkey,
+            outpoint,
+          });
+        }
+      }
+
+      for result in script_pubkey_to_outpoint.iter()? {
+        let (script_pubkey, outpoints) = result?;
+        let script_pubkey = ScriptBuf::from_bytes(script_pubkey.value().to_vec());
+        for outpoint in outpoints {
+          let outpoint = OutPoint::load(*outpoint?.value());
+          let actual = outpoint_to_txout
+            .get(&outpoint.store())?
+            .map(|tx_out| consensus::deserialize::<TxOut>(tx_out.value()))
+            .transpose()?
+            .map(|tx_out| tx_out.script_pubkey);
+          if actual.as_ref() != Some(&script_pubkey) {
+            discrepancies.push(Discrepancy::OutpointMissingTxOut {
+              outpoint,
+              script_pubkey: script_pubkey.clone(),
+            });
+          }
+        }
+      }
+    }
+
+    Ok(discrepancies)
+  }
+
+  pub(crate) fn metrics(&self) -> Result<Metrics> {
+    let rtx = self.begin_read()?;
+
+    let statistics = {
+      let statistic_to_count = rtx.0.open_table(STATISTIC_TO_COUNT)?;
+      let mut statistics = Vec::new();
+      for statistic in Statistic::ALL {
+        statistics.push((
+          statistic,
+          statistic_to_count
+            .get(&statistic.key())?
+            .map(|x| x.value())
+            .unwrap_or(0),
+        ));
+      }
+      statistics
+    };
+
+    let throughput = self.throughput.lock().unwrap();
+
+    Ok(Metrics {
+      blocks_indexed: rtx.block_count()?,
+      blocks_indexed_total: throughput.blocks_indexed,
+      blocks_per_second: throughput.blocks_per_second,
+      chain_blocks: self.client.get_block_count()? + 1,
+      index_file_size: fs::metadata(&self.path)?.len(),
+      last_commit_duration: throughput.last_commit_duration,
+      statistics,
+    })
+  }
+
+  fn record_commit(&self, duration: Duration) {
+    self.throughput.lock().unwrap().last_commit_duration = duration;
+  }
+
+  fn record_update(&self, blocks: u64, elapsed: Duration) {
+    if blocks == 0 {
+      return;
+    }
+
+    let mut throughput = self.throughput.lock().unwrap();
+    throughput.blocks_indexed += blocks;
+    throughput.blocks_per_second = blocks as f64 / elapsed.as_secs_f64();
+  }
+
   pub(crate) fn update(&self) -> Result {
     Updater::update(self)
   }
@@ -655,18 +1261,72 @@ impl Index {
     Ok(ids)
   }
 
-  pub(crate) fn get_inscription_id_by_inscription_number(
+  pub(crate) fn get_children_by_inscription_id(
     &self,
-    n: i64,
-  ) -> Result<Option<InscriptionId>> {
-    Ok(
-      self
-        .database
-        .begin_read()?
-        .open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?
-        .get(&n)?
-        .map(|id| Entry::load(*id.value())),
-    )
+    inscription_id: InscriptionId,
+  ) -> Result<Vec<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let id_to_entry = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+
+    let mut children = rtx
+      .open_multimap_table(PARENT_TO_CHILDREN)?
+      .get(&inscription_id.store())?
+      .map(|result| {
+        let child = InscriptionId::load(*result?.value());
+        let number = id_to_entry
+          .get(&child.store())?
+          .map(|entry| InscriptionEntry::load(entry.value()).number);
+        Ok((number, child))
+      })
+      .collect::<Result<Vec<(Option<i64>, InscriptionId)>, StorageError>>()?;
+
+    children.sort_by_key(|(number, _child)| *number);
+
+    Ok(children.into_iter().map(|(_number, child)| child).collect())
+  }
+
+  /// Get up to `n` inscriptions with `metaprotocol`, newest first, starting
+  /// at number `from`, along with the number the next, older page starts at.
+  pub(crate) fn get_inscriptions_by_metaprotocol(
+    &self,
+    metaprotocol: &str,
+    n: usize,
+    from: Option<i64>,
+  ) -> Result<(Vec<InscriptionId>, Option<i64>)> {
+    let mut inscriptions = Vec::new();
+
+    for result in self
+      .database
+      .begin_read()?
+      .open_table(METAPROTOCOL_AND_NUMBER_TO_INSCRIPTION_ID)?
+      .range((metaprotocol, i64::MIN)..=(metaprotocol, from.unwrap_or(i64::MAX)))?
+      .rev()
+    {
+      let (key, id) = result?;
+
+      if inscriptions.len() == n {
+        return Ok((inscriptions, Some(key.value().1)));
+      }
+
+      inscriptions.push(InscriptionId::load(*id.value()));
+    }
+
+    Ok((inscriptions, None))
+  }
+
+  pub(crate) fn get_inscription_id_by_inscription_number(
+    &self,
+    n: i64,
+  ) -> Result<Option<InscriptionId>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?
+        .get(&n)?
+        .map(|id| Entry::load(*id.value())),
+    )
   }
 
   pub(crate) fn get_inscription_satpoint_by_id(
@@ -683,6 +1343,54 @@ impl Index {
     )
   }
 
+  pub(crate) fn get_inscription_satpoint_at_height(
+    &self,
+    inscription_id: InscriptionId,
+    height: u64,
+  ) -> Result<Option<SatPoint>> {
+    let rtx = self.begin_read()?;
+
+    let block_count = rtx.block_count()?;
+    if height >= block_count {
+      bail!(
+        "height {height} is above index height {}",
+        block_count.saturating_sub(1)
+      );
+    }
+
+    let start = (inscription_id, 0).store();
+    let end = (inscription_id, height).store();
+
+    let satpoint = rtx
+      .0
+      .open_table(INSCRIPTION_ID_AND_HEIGHT_TO_SATPOINT)?
+      .range::<&InscriptionIdHeightValue>(&start..=&end)?
+      .next_back()
+      .transpose()?
+      .map(|(_key, satpoint)| Entry::load(*satpoint.value()));
+
+    Ok(satpoint)
+  }
+
+  pub(crate) fn get_output_address(&self, outpoint: OutPoint) -> Result<Option<Address>> {
+    if outpoint == OutPoint::null() || outpoint == unbound_outpoint() {
+      return Ok(None);
+    }
+
+    Ok(
+      self
+        .get_transaction(outpoint.txid)?
+        .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.try_into().unwrap()))
+        .and_then(|output| {
+          self
+            .options
+            .chain()
+            .address_from_script(&output.script_pubkey)
+            .ok()
+        }),
+    )
+  }
+
   pub(crate) fn get_inscription_by_id(
     &self,
     inscription_id: InscriptionId,
@@ -764,7 +1472,7 @@ impl Index {
   }
 
   pub(crate) fn find(&self, sat: u64) -> Result<Option<SatPoint>> {
-    self.require_sat_index("find")?;
+    let mode = self.require_sat_index("find")?;
 
     let rtx = self.begin_read()?;
 
@@ -789,6 +1497,13 @@ impl Index {
       }
     }
 
+    if let SatIndexMode::Selective(min_rarity) = mode {
+      let rarity = Sat(sat).rarity();
+      if rarity < min_rarity {
+        bail!("{rarity} sat {sat} is not tracked by the index, {mode}");
+      }
+    }
+
     Ok(None)
   }
 
@@ -1102,6 +1817,7 @@ impl Index {
 mod tests {
   use {
     super::*,
+    crate::inscription::Curse,
     bitcoin::secp256k1::rand::{self, RngCore},
   };
 
@@ -2964,15 +3680,23 @@ mod tests {
         Some(100 * COIN_VALUE),
       );
 
-      assert_eq!(
-        context
-          .index
-          .get_inscription_entry(reinscription_on_cursed)
-          .unwrap()
-          .unwrap()
-          .number,
-        1
-      );
+      let entry = context
+        .index
+        .get_inscription_entry(reinscription_on_cursed)
+        .unwrap()
+        .unwrap();
+
+      assert_eq!(entry.number, 1);
+      assert_eq!(entry.curse, Some(Curse::Reinscription));
+
+      let output = crate::subcommand::decode::decode(
+        &context.index.get_transaction(txid).unwrap().unwrap(),
+        Some(&context.index),
+      )
+      .unwrap();
+
+      assert_eq!(output.envelopes[0].number, Some(1));
+      assert_eq!(output.envelopes[0].curse, Some("reinscription".into()));
     }
   }
 
@@ -3246,4 +3970,891 @@ mod tests {
       )
     }
   }
+
+  #[test]
+  fn verify_finds_no_discrepancies_in_consistent_index() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      assert_eq!(context.index.verify().unwrap(), Vec::new());
+    }
+  }
+
+  #[test]
+  fn verify_finds_diverged_satpoint_tables() {
+    let context = Context::builder().build();
+    context.mine_blocks(1);
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain", "hello").to_witness(),
+      ..Default::default()
+    });
+    let inscription_id = InscriptionId::from(txid);
+
+    context.mine_blocks(1);
+
+    let satpoint = SatPoint {
+      outpoint: OutPoint { txid, vout: 0 },
+      offset: 0,
+    };
+
+    let wtx = context.index.database.begin_write().unwrap();
+    wtx
+      .open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)
+      .unwrap()
+      .remove_all(&satpoint.store())
+      .unwrap();
+    wtx.commit().unwrap();
+
+    assert_eq!(
+      context.index.verify().unwrap(),
+      vec![Discrepancy::SatpointMissingInscription {
+        satpoint,
+        inscription_id,
+      }]
+    );
+  }
+
+  #[test]
+  fn verify_finds_diverged_address_tables() {
+    let context = Context::builder().arg("--index-addresses").build();
+    let txid = context.mine_blocks(1)[0].txdata[0].txid();
+
+    assert_eq!(context.index.verify().unwrap(), Vec::new());
+
+    let outpoint = OutPoint { txid, vout: 0 };
+
+    let wtx = context.index.database.begin_write().unwrap();
+    let script_pubkey = consensus::deserialize::<TxOut>(
+      wtx
+        .open_table(OUTPOINT_TO_TXOUT)
+        .unwrap()
+        .get(&outpoint.store())
+        .unwrap()
+        .unwrap()
+        .value(),
+    )
+    .unwrap()
+    .script_pubkey;
+    wtx
+      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)
+      .unwrap()
+      .remove(script_pubkey.as_bytes(), &outpoint.store())
+      .unwrap();
+    wtx.commit().unwrap();
+
+    assert_eq!(
+      context.index.verify().unwrap(),
+      vec![Discrepancy::ScriptPubkeyMissingOutpoint {
+        script_pubkey,
+        outpoint,
+      }]
+    );
+  }
+
+  #[test]
+  fn table_info_counts_rows_and_bytes() {
+    let context = Context::builder().arg("--index-sats").build();
+    context.mine_blocks(1);
+
+    let tables = context.index.table_info().unwrap();
+
+    assert_eq!(
+      tables
+        .iter()
+        .find(|table| table.name == "height_to_block_hash")
+        .unwrap(),
+      &TableInfo {
+        name: "height_to_block_hash".into(),
+        rows: 2,
+        key_bytes: 16,
+        value_bytes: 64,
+      }
+    );
+
+    assert!(tables
+      .iter()
+      .any(|table| table.name == "outpoint_to_sat_ranges" && table.rows == 3));
+  }
+
+  #[test]
+  fn table_info_includes_address_index_tables() {
+    let context = Context::builder().arg("--index-addresses").build();
+    context.mine_blocks(1);
+
+    let tables = context.index.table_info().unwrap();
+
+    let rows = |name: &str| tables.iter().find(|table| table.name == name).unwrap().rows;
+
+    assert_eq!(rows("outpoint_to_txout"), 2);
+    assert_eq!(rows("script_pubkey_to_outpoint"), 2);
+  }
+
+  #[test]
+  fn table_info_skips_sat_ranges_without_sat_index() {
+    let context = Context::builder().build();
+    context.mine_blocks(1);
+
+    assert!(!context
+      .index
+      .table_info()
+      .unwrap()
+      .iter()
+      .any(|table| table.name == "outpoint_to_sat_ranges"));
+  }
+
+  #[test]
+  fn compact_preserves_index() {
+    let mut context = Context::builder().build();
+    context.mine_blocks(1);
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain", "hello").to_witness(),
+      ..Default::default()
+    });
+    let inscription_id = InscriptionId::from(txid);
+
+    context.mine_blocks(1);
+
+    context.index.compact().unwrap();
+
+    assert_eq!(
+      context
+        .index
+        .get_inscription_entry(inscription_id)
+        .unwrap()
+        .unwrap()
+        .number,
+      0
+    );
+  }
+
+  #[test]
+  fn selective_sat_index_collapses_untracked_ranges() {
+    let context = Context::builder()
+      .args(["--index-sats", "--index-sats-min-rarity", "uncommon"])
+      .build();
+
+    assert_eq!(
+      context.index.sat_index_mode().unwrap(),
+      SatIndexMode::Selective(Rarity::Uncommon)
+    );
+
+    context.mine_blocks(1);
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      outputs: 2,
+      fee: 0,
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
+      List::Unspent(vec![(50 * COIN_VALUE, 75 * COIN_VALUE)])
+    );
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 1)).unwrap().unwrap(),
+      List::Unspent(vec![(Sat::SUPPLY, Sat::SUPPLY + 25 * COIN_VALUE)])
+    );
+
+    assert_eq!(
+      context.index.find(50 * COIN_VALUE).unwrap().unwrap(),
+      SatPoint {
+        outpoint: OutPoint::new(txid, 0),
+        offset: 0,
+      }
+    );
+
+    assert_eq!(
+      context.index.find(75 * COIN_VALUE).unwrap_err().to_string(),
+      "common sat 7500000000 is not tracked by the index, only sats of rarity uncommon or higher are indexed"
+    );
+
+    assert_eq!(context.index.verify().unwrap(), Vec::new());
+  }
+
+  #[test]
+  fn selective_sat_index_only_knows_sats_inscribed_in_tracked_outputs() {
+    let context = Context::builder()
+      .args(["--index-sats", "--index-sats-min-rarity", "uncommon"])
+      .build();
+
+    context.mine_blocks(2);
+
+    let tracked = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain", "foo")
+        .with_pointer(1)
+        .to_witness(),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    let split = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0)],
+      outputs: 2,
+      fee: 0,
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    let untracked = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(4, 1, 1)],
+      witness: inscription("text/plain", "bar").to_witness(),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    // the common sat is inscribed in an output that also holds an uncommon sat
+    assert_eq!(
+      context
+        .index
+        .get_inscription_entry(InscriptionId::from(tracked))
+        .unwrap()
+        .unwrap()
+        .sat,
+      Some(Sat(50 * COIN_VALUE + 1))
+    );
+
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint::new(split, 1))
+        .unwrap()
+        .unwrap(),
+      List::Spent,
+    );
+
+    // the ranges of the split's second output were already collapsed
+    assert_eq!(
+      context
+        .index
+        .get_inscription_entry(InscriptionId::from(untracked))
+        .unwrap()
+        .unwrap()
+        .sat,
+      None
+    );
+  }
+
+  #[test]
+  fn selective_sat_index_splits_large_untracked_ranges() {
+    let context = Context::builder()
+      .args(["--index-sats", "--index-sats-min-rarity", "uncommon"])
+      .build();
+
+    context.mine_blocks(2);
+
+    let split = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0), (2, 0, 0)],
+      outputs: 4,
+      output_values: &[1, 50 * COIN_VALUE - 1, 1, 50 * COIN_VALUE - 1],
+      fee: 0,
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint::new(split, 0))
+        .unwrap()
+        .unwrap(),
+      List::Unspent(vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 1)])
+    );
+
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint::new(split, 1))
+        .unwrap()
+        .unwrap(),
+      List::Unspent(vec![(Sat::SUPPLY, Sat::SUPPLY + 50 * COIN_VALUE - 1)])
+    );
+
+    let merged = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 1), (3, 1, 3)],
+      fee: 0,
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint::new(merged, 0))
+        .unwrap()
+        .unwrap(),
+      List::Unspent(vec![
+        (Sat::SUPPLY, Sat::SUPPLY + (1 << 33) - 1),
+        (
+          Sat::SUPPLY,
+          Sat::SUPPLY + 100 * COIN_VALUE - 2 - ((1 << 33) - 1)
+        ),
+      ])
+    );
+
+    assert_eq!(context.index.verify().unwrap(), Vec::new());
+  }
+
+  #[test]
+  fn full_sat_index_mode() {
+    assert_eq!(
+      Context::builder()
+        .arg("--index-sats")
+        .build()
+        .index
+        .sat_index_mode()
+        .unwrap(),
+      SatIndexMode::Full
+    );
+
+    assert_eq!(
+      Context::builder().build().index.sat_index_mode().unwrap(),
+      SatIndexMode::Disabled
+    );
+  }
+
+  #[test]
+  fn require_sat_index_explains_mode() {
+    let context = Context::builder().build();
+
+    assert_eq!(
+      context.index.find(0).unwrap_err().to_string(),
+      "find requires index created with `--index-sats` flag, but sats are not indexed"
+    );
+  }
+
+  #[test]
+  fn selective_sat_index_min_rarity_must_match_existing_index() {
+    let tempdir = Context::builder().arg("--index-sats").build().tempdir;
+
+    let path = tempdir.path().to_owned();
+
+    let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+    assert_eq!(
+      Context::builder()
+        .args(["--index-sats", "--index-sats-min-rarity", "rare"])
+        .tempdir(tempdir)
+        .try_build()
+        .err()
+        .unwrap()
+        .to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` was not created with `--index-sats-min-rarity rare`, consider deleting and rebuilding the index", path.display())
+    );
+  }
+
+  #[test]
+  fn address_index_must_match_existing_index() {
+    let tempdir = Context::builder().build().tempdir;
+
+    let path = tempdir.path().to_owned();
+
+    let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+    assert_eq!(
+      Context::builder()
+        .arg("--index-addresses")
+        .tempdir(tempdir)
+        .try_build()
+        .err()
+        .unwrap()
+        .to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` was not created with `--index-addresses`, consider deleting and rebuilding the index", path.display())
+    );
+  }
+
+  #[test]
+  fn watch_only_wallet_requires_address_index() {
+    let context = Context::builder()
+      .args(["--watch-address", WATCHED])
+      .build();
+
+    assert_eq!(
+      context
+        .index
+        .get_unspent_outputs(Wallet::load(&context.options).unwrap())
+        .unwrap_err()
+        .to_string(),
+      "watch-only wallet requires index created with `--index-addresses` flag",
+    );
+  }
+
+  const WATCHED: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+  #[test]
+  fn address_index_tracks_unspent_outputs_of_watched_addresses() {
+    let context = Context::builder()
+      .args(["--index-addresses", "--watch-address", WATCHED])
+      .build();
+
+    context.mine_blocks(1);
+
+    let recipient = WATCHED
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked();
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      outputs: 2,
+      recipient: Some(recipient),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context
+        .index
+        .get_unspent_outputs(Wallet::load(&context.options).unwrap())
+        .unwrap(),
+      [
+        (OutPoint { txid, vout: 0 }, Amount::from_sat(25 * COIN_VALUE)),
+        (OutPoint { txid, vout: 1 }, Amount::from_sat(25 * COIN_VALUE)),
+      ]
+      .into_iter()
+      .collect(),
+    );
+
+    context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0)],
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context
+        .index
+        .get_unspent_outputs(Wallet::load(&context.options).unwrap())
+        .unwrap(),
+      [(OutPoint { txid, vout: 1 }, Amount::from_sat(25 * COIN_VALUE))]
+        .into_iter()
+        .collect(),
+    );
+  }
+
+  #[test]
+  fn child_is_recorded_if_parent_is_spent() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let parent_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "parent").to_witness(),
+        ..Default::default()
+      });
+      let parent = InscriptionId::from(parent_txid);
+
+      context.mine_blocks(1);
+
+      let child_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0), (2, 1, 0)],
+        witness: inscription("text/plain", "child")
+          .with_parent(parent)
+          .to_witness(),
+        ..Default::default()
+      });
+      let child = InscriptionId::from(child_txid);
+
+      context.mine_blocks(1);
+
+      assert!(context
+        .index
+        .get_children_by_inscription_id(parent)
+        .unwrap()
+        .contains(&child));
+    }
+  }
+
+  #[test]
+  fn child_is_not_recorded_if_parent_is_not_spent() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let parent_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "parent").to_witness(),
+        ..Default::default()
+      });
+      let parent = InscriptionId::from(parent_txid);
+
+      context.mine_blocks(1);
+
+      context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0)],
+        witness: inscription("text/plain", "child")
+          .with_parent(parent)
+          .to_witness(),
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      assert_eq!(
+        context
+          .index
+          .get_children_by_inscription_id(parent)
+          .unwrap(),
+        Vec::new()
+      );
+    }
+  }
+
+  #[test]
+  fn inscriptions_are_indexed_by_metaprotocol() {
+    for context in Context::configurations() {
+      context.mine_blocks(2);
+
+      let first = InscriptionId::from(
+        context.rpc_server.broadcast_tx(TransactionTemplate {
+          inputs: &[(1, 0, 0)],
+          witness: inscription("text/plain", "first")
+            .with_metaprotocol("foo")
+            .to_witness(),
+          ..Default::default()
+        }),
+      );
+
+      context.mine_blocks(1);
+
+      let second = InscriptionId::from(
+        context.rpc_server.broadcast_tx(TransactionTemplate {
+          inputs: &[(2, 0, 0)],
+          witness: inscription("text/plain", "second")
+            .with_metaprotocol("foo")
+            .to_witness(),
+          ..Default::default()
+        }),
+      );
+
+      context.mine_blocks(1);
+
+      assert_eq!(
+        context
+          .index
+          .get_inscriptions_by_metaprotocol("foo", 100, None)
+          .unwrap(),
+        (vec![second, first], None)
+      );
+
+      let (page, next) = context
+        .index
+        .get_inscriptions_by_metaprotocol("foo", 1, None)
+        .unwrap();
+
+      assert_eq!(page, [second]);
+
+      assert_eq!(
+        context
+          .index
+          .get_inscriptions_by_metaprotocol("foo", 1, next)
+          .unwrap(),
+        (vec![first], None)
+      );
+
+      assert_eq!(
+        context
+          .index
+          .get_inscriptions_by_metaprotocol("bar", 100, None)
+          .unwrap(),
+        (Vec::new(), None)
+      );
+    }
+  }
+
+  #[test]
+  fn pointer_directs_inscription_to_output() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        outputs: 2,
+        witness: inscription("text/plain", "hello")
+          .with_pointer(25 * COIN_VALUE + 1)
+          .to_witness(),
+        ..Default::default()
+      });
+      let inscription_id = InscriptionId::from(txid);
+
+      context.mine_blocks(1);
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_by_id(inscription_id)
+          .unwrap(),
+        Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 1 },
+          offset: 1,
+        })
+      );
+    }
+  }
+
+  #[test]
+  fn pointer_beyond_output_value_is_ignored() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "hello")
+          .with_pointer(50 * COIN_VALUE)
+          .to_witness(),
+        ..Default::default()
+      });
+      let inscription_id = InscriptionId::from(txid);
+
+      context.mine_blocks(1);
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_by_id(inscription_id)
+          .unwrap(),
+        Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        })
+      );
+    }
+  }
+
+  #[test]
+  fn batch_inscriptions_with_pointers_are_bound_to_their_outputs() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let mut witness = Witness::new();
+      witness.push(Inscription::append_batch_reveal_script(
+        &[
+          inscription("text/plain", "foo"),
+          inscription("text/plain", "bar").with_pointer(25 * COIN_VALUE),
+        ],
+        script::Builder::new(),
+      ));
+      witness.push([]);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        outputs: 2,
+        witness,
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      for vout in 0..2 {
+        assert_eq!(
+          context
+            .index
+            .get_inscription_satpoint_by_id(InscriptionId { txid, index: vout })
+            .unwrap(),
+          Some(SatPoint {
+            outpoint: OutPoint { txid, vout },
+            offset: 0,
+          })
+        );
+
+        assert_eq!(
+          context
+            .index
+            .get_inscription_entry(InscriptionId { txid, index: vout })
+            .unwrap()
+            .unwrap()
+            .number,
+          -i64::from(vout),
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn batch_inscriptions_with_pointers_to_same_offset_share_sat() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let mut witness = Witness::new();
+      witness.push(Inscription::append_batch_reveal_script(
+        &[
+          inscription("text/plain", "foo"),
+          inscription("text/plain", "bar").with_pointer(0),
+        ],
+        script::Builder::new(),
+      ));
+      witness.push([]);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness,
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      let satpoint = SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      };
+
+      assert_eq!(
+        context
+          .index
+          .get_inscriptions_on_output_with_satpoints(satpoint.outpoint)
+          .unwrap(),
+        [
+          (satpoint, InscriptionId { txid, index: 0 }),
+          (satpoint, InscriptionId { txid, index: 1 }),
+        ]
+      );
+
+      for (index, number) in [(0, 0), (1, -1)] {
+        assert_eq!(
+          context
+            .index
+            .get_inscription_entry(InscriptionId { txid, index })
+            .unwrap()
+            .unwrap()
+            .number,
+          number,
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn pointer_to_inscribed_sat_of_later_input_is_reinscription() {
+    for context in Context::configurations() {
+      context.mine_blocks(2);
+
+      let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "foo").to_witness(),
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0), (3, 1, 0)],
+        witness: inscription("text/plain", "bar")
+          .with_pointer(50 * COIN_VALUE)
+          .to_witness(),
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      let reinscription = InscriptionId { txid, index: 0 };
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_by_id(reinscription)
+          .unwrap(),
+        context
+          .index
+          .get_inscription_satpoint_by_id(InscriptionId::from(first))
+          .unwrap(),
+      );
+
+      assert!(
+        context
+          .index
+          .get_inscription_entry(reinscription)
+          .unwrap()
+          .unwrap()
+          .number
+          < 0
+      );
+    }
+  }
+
+  #[test]
+  fn inscription_satpoint_at_height() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+      let inscription_id = InscriptionId::from(txid);
+
+      context.mine_blocks(1);
+
+      let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 1, 0)],
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_at_height(inscription_id, 1)
+          .unwrap(),
+        None
+      );
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_at_height(inscription_id, 2)
+          .unwrap(),
+        Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        })
+      );
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_at_height(inscription_id, 3)
+          .unwrap(),
+        Some(SatPoint {
+          outpoint: OutPoint {
+            txid: send_txid,
+            vout: 0,
+          },
+          offset: 0,
+        })
+      );
+
+      assert_eq!(
+        context
+          .index
+          .get_inscription_satpoint_at_height(inscription_id, 4)
+          .unwrap_err()
+          .to_string(),
+        "height 4 is above index height 3"
+      );
+    }
+  }
 }
@@ -1,4 +1,4 @@
-use super::*;
+use {super::*, crate::inscription::Curse};
 
 pub(super) trait Entry: Sized {
   type Value;
@@ -24,6 +24,7 @@ impl Entry for BlockHash {
 
 #[derive(Debug)]
 pub(crate) struct InscriptionEntry {
+  pub(crate) curse: Option<Curse>,
   pub(crate) fee: u64,
   pub(crate) height: u64,
   pub(crate) number: i64,
@@ -31,13 +32,20 @@ pub(crate) struct InscriptionEntry {
   pub(crate) timestamp: u32,
 }
 
-pub(crate) type InscriptionEntryValue = (u64, u64, i64, u64, u32);
+pub(crate) type InscriptionEntryValue = (u8, u64, u64, i64, u64, u32);
 
 impl Entry for InscriptionEntry {
   type Value = InscriptionEntryValue;
 
-  fn load((fee, height, number, sat, timestamp): InscriptionEntryValue) -> Self {
+  fn load((curse, fee, height, number, sat, timestamp): InscriptionEntryValue) -> Self {
     Self {
+      curse: match curse {
+        0 => None,
+        1 => Some(Curse::NotInFirstInput),
+        2 => Some(Curse::NotAtOffsetZero),
+        3 => Some(Curse::Reinscription),
+        n => panic!("invalid curse: {n}"),
+      },
       fee,
       height,
       number,
@@ -52,6 +60,12 @@ impl Entry for InscriptionEntry {
 
   fn store(self) -> Self::Value {
     (
+      match self.curse {
+        None => 0,
+        Some(Curse::NotInFirstInput) => 1,
+        Some(Curse::NotAtOffsetZero) => 2,
+        Some(Curse::Reinscription) => 3,
+      },
       self.fee,
       self.height,
       self.number,
@@ -86,6 +100,28 @@ impl Entry for InscriptionId {
   }
 }
 
+pub(super) type InscriptionIdHeightValue = [u8; 44];
+
+impl Entry for (InscriptionId, u64) {
+  type Value = InscriptionIdHeightValue;
+
+  fn load(value: Self::Value) -> Self {
+    let (inscription_id, height) = value.split_at(36);
+    (
+      InscriptionId::load(inscription_id.try_into().unwrap()),
+      u64::from_be_bytes(height.try_into().unwrap()),
+    )
+  }
+
+  fn store(self) -> Self::Value {
+    let mut value = [0; 44];
+    let (inscription_id, height) = value.split_at_mut(36);
+    inscription_id.copy_from_slice(&self.0.store());
+    height.copy_from_slice(&self.1.to_be_bytes());
+    value
+  }
+}
+
 pub(super) type OutPointValue = [u8; 36];
 
 impl Entry for OutPoint {
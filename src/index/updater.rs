@@ -8,6 +8,8 @@ use {
 
 mod inscription_updater;
 
+const MAX_SAT_RANGE_SIZE: u64 = (1 << 33) - 1;
+
 struct BlockData {
   header: Header,
   txdata: Vec<(Transaction, Txid)>,
@@ -32,7 +34,9 @@ impl From<Block> for BlockData {
 pub(crate) struct Updater {
   range_cache: HashMap<OutPointValue, Vec<u8>>,
   height: u64,
+  index_addresses: bool,
   index_sats: bool,
+  min_rarity: Option<Rarity>,
   sat_ranges_since_flush: u64,
   outputs_cached: u64,
   outputs_inserted_since_flush: u64,
@@ -64,7 +68,12 @@ impl Updater {
     let mut updater = Self {
       range_cache: HashMap::new(),
       height,
+      index_addresses: index.has_address_index()?,
       index_sats: index.has_sat_index()?,
+      min_rarity: match index.sat_index_mode()? {
+        SatIndexMode::Selective(min_rarity) => Some(min_rarity),
+        SatIndexMode::Disabled | SatIndexMode::Full => None,
+      },
       sat_ranges_since_flush: 0,
       outputs_cached: 0,
       outputs_inserted_since_flush: 0,
@@ -96,10 +105,12 @@ impl Updater {
       Some(progress_bar)
     };
 
-    let rx = Self::fetch_blocks_from(index, self.height, self.index_sats)?;
+    let rx = Self::fetch_blocks_from(index, self.height, self.index_sats || self.index_addresses)?;
 
     let (mut outpoint_sender, mut value_receiver) = Self::spawn_fetcher(index)?;
 
+    let start = Instant::now();
+    let first_height = self.height;
     let mut uncommitted = 0;
     let mut value_cache = HashMap::new();
     while let Ok(block) = rx.recv() {
@@ -127,7 +138,7 @@ impl Updater {
       uncommitted += 1;
 
       if uncommitted == 5000 {
-        self.commit(wtx, value_cache)?;
+        self.commit(index, wtx, value_cache)?;
         value_cache = HashMap::new();
         uncommitted = 0;
         wtx = index.begin_write()?;
@@ -160,9 +171,11 @@ impl Updater {
     }
 
     if uncommitted > 0 {
-      self.commit(wtx, value_cache)?;
+      self.commit(index, wtx, value_cache)?;
     }
 
+    index.record_update(self.height - first_height, start.elapsed());
+
     if let Some(progress_bar) = &mut progress_bar {
       progress_bar.finish_and_clear();
     }
@@ -173,7 +186,7 @@ impl Updater {
   fn fetch_blocks_from(
     index: &Index,
     mut height: u64,
-    index_sats: bool,
+    index_all_blocks: bool,
   ) -> Result<mpsc::Receiver<BlockData>> {
     let (tx, rx) = mpsc::sync_channel(32);
 
@@ -190,7 +203,12 @@ impl Updater {
         }
       }
 
-      match Self::get_block_with_retries(&client, height, index_sats, first_inscription_height) {
+      match Self::get_block_with_retries(
+        &client,
+        height,
+        index_all_blocks,
+        first_inscription_height,
+      ) {
         Ok(Some(block)) => {
           if let Err(err) = tx.send(block.into()) {
             log::info!("Block receiver disconnected: {err}");
@@ -212,7 +230,7 @@ impl Updater {
   fn get_block_with_retries(
     client: &Client,
     height: u64,
-    index_sats: bool,
+    index_all_blocks: bool,
     first_inscription_height: u64,
   ) -> Result<Option<Block>> {
     let mut errors = 0;
@@ -223,7 +241,7 @@ impl Updater {
         .and_then(|option| {
           option
             .map(|hash| {
-              if index_sats || height >= first_inscription_height {
+              if index_all_blocks || height >= first_inscription_height {
                 Ok(client.get_block(&hash)?)
               } else {
                 Ok(Block {
@@ -401,8 +419,13 @@ impl Updater {
     let mut inscription_id_to_inscription_entry =
       wtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
     let mut inscription_id_to_satpoint = wtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+    let mut inscription_id_and_height_to_satpoint =
+      wtx.open_table(INSCRIPTION_ID_AND_HEIGHT_TO_SATPOINT)?;
     let mut inscription_number_to_inscription_id =
       wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut metaprotocol_and_number_to_inscription_id =
+      wtx.open_table(METAPROTOCOL_AND_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut parent_to_children = wtx.open_multimap_table(PARENT_TO_CHILDREN)?;
     let mut reinscription_id_to_seq_num = wtx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
     let mut sat_to_inscription_id = wtx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
     let mut satpoint_to_inscription_id = wtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
@@ -421,11 +444,14 @@ impl Updater {
     let mut inscription_updater = InscriptionUpdater::new(
       self.height,
       &mut inscription_id_to_satpoint,
+      &mut inscription_id_and_height_to_satpoint,
       value_receiver,
       &mut inscription_id_to_inscription_entry,
       lost_sats,
+      &mut metaprotocol_and_number_to_inscription_id,
       &mut inscription_number_to_inscription_id,
       &mut outpoint_to_value,
+      &mut parent_to_children,
       &mut reinscription_id_to_seq_num,
       &mut sat_to_inscription_id,
       &mut satpoint_to_inscription_id,
@@ -505,10 +531,12 @@ impl Updater {
           .map(|ranges| ranges.value().to_vec())
           .unwrap_or_default();
 
-        for (start, end) in coinbase_inputs {
-          if !Sat(start).is_common() {
+        let coinbase_inputs = Vec::from(coinbase_inputs);
+
+        for (start, end) in &coinbase_inputs {
+          if self.is_tracked_sat(start) {
             sat_to_satpoint.insert(
-              &start,
+              start,
               &SatPoint {
                 outpoint: OutPoint::null(),
                 offset: lost_sats,
@@ -517,11 +545,11 @@ impl Updater {
             )?;
           }
 
-          lost_sat_ranges.extend_from_slice(&(start, end).store());
-
           lost_sats += end - start;
         }
 
+        lost_sat_ranges.extend_from_slice(&Self::store_sat_ranges(&coinbase_inputs, true));
+
         outpoint_to_sat_ranges.insert(&OutPoint::null().store(), lost_sat_ranges.as_slice())?;
       }
     } else {
@@ -530,6 +558,20 @@ impl Updater {
       }
     }
 
+    if self.index_addresses {
+      let mut outpoint_to_txout = wtx.open_table(OUTPOINT_TO_TXOUT)?;
+      let mut script_pubkey_to_outpoint = wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+
+      for (tx, txid) in &block.txdata {
+        Self::index_transaction_addresses(
+          tx,
+          *txid,
+          &mut outpoint_to_txout,
+          &mut script_pubkey_to_outpoint,
+        )?;
+      }
+    }
+
     statistic_to_count.insert(&Statistic::LostSats.key(), &inscription_updater.lost_sats)?;
 
     statistic_to_count.insert(
@@ -550,6 +592,47 @@ impl Updater {
     Ok(())
   }
 
+  fn index_transaction_addresses(
+    tx: &Transaction,
+    txid: Txid,
+    outpoint_to_txout: &mut Table<&OutPointValue, &[u8]>,
+    script_pubkey_to_outpoint: &mut MultimapTable<&[u8], &OutPointValue>,
+  ) -> Result {
+    for input in &tx.input {
+      if input.previous_output.is_null() {
+        continue;
+      }
+
+      let key = input.previous_output.store();
+
+      let spent = outpoint_to_txout
+        .remove(&key)?
+        .map(|tx_out| consensus::deserialize::<TxOut>(tx_out.value()))
+        .transpose()?;
+
+      if let Some(spent) = spent {
+        script_pubkey_to_outpoint.remove(spent.script_pubkey.as_bytes(), &key)?;
+      }
+    }
+
+    for (vout, tx_out) in tx.output.iter().enumerate() {
+      if tx_out.script_pubkey.is_op_return() {
+        continue;
+      }
+
+      let key = OutPoint {
+        txid,
+        vout: vout.try_into().unwrap(),
+      }
+      .store();
+
+      outpoint_to_txout.insert(&key, consensus::serialize(tx_out).as_slice())?;
+      script_pubkey_to_outpoint.insert(tx_out.script_pubkey.as_bytes(), &key)?;
+    }
+
+    Ok(())
+  }
+
   fn index_transaction_sats(
     &mut self,
     tx: &Transaction,
@@ -571,6 +654,7 @@ impl Updater {
         txid,
       };
       let mut sats = Vec::new();
+      let mut tracked = self.min_rarity.is_none();
 
       let mut remaining = output.value;
       while remaining > 0 {
@@ -578,7 +662,8 @@ impl Updater {
           .pop_front()
           .ok_or_else(|| anyhow!("insufficient inputs for transaction outputs"))?;
 
-        if !Sat(range.0).is_common() {
+        if self.is_tracked_sat(&range.0) {
+          tracked = true;
           sat_to_satpoint.insert(
             &range.0,
             &SatPoint {
@@ -600,23 +685,76 @@ impl Updater {
           range
         };
 
-        sats.extend_from_slice(&assigned.store());
+        sats.push(assigned);
 
         remaining -= assigned.1 - assigned.0;
 
         *sat_ranges_written += 1;
       }
 
+      if !tracked {
+        tracked = inscription_updater.has_inscriptions(outpoint)?;
+      }
+
       *outputs_traversed += 1;
 
-      self.range_cache.insert(outpoint.store(), sats);
+      self
+        .range_cache
+        .insert(outpoint.store(), Self::store_sat_ranges(&sats, tracked));
       self.outputs_inserted_since_flush += 1;
     }
 
     Ok(())
   }
 
-  fn commit(&mut self, wtx: WriteTransaction, value_cache: HashMap<OutPoint, u64>) -> Result {
+  fn is_tracked_sat(&self, sat: &u64) -> bool {
+    let sat = Sat(*sat);
+
+    if sat.n() >= Sat::SUPPLY || sat.is_common() {
+      return false;
+    }
+
+    match self.min_rarity {
+      Some(min_rarity) => sat.rarity() >= min_rarity,
+      None => true,
+    }
+  }
+
+  fn store_sat_ranges(sat_ranges: &[(u64, u64)], tracked: bool) -> Vec<u8> {
+    let mut sats = Vec::new();
+    let mut untracked = 0;
+
+    for range in sat_ranges {
+      if tracked && is_tracked_sat_range(range) {
+        Self::store_untracked_sats(&mut sats, untracked);
+        untracked = 0;
+        sats.extend_from_slice(&range.store());
+      } else {
+        untracked += range.1 - range.0;
+      }
+    }
+
+    Self::store_untracked_sats(&mut sats, untracked);
+
+    sats
+  }
+
+  fn store_untracked_sats(sats: &mut Vec<u8>, mut count: u64) {
+    while count > 0 {
+      let size = count.min(MAX_SAT_RANGE_SIZE);
+      sats.extend_from_slice(&(Sat::SUPPLY, Sat::SUPPLY + size).store());
+      count -= size;
+    }
+  }
+
+  fn commit(
+    &mut self,
+    index: &Index,
+    wtx: WriteTransaction,
+    value_cache: HashMap<OutPoint, u64>,
+  ) -> Result {
+    let start = Instant::now();
+
     log::info!(
       "Committing at block height {}, {} outputs traversed, {} in map, {} cached",
       self.height,
@@ -657,6 +795,9 @@ impl Updater {
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
 
     wtx.commit()?;
+
+    index.record_commit(start.elapsed());
+
     Ok(())
   }
 }
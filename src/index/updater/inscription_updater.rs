@@ -10,8 +10,11 @@ pub(super) struct Flotsam {
 #[derive(Debug, Clone)]
 enum Origin {
   New {
+    curse: Option<Curse>,
     fee: u64,
     cursed: bool,
+    metaprotocol: Option<String>,
+    parent: Option<InscriptionId>,
     unbound: bool,
   },
   Old {
@@ -23,13 +26,19 @@ pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
   flotsam: Vec<Flotsam>,
   height: u64,
   id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
+  id_and_height_to_satpoint:
+    &'a mut Table<'db, 'tx, &'static InscriptionIdHeightValue, &'static SatPointValue>,
   value_receiver: &'a mut Receiver<u64>,
   id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
   pub(super) lost_sats: u64,
+  metaprotocol_and_number_to_id:
+    &'a mut Table<'db, 'tx, (&'static str, i64), &'static InscriptionIdValue>,
   next_cursed_number: i64,
   next_number: i64,
   number_to_id: &'a mut Table<'db, 'tx, i64, &'static InscriptionIdValue>,
   outpoint_to_value: &'a mut Table<'db, 'tx, &'static OutPointValue, u64>,
+  parent_to_children:
+    &'a mut MultimapTable<'db, 'tx, &'static InscriptionIdValue, &'static InscriptionIdValue>,
   reward: u64,
   reinscription_id_to_seq_num: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, u64>,
   sat_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
@@ -44,11 +53,29 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
   pub(super) fn new(
     height: u64,
     id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
+    id_and_height_to_satpoint: &'a mut Table<
+      'db,
+      'tx,
+      &'static InscriptionIdHeightValue,
+      &'static SatPointValue,
+    >,
     value_receiver: &'a mut Receiver<u64>,
     id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
     lost_sats: u64,
+    metaprotocol_and_number_to_id: &'a mut Table<
+      'db,
+      'tx,
+      (&'static str, i64),
+      &'static InscriptionIdValue,
+    >,
     number_to_id: &'a mut Table<'db, 'tx, i64, &'static InscriptionIdValue>,
     outpoint_to_value: &'a mut Table<'db, 'tx, &'static OutPointValue, u64>,
+    parent_to_children: &'a mut MultimapTable<
+      'db,
+      'tx,
+      &'static InscriptionIdValue,
+      &'static InscriptionIdValue,
+    >,
     reinscription_id_to_seq_num: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, u64>,
     sat_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
     satpoint_to_id: &'a mut MultimapTable<
@@ -79,13 +106,16 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       flotsam: Vec::new(),
       height,
       id_to_satpoint,
+      id_and_height_to_satpoint,
       value_receiver,
       id_to_entry,
       lost_sats,
+      metaprotocol_and_number_to_id,
       next_cursed_number,
       next_number,
       number_to_id,
       outpoint_to_value,
+      parent_to_children,
       reward: Height(height).subsidy(),
       reinscription_id_to_seq_num,
       sat_to_inscription_id,
@@ -107,14 +137,22 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
     let mut inscribed_offsets = BTreeMap::new();
     let mut input_value = 0;
     let mut id_counter = 0;
+    let total_output_value = tx.output.iter().map(|txout| txout.value).sum::<u64>();
 
-    for (input_index, tx_in) in tx.input.iter().enumerate() {
+    // collect the inscriptions already on every input first, so that new
+    // inscriptions see inscribed offsets of later inputs as well
+    let mut inputs = Vec::new();
+
+    for tx_in in &tx.input {
       // skip subsidy since no inscriptions possible
       if tx_in.previous_output.is_null() {
         input_value += Height(self.height).subsidy();
+        inputs.push(None);
         continue;
       }
 
+      let mut old_inscriptions = Vec::new();
+
       // find existing inscriptions on input (transfers of inscriptions)
       for (old_satpoint, inscription_id) in Index::inscriptions_on_output_ordered(
         self.reinscription_id_to_seq_num,
@@ -122,7 +160,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         tx_in.previous_output,
       )? {
         let offset = input_value + old_satpoint.offset;
-        floating_inscriptions.push(Flotsam {
+        old_inscriptions.push(Flotsam {
           offset,
           inscription_id,
           origin: Origin::Old { old_satpoint },
@@ -153,6 +191,16 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         })?
       };
 
+      inputs.push(Some((offset, input_value, old_inscriptions)));
+    }
+
+    for (input_index, input) in inputs.into_iter().enumerate() {
+      let Some((offset, input_end, old_inscriptions)) = input else {
+        continue;
+      };
+
+      floating_inscriptions.extend(old_inscriptions);
+
       // go through all inscriptions in this input
       while let Some(inscription) = new_inscriptions.peek() {
         if inscription.tx_in_index != u32::try_from(input_index).unwrap() {
@@ -164,10 +212,18 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           index: id_counter,
         };
 
-        let curse = if inscription.tx_in_index != 0 {
-          Some(Curse::NotInFirstInput)
-        } else if inscription.tx_in_offset != 0 {
-          Some(Curse::NotAtOffsetZero)
+        // a pointer directs the inscription to a specific sat of the outputs
+        let pointer = inscription
+          .inscription
+          .pointer()
+          .filter(|pointer| *pointer < total_output_value);
+
+        let offset = pointer.unwrap_or(offset);
+
+        let curse = if let Some(curse) =
+          Curse::from_position(inscription.tx_in_index, inscription.tx_in_offset)
+        {
+          Some(curse)
         } else if inscribed_offsets.contains_key(&offset) {
           let seq_num = self.reinscription_id_to_seq_num.len()?;
 
@@ -198,10 +254,16 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             .get(&offset)
             .and_then(|(inscription_id, _count)| {
               match self.id_to_entry.get(&inscription_id.store()) {
-                Ok(option) => option.map(|entry| {
-                  let loaded_entry = InscriptionEntry::load(entry.value());
-                  loaded_entry.number < 0
-                }),
+                Ok(Some(entry)) => Some(InscriptionEntry::load(entry.value()).number < 0),
+                // the initial inscription is new in this transaction
+                Ok(None) => floating_inscriptions
+                  .iter()
+                  .find_map(|flotsam| match flotsam.origin {
+                    Origin::New { cursed, .. } if flotsam.inscription_id == *inscription_id => {
+                      Some(cursed)
+                    }
+                    _ => None,
+                  }),
                 Err(_) => None,
               }
             })
@@ -214,7 +276,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           curse.is_some()
         };
 
-        let unbound = input_value == 0 || inscription.tx_in_offset != 0;
+        let unbound = input_end == 0 || (inscription.tx_in_offset != 0 && pointer.is_none());
 
         if curse.is_some() || unbound {
           log::info!(
@@ -229,19 +291,33 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           inscription_id,
           offset,
           origin: Origin::New {
+            curse,
             fee: 0,
             cursed,
+            metaprotocol: inscription.inscription.metaprotocol().map(str::to_string),
+            parent: inscription.inscription.parent(),
             unbound,
           },
         });
 
+        inscribed_offsets
+          .entry(offset)
+          .and_modify(|(_id, count)| *count += 1)
+          .or_insert((inscription_id, 0));
+
         new_inscriptions.next();
         id_counter += 1;
       }
     }
 
+    // a parent is only valid if it is spent by one of this transaction's inputs
+    let spent_inscriptions = floating_inscriptions
+      .iter()
+      .filter(|flotsam| matches!(flotsam.origin, Origin::Old { .. }))
+      .map(|flotsam| flotsam.inscription_id)
+      .collect::<HashSet<InscriptionId>>();
+
     // still have to normalize over inscription size
-    let total_output_value = tx.output.iter().map(|txout| txout.value).sum::<u64>();
     let mut floating_inscriptions = floating_inscriptions
       .into_iter()
       .map(|flotsam| {
@@ -250,8 +326,11 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           offset,
           origin:
             Origin::New {
+              curse,
               fee: _,
               cursed,
+              metaprotocol,
+              parent,
               unbound,
             },
         } = flotsam
@@ -260,8 +339,11 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             inscription_id,
             offset,
             origin: Origin::New {
+              curse,
               fee: (input_value - total_output_value) / u64::from(id_counter),
               cursed,
+              metaprotocol,
+              parent: parent.filter(|parent| spent_inscriptions.contains(parent)),
               unbound,
             },
           }
@@ -339,6 +421,14 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
     }
   }
 
+  pub(super) fn has_inscriptions(&self, outpoint: OutPoint) -> Result<bool> {
+    Ok(
+      Index::inscriptions_on_output_unordered(self.satpoint_to_id, outpoint)?
+        .next()
+        .is_some(),
+    )
+  }
+
   fn calculate_sat(
     input_sat_ranges: Option<&VecDeque<(u64, u64)>>,
     input_offset: u64,
@@ -349,8 +439,9 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       for (start, end) in input_sat_ranges {
         let size = end - start;
         if offset + size > input_offset {
-          let n = start + input_offset - offset;
-          sat = Some(Sat(n));
+          if is_tracked_sat_range(&(*start, *end)) {
+            sat = Some(Sat(start + input_offset - offset));
+          }
           break;
         }
         offset += size;
@@ -373,8 +464,11 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         false
       }
       Origin::New {
+        curse,
         fee,
         cursed,
+        metaprotocol,
+        parent,
         unbound,
       } => {
         let number = if cursed {
@@ -391,6 +485,18 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
 
         self.number_to_id.insert(number, &inscription_id)?;
 
+        if let Some(metaprotocol) = metaprotocol {
+          self
+            .metaprotocol_and_number_to_id
+            .insert((metaprotocol.as_str(), number), &inscription_id)?;
+        }
+
+        if let Some(parent) = parent {
+          self
+            .parent_to_children
+            .insert(&parent.store(), &inscription_id)?;
+        }
+
         let sat = if unbound {
           None
         } else {
@@ -400,9 +506,11 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             for (start, end) in input_sat_ranges {
               let size = end - start;
               if offset + size > flotsam.offset {
-                let n = start + flotsam.offset - offset;
-                self.sat_to_inscription_id.insert(&n, &inscription_id)?;
-                sat = Some(Sat(n));
+                if is_tracked_sat_range(&(*start, *end)) {
+                  let n = start + flotsam.offset - offset;
+                  self.sat_to_inscription_id.insert(&n, &inscription_id)?;
+                  sat = Some(Sat(n));
+                }
                 break;
               }
               offset += size;
@@ -414,6 +522,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         self.id_to_entry.insert(
           &inscription_id,
           &InscriptionEntry {
+            curse,
             fee,
             height: self.height,
             number,
@@ -440,6 +549,9 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
 
     self.satpoint_to_id.insert(&satpoint, &inscription_id)?;
     self.id_to_satpoint.insert(&inscription_id, &satpoint)?;
+    self
+      .id_and_height_to_satpoint
+      .insert(&(flotsam.inscription_id, self.height).store(), &satpoint)?;
 
     Ok(())
   }
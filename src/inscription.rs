@@ -8,24 +8,60 @@ use {
     taproot::TAPROOT_ANNEX_PREFIX,
     ScriptBuf, Witness,
   },
-  std::{iter::Peekable, str},
+  std::{io::Write, iter::Peekable, str},
 };
 
 const PROTOCOL_ID: [u8; 3] = *b"ord";
 const BODY_TAG: [u8; 0] = [];
 const CONTENT_TYPE_TAG: [u8; 1] = [1];
-
-#[derive(Debug, PartialEq, Clone)]
+const POINTER_TAG: [u8; 1] = [2];
+const PARENT_TAG: [u8; 1] = [3];
+const METADATA_TAG: [u8; 1] = [5];
+const METAPROTOCOL_TAG: [u8; 1] = [7];
+const CONTENT_ENCODING_TAG: [u8; 1] = [9];
+const DELEGATE_TAG: [u8; 1] = [11];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub(crate) enum Curse {
   NotInFirstInput,
   NotAtOffsetZero,
   Reinscription,
 }
 
+impl Curse {
+  /// The curse that follows from an envelope's position alone, without
+  /// considering reinscriptions.
+  pub(crate) fn from_position(tx_in_index: u32, tx_in_offset: u32) -> Option<Self> {
+    if tx_in_index != 0 {
+      Some(Self::NotInFirstInput)
+    } else if tx_in_offset != 0 {
+      Some(Self::NotAtOffsetZero)
+    } else {
+      None
+    }
+  }
+}
+
+impl Display for Curse {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::NotInFirstInput => write!(f, "not in first input"),
+      Self::NotAtOffsetZero => write!(f, "not at offset zero"),
+      Self::Reinscription => write!(f, "reinscription"),
+    }
+  }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct Inscription {
   body: Option<Vec<u8>>,
+  content_encoding: Option<Vec<u8>>,
   content_type: Option<Vec<u8>>,
+  delegate: Option<Vec<u8>>,
+  metadata: Option<Vec<u8>>,
+  metaprotocol: Option<Vec<u8>>,
+  parent: Option<Vec<u8>>,
+  pointer: Option<Vec<u8>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -38,7 +74,63 @@ pub(crate) struct TransactionInscription {
 impl Inscription {
   #[cfg(test)]
   pub(crate) fn new(content_type: Option<Vec<u8>>, body: Option<Vec<u8>>) -> Self {
-    Self { content_type, body }
+    Self {
+      content_type,
+      body,
+      content_encoding: None,
+      delegate: None,
+      metadata: None,
+      metaprotocol: None,
+      parent: None,
+      pointer: None,
+    }
+  }
+
+  pub(crate) fn from_delegate(delegate: InscriptionId, metadata: Option<Vec<u8>>) -> Self {
+    Self {
+      body: None,
+      content_encoding: None,
+      content_type: None,
+      delegate: Some(delegate.value()),
+      metadata,
+      metaprotocol: None,
+      parent: None,
+      pointer: None,
+    }
+  }
+
+  #[cfg(test)]
+  pub(crate) fn with_content_encoding(mut self, content_encoding: &str) -> Self {
+    self.content_encoding = Some(content_encoding.into());
+    self
+  }
+
+  #[cfg(test)]
+  pub(crate) fn with_parent(mut self, parent: InscriptionId) -> Self {
+    self.parent = Some(parent.value());
+    self
+  }
+
+  #[cfg(test)]
+  pub(crate) fn with_metaprotocol(mut self, metaprotocol: &str) -> Self {
+    self.metaprotocol = Some(metaprotocol.into());
+    self
+  }
+
+  #[cfg(test)]
+  pub(crate) fn with_metadata(mut self, metadata: Option<Vec<u8>>) -> Self {
+    self.metadata = metadata;
+    self
+  }
+
+  pub(crate) fn with_pointer(mut self, pointer: u64) -> Self {
+    let pointer = pointer.to_le_bytes();
+    let len = pointer
+      .iter()
+      .rposition(|byte| *byte != 0)
+      .map_or(0, |i| i + 1);
+    self.pointer = Some(pointer[..len].to_vec());
+    self
   }
 
   pub(crate) fn from_transaction(tx: &Transaction) -> Vec<TransactionInscription> {
@@ -62,10 +154,43 @@ impl Inscription {
     result
   }
 
-  pub(crate) fn from_file(chain: Chain, path: impl AsRef<Path>) -> Result<Self, Error> {
+  pub(crate) fn envelopes_from_witness(
+    witness: &Witness,
+  ) -> Result<Vec<Result<Inscription, InscriptionError>>, InscriptionError> {
+    InscriptionParser::parse_envelopes(witness)
+  }
+
+  pub(crate) fn from_file(
+    chain: Chain,
+    path: impl AsRef<Path>,
+    parent: Option<InscriptionId>,
+    metadata: Option<Vec<u8>>,
+    metaprotocol: Option<String>,
+    compress: bool,
+    policy: &ContentPolicy,
+  ) -> Result<Self, Error> {
     let path = path.as_ref();
 
-    let body = fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
+    let mut body =
+      fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
+
+    let content_type = policy.content_type(path, &body)?;
+
+    let mut content_encoding = None;
+
+    if compress {
+      let mut compressed = Vec::new();
+
+      {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 24);
+        writer.write_all(&body)?;
+      }
+
+      if compressed.len() < body.len() {
+        body = compressed;
+        content_encoding = Some("br".into());
+      }
+    }
 
     if let Some(limit) = chain.inscription_content_size_limit() {
       let len = body.len();
@@ -74,14 +199,76 @@ impl Inscription {
       }
     }
 
-    let content_type = Media::content_type_for_path(path)?;
+    policy.check_body_size(body.len())?;
 
     Ok(Self {
       body: Some(body),
-      content_type: Some(content_type.into()),
+      content_encoding,
+      content_type: Some(content_type.into_bytes()),
+      delegate: None,
+      metadata,
+      metaprotocol: metaprotocol.map(String::into_bytes),
+      parent: parent.map(InscriptionId::value),
+      pointer: None,
     })
   }
 
+  pub(crate) fn from_text(
+    chain: Chain,
+    text: String,
+    content_type: Option<String>,
+    metadata: Option<Vec<u8>>,
+    policy: &ContentPolicy,
+  ) -> Result<Self, Error> {
+    let content_type = content_type.unwrap_or_else(|| "text/plain;charset=utf-8".into());
+
+    if content_type.starts_with("text/plain") {
+      ContentPolicy::check_name(&text)?;
+    }
+
+    if let Some(limit) = chain.inscription_content_size_limit() {
+      let len = text.len();
+      if len > limit {
+        bail!("content size of {len} bytes exceeds {limit} byte limit for {chain} inscriptions");
+      }
+    }
+
+    policy.check_body_size(text.len())?;
+
+    Ok(Self {
+      body: Some(text.into_bytes()),
+      content_encoding: None,
+      content_type: Some(content_type.into_bytes()),
+      delegate: None,
+      metadata,
+      metaprotocol: None,
+      parent: None,
+      pointer: None,
+    })
+  }
+
+  pub(crate) fn metadata_from_file(path: impl AsRef<Path>) -> Result<Vec<u8>, Error> {
+    let path = path.as_ref();
+
+    let data = fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
+
+    if path
+      .extension()
+      .map(|extension| extension.eq_ignore_ascii_case("json"))
+      .unwrap_or_default()
+    {
+      let value: serde_json::Value = serde_json::from_slice(&data)
+        .with_context(|| format!("failed to parse JSON metadata in {}", path.display()))?;
+      let mut cbor = Vec::new();
+      ciborium::into_writer(&value, &mut cbor)?;
+      Ok(cbor)
+    } else {
+      ciborium::from_reader::<ciborium::Value, _>(data.as_slice())
+        .with_context(|| format!("failed to parse CBOR metadata in {}", path.display()))?;
+      Ok(data)
+    }
+  }
+
   fn append_reveal_script_to_builder(&self, mut builder: script::Builder) -> script::Builder {
     builder = builder
       .push_opcode(opcodes::OP_FALSE)
@@ -94,6 +281,44 @@ impl Inscription {
         .push_slice(PushBytesBuf::try_from(content_type).unwrap());
     }
 
+    if let Some(pointer) = self.pointer.clone() {
+      builder = builder
+        .push_slice(POINTER_TAG)
+        .push_slice(PushBytesBuf::try_from(pointer).unwrap());
+    }
+
+    if let Some(content_encoding) = self.content_encoding.clone() {
+      builder = builder
+        .push_slice(CONTENT_ENCODING_TAG)
+        .push_slice(PushBytesBuf::try_from(content_encoding).unwrap());
+    }
+
+    if let Some(parent) = self.parent.clone() {
+      builder = builder
+        .push_slice(PARENT_TAG)
+        .push_slice(PushBytesBuf::try_from(parent).unwrap());
+    }
+
+    if let Some(delegate) = self.delegate.clone() {
+      builder = builder
+        .push_slice(DELEGATE_TAG)
+        .push_slice(PushBytesBuf::try_from(delegate).unwrap());
+    }
+
+    if let Some(metaprotocol) = self.metaprotocol.clone() {
+      builder = builder
+        .push_slice(METAPROTOCOL_TAG)
+        .push_slice(PushBytesBuf::try_from(metaprotocol).unwrap());
+    }
+
+    if let Some(metadata) = &self.metadata {
+      for chunk in metadata.chunks(520) {
+        builder = builder
+          .push_slice(METADATA_TAG)
+          .push_slice(PushBytesBuf::try_from(chunk.to_vec()).unwrap());
+      }
+    }
+
     if let Some(body) = &self.body {
       builder = builder.push_slice(BODY_TAG);
       for chunk in body.chunks(520) {
@@ -108,6 +333,17 @@ impl Inscription {
     self.append_reveal_script_to_builder(builder).into_script()
   }
 
+  pub(crate) fn append_batch_reveal_script(
+    inscriptions: &[Inscription],
+    mut builder: script::Builder,
+  ) -> ScriptBuf {
+    for inscription in inscriptions {
+      builder = inscription.append_reveal_script_to_builder(builder);
+    }
+
+    builder.into_script()
+  }
+
   pub(crate) fn media(&self) -> Media {
     if self.body.is_none() {
       return Media::Unknown;
@@ -136,6 +372,44 @@ impl Inscription {
     str::from_utf8(self.content_type.as_ref()?).ok()
   }
 
+  pub(crate) fn content_encoding(&self) -> Option<&str> {
+    str::from_utf8(self.content_encoding.as_ref()?).ok()
+  }
+
+  pub(crate) fn delegate(&self) -> Option<InscriptionId> {
+    InscriptionId::from_value(self.delegate.as_ref()?)
+  }
+
+  pub(crate) fn parent(&self) -> Option<InscriptionId> {
+    InscriptionId::from_value(self.parent.as_ref()?)
+  }
+
+  pub(crate) fn pointer(&self) -> Option<u64> {
+    let value = self.pointer.as_ref()?;
+
+    if value.iter().skip(8).any(|byte| *byte != 0) {
+      return None;
+    }
+
+    let mut pointer = [0; 8];
+    let len = value.len().min(8);
+    pointer[..len].copy_from_slice(&value[..len]);
+
+    Some(u64::from_le_bytes(pointer))
+  }
+
+  pub(crate) fn metaprotocol(&self) -> Option<&str> {
+    str::from_utf8(self.metaprotocol.as_ref()?).ok()
+  }
+
+  pub(crate) fn metadata(&self) -> Option<ciborium::Value> {
+    ciborium::from_reader(self.metadata.as_deref()?).ok()
+  }
+
+  pub(crate) fn metadata_json(&self) -> Option<serde_json::Value> {
+    Some(cbor_to_json(self.metadata()?))
+  }
+
   #[cfg(test)]
   pub(crate) fn to_witness(&self) -> Witness {
     let builder = script::Builder::new();
@@ -151,6 +425,45 @@ impl Inscription {
   }
 }
 
+fn cbor_to_json(value: ciborium::Value) -> serde_json::Value {
+  use {ciborium::Value as Cbor, serde_json::Value as Json};
+
+  match value {
+    Cbor::Integer(integer) => {
+      let integer = i128::from(integer);
+      if let Ok(integer) = i64::try_from(integer) {
+        Json::from(integer)
+      } else if let Ok(integer) = u64::try_from(integer) {
+        Json::from(integer)
+      } else {
+        Json::String(integer.to_string())
+      }
+    }
+    Cbor::Bytes(bytes) => Json::String(hex::encode(bytes)),
+    Cbor::Float(float) => serde_json::Number::from_f64(float)
+      .map(Json::Number)
+      .unwrap_or(Json::Null),
+    Cbor::Text(text) => Json::String(text),
+    Cbor::Bool(bool) => Json::Bool(bool),
+    Cbor::Null => Json::Null,
+    Cbor::Tag(_, value) => cbor_to_json(*value),
+    Cbor::Array(array) => Json::Array(array.into_iter().map(cbor_to_json).collect()),
+    Cbor::Map(map) => Json::Object(
+      map
+        .into_iter()
+        .map(|(key, value)| {
+          let key = match key {
+            Cbor::Text(text) => text,
+            key => cbor_to_json(key).to_string(),
+          };
+          (key, cbor_to_json(value))
+        })
+        .collect(),
+    ),
+    _ => Json::Null,
+  }
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum InscriptionError {
   EmptyWitness,
@@ -161,6 +474,19 @@ pub(crate) enum InscriptionError {
   UnrecognizedEvenField,
 }
 
+impl Display for InscriptionError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::EmptyWitness => write!(f, "empty witness"),
+      Self::InvalidInscription => write!(f, "invalid inscription"),
+      Self::KeyPathSpend => write!(f, "key path spend"),
+      Self::NoInscription => write!(f, "no inscription"),
+      Self::Script(err) => write!(f, "script error: {err}"),
+      Self::UnrecognizedEvenField => write!(f, "unrecognized even field"),
+    }
+  }
+}
+
 type Result<T, E = InscriptionError> = std::result::Result<T, E>;
 
 #[derive(Debug)]
@@ -170,6 +496,10 @@ struct InscriptionParser<'a> {
 
 impl<'a> InscriptionParser<'a> {
   fn parse(witness: &Witness) -> Result<Vec<Inscription>> {
+    Self::parse_envelopes(witness)?.into_iter().collect()
+  }
+
+  fn parse_envelopes(witness: &Witness) -> Result<Vec<Result<Inscription>>> {
     if witness.is_empty() {
       return Err(InscriptionError::EmptyWitness);
     }
@@ -196,12 +526,12 @@ impl<'a> InscriptionParser<'a> {
       })
       .unwrap();
 
-    InscriptionParser {
-      instructions: ScriptBuf::from(Vec::from(script)).instructions().peekable(),
-    }
-    .parse_inscriptions()
-    .into_iter()
-    .collect()
+    Ok(
+      InscriptionParser {
+        instructions: ScriptBuf::from(Vec::from(script)).instructions().peekable(),
+      }
+      .parse_inscriptions(),
+    )
   }
 
   fn parse_inscriptions(&mut self) -> Vec<Result<Inscription>> {
@@ -231,6 +561,13 @@ impl<'a> InscriptionParser<'a> {
           fields.insert(BODY_TAG.as_slice(), body);
           break;
         }
+        Instruction::PushBytes(tag) if tag.as_bytes() == METADATA_TAG.as_slice() => {
+          let chunk = self.expect_push()?;
+          fields
+            .entry(METADATA_TAG.as_slice())
+            .or_insert_with(Vec::new)
+            .extend_from_slice(chunk);
+        }
         Instruction::PushBytes(tag) => {
           if fields.contains_key(tag.as_bytes()) {
             return Err(InscriptionError::InvalidInscription);
@@ -244,6 +581,12 @@ impl<'a> InscriptionParser<'a> {
 
     let body = fields.remove(BODY_TAG.as_slice());
     let content_type = fields.remove(CONTENT_TYPE_TAG.as_slice());
+    let content_encoding = fields.remove(CONTENT_ENCODING_TAG.as_slice());
+    let delegate = fields.remove(DELEGATE_TAG.as_slice());
+    let metadata = fields.remove(METADATA_TAG.as_slice());
+    let metaprotocol = fields.remove(METAPROTOCOL_TAG.as_slice());
+    let parent = fields.remove(PARENT_TAG.as_slice());
+    let pointer = fields.remove(POINTER_TAG.as_slice());
 
     for tag in fields.keys() {
       if let Some(lsb) = tag.first() {
@@ -253,7 +596,16 @@ impl<'a> InscriptionParser<'a> {
       }
     }
 
-    Ok(Inscription { body, content_type })
+    Ok(Inscription {
+      body,
+      content_encoding,
+      content_type,
+      delegate,
+      metadata,
+      metaprotocol,
+      parent,
+      pointer,
+    })
   }
 
   fn advance(&mut self) -> Result<Instruction<'a>> {
@@ -313,7 +665,7 @@ impl<'a> InscriptionParser<'a> {
 
 #[cfg(test)]
 mod tests {
-  use super::*;
+  use {super::*, std::io::Read};
 
   #[test]
   fn empty() {
@@ -395,7 +747,7 @@ mod tests {
         b"ord",
         &[1],
         b"text/plain;charset=utf-8",
-        &[3],
+        &[15],
         b"bar",
         &[],
         b"ord",
@@ -411,6 +763,12 @@ mod tests {
       Ok(vec![Inscription {
         content_type: Some(b"text/plain;charset=utf-8".to_vec()),
         body: None,
+        content_encoding: None,
+        delegate: None,
+        metadata: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
       }]),
     );
   }
@@ -422,6 +780,12 @@ mod tests {
       Ok(vec![Inscription {
         content_type: None,
         body: Some(b"foo".to_vec()),
+        content_encoding: None,
+        delegate: None,
+        metadata: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
       }]),
     );
   }
@@ -752,6 +1116,12 @@ mod tests {
       &Inscription {
         content_type: None,
         body: None,
+        content_encoding: None,
+        delegate: None,
+        metadata: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
       }
       .append_reveal_script(script::Builder::new()),
     );
@@ -763,6 +1133,12 @@ mod tests {
       vec![Inscription {
         content_type: None,
         body: None,
+        content_encoding: None,
+        delegate: None,
+        metadata: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
       }]
     );
   }
@@ -770,10 +1146,16 @@ mod tests {
   #[test]
   fn unknown_odd_fields_are_ignored() {
     assert_eq!(
-      InscriptionParser::parse(&envelope(&[b"ord", &[3], &[0]])),
+      InscriptionParser::parse(&envelope(&[b"ord", &[15], &[0]])),
       Ok(vec![Inscription {
         content_type: None,
         body: None,
+        content_encoding: None,
+        delegate: None,
+        metadata: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
       }]),
     );
   }
@@ -781,8 +1163,300 @@ mod tests {
   #[test]
   fn unknown_even_fields_are_invalid() {
     assert_eq!(
-      InscriptionParser::parse(&envelope(&[b"ord", &[2], &[0]])),
+      InscriptionParser::parse(&envelope(&[b"ord", &[4], &[0]])),
       Err(InscriptionError::UnrecognizedEvenField),
     );
   }
+
+  #[test]
+  fn metadata_is_parsed() {
+    assert_eq!(
+      InscriptionParser::parse(&envelope(&[b"ord", &[5], &[0xa0], &[], b"foo"])),
+      Ok(vec![Inscription {
+        content_type: None,
+        body: Some(b"foo".to_vec()),
+        content_encoding: None,
+        delegate: None,
+        metadata: Some(vec![0xa0]),
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
+      }]),
+    );
+  }
+
+  #[test]
+  fn metadata_pushes_are_concatenated() {
+    assert_eq!(
+      InscriptionParser::parse(&envelope(&[b"ord", &[5], &[0x82, 0x01], &[5], &[0x02]])),
+      Ok(vec![Inscription {
+        content_type: None,
+        body: None,
+        content_encoding: None,
+        delegate: None,
+        metadata: Some(vec![0x82, 0x01, 0x02]),
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
+      }]),
+    );
+  }
+
+  #[test]
+  fn large_metadata_round_trips() {
+    let mut metadata = Vec::new();
+    ciborium::into_writer(&"x".repeat(1000), &mut metadata).unwrap();
+
+    let inscription = inscription("text/plain", "foo").with_metadata(Some(metadata));
+
+    assert_eq!(
+      InscriptionParser::parse(&inscription.to_witness()).unwrap(),
+      vec![inscription.clone()],
+    );
+
+    assert_eq!(
+      inscription.metadata_json(),
+      Some(serde_json::Value::String("x".repeat(1000)))
+    );
+  }
+
+  #[test]
+  fn metadata_json() {
+    let mut metadata = Vec::new();
+    ciborium::into_writer(
+      &ciborium::Value::Map(vec![
+        (
+          ciborium::Value::Text("name".into()),
+          ciborium::Value::Text("foo".into()),
+        ),
+        (
+          ciborium::Value::Integer(1.into()),
+          ciborium::Value::Bytes(vec![0xff]),
+        ),
+      ]),
+      &mut metadata,
+    )
+    .unwrap();
+
+    assert_eq!(
+      inscription("text/plain", "foo")
+        .with_metadata(Some(metadata))
+        .metadata_json(),
+      Some(serde_json::json!({"name": "foo", "1": "ff"}))
+    );
+  }
+
+  #[test]
+  fn invalid_metadata_is_ignored() {
+    assert_eq!(
+      inscription("text/plain", "foo")
+        .with_metadata(Some(vec![0xff, 0xff]))
+        .metadata(),
+      None
+    );
+  }
+
+  #[test]
+  fn parent_round_trips() {
+    let parent = InscriptionId {
+      txid: txid(1),
+      index: 0x0102,
+    };
+
+    let inscription = inscription("text/plain", "foo").with_parent(parent);
+
+    assert_eq!(
+      InscriptionParser::parse(&inscription.to_witness()).unwrap(),
+      vec![inscription.clone()],
+    );
+
+    assert_eq!(inscription.parent(), Some(parent));
+  }
+
+  #[test]
+  fn parent_with_invalid_length_is_ignored() {
+    let inscriptions =
+      InscriptionParser::parse(&envelope(&[b"ord", &[3], &[0; 31], &[], b"foo"])).unwrap();
+
+    assert_eq!(inscriptions[0].parent(), None);
+  }
+
+  #[test]
+  fn duplicate_parent_field_is_invalid() {
+    assert_eq!(
+      InscriptionParser::parse(&envelope(&[b"ord", &[3], &[0; 32], &[3], &[0; 32]])),
+      Err(InscriptionError::InvalidInscription),
+    );
+  }
+
+  #[test]
+  fn content_encoding_round_trips() {
+    let inscription = inscription("text/plain", "foo").with_content_encoding("br");
+
+    assert_eq!(
+      InscriptionParser::parse(&inscription.to_witness()).unwrap(),
+      vec![inscription.clone()],
+    );
+
+    assert_eq!(inscription.content_encoding(), Some("br"));
+  }
+
+  #[test]
+  fn from_file_compresses_when_smaller() {
+    let tempdir = TempDir::new().unwrap();
+
+    let path = tempdir.path().join("foo.txt");
+    fs::write(&path, "foo".repeat(1000)).unwrap();
+
+    let inscription = Inscription::from_file(
+      Chain::Mainnet,
+      &path,
+      None,
+      None,
+      None,
+      true,
+      &ContentPolicy::default(),
+    )
+    .unwrap();
+    assert_eq!(inscription.content_encoding(), Some("br"));
+    assert!(inscription.body().unwrap().len() < 3000);
+
+    let mut decompressed = Vec::new();
+    brotli::Decompressor::new(inscription.body().unwrap(), 4096)
+      .read_to_end(&mut decompressed)
+      .unwrap();
+    assert_eq!(decompressed, "foo".repeat(1000).as_bytes());
+  }
+
+  #[test]
+  fn from_file_does_not_compress_when_larger() {
+    let tempdir = TempDir::new().unwrap();
+
+    let path = tempdir.path().join("foo.txt");
+    fs::write(&path, "a").unwrap();
+
+    let inscription = Inscription::from_file(
+      Chain::Mainnet,
+      &path,
+      None,
+      None,
+      None,
+      true,
+      &ContentPolicy::default(),
+    )
+    .unwrap();
+    assert_eq!(inscription.content_encoding(), None);
+    assert_eq!(inscription.body(), Some(b"a".as_slice()));
+  }
+
+  #[test]
+  fn metaprotocol_round_trips() {
+    let inscription = inscription("text/plain", "foo").with_metaprotocol("brc-20");
+
+    assert_eq!(
+      InscriptionParser::parse(&inscription.to_witness()).unwrap(),
+      vec![inscription.clone()],
+    );
+
+    assert_eq!(inscription.metaprotocol(), Some("brc-20"));
+  }
+
+  #[test]
+  fn metaprotocol_is_parsed() {
+    let inscriptions =
+      InscriptionParser::parse(&envelope(&[b"ord", &[7], b"foo", &[], b"bar"])).unwrap();
+
+    assert_eq!(inscriptions[0].metaprotocol(), Some("foo"));
+  }
+
+  #[test]
+  fn delegate_round_trips() {
+    let delegate = InscriptionId {
+      txid: txid(1),
+      index: 2,
+    };
+
+    let inscription = Inscription::from_delegate(delegate, None);
+
+    assert_eq!(
+      InscriptionParser::parse(&inscription.to_witness()).unwrap(),
+      vec![inscription.clone()],
+    );
+
+    assert_eq!(inscription.delegate(), Some(delegate));
+    assert_eq!(inscription.body(), None);
+  }
+
+  #[test]
+  fn delegate_is_parsed() {
+    let inscriptions =
+      InscriptionParser::parse(&envelope(&[b"ord", &[11], &[0x11; 32], &[], b"foo"])).unwrap();
+
+    assert_eq!(
+      inscriptions[0].delegate(),
+      Some(InscriptionId::from(txid(1)))
+    );
+  }
+
+  #[test]
+  fn pointer_round_trips() {
+    for pointer in [0, 1, 0x0100, u64::MAX] {
+      let inscription = inscription("text/plain", "foo").with_pointer(pointer);
+
+      assert_eq!(
+        InscriptionParser::parse(&inscription.to_witness()).unwrap(),
+        vec![inscription.clone()],
+      );
+
+      assert_eq!(inscription.pointer(), Some(pointer));
+    }
+  }
+
+  #[test]
+  fn pointer_encoding_is_minimal() {
+    assert_eq!(
+      inscription("text/plain", "foo").with_pointer(0).pointer,
+      Some(Vec::new())
+    );
+    assert_eq!(
+      inscription("text/plain", "foo")
+        .with_pointer(0x0100)
+        .pointer,
+      Some(vec![0, 1])
+    );
+  }
+
+  #[test]
+  fn pointer_with_trailing_zeros_is_parsed() {
+    let inscriptions =
+      InscriptionParser::parse(&envelope(&[b"ord", &[2], &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0]]))
+        .unwrap();
+
+    assert_eq!(inscriptions[0].pointer(), Some(1));
+  }
+
+  #[test]
+  fn pointer_larger_than_u64_is_ignored() {
+    let inscriptions =
+      InscriptionParser::parse(&envelope(&[b"ord", &[2], &[0, 0, 0, 0, 0, 0, 0, 0, 1]])).unwrap();
+
+    assert_eq!(inscriptions[0].pointer(), None);
+  }
+
+  #[test]
+  fn batch_reveal_script_contains_all_inscriptions() {
+    let inscriptions = vec![
+      inscription("text/plain", "foo"),
+      inscription("text/plain", "bar").with_pointer(1),
+    ];
+
+    let mut witness = Witness::new();
+    witness.push(Inscription::append_batch_reveal_script(
+      &inscriptions,
+      script::Builder::new(),
+    ));
+    witness.push([]);
+
+    assert_eq!(InscriptionParser::parse(&witness).unwrap(), inscriptions);
+  }
 }
@@ -85,6 +85,40 @@ impl FromStr for InscriptionId {
   }
 }
 
+impl InscriptionId {
+  pub(crate) fn value(self) -> Vec<u8> {
+    let index = self.index.to_le_bytes();
+    let index_len = index
+      .iter()
+      .rposition(|byte| *byte != 0)
+      .map_or(0, |i| i + 1);
+
+    let mut value = self.txid.to_byte_array().to_vec();
+    value.extend_from_slice(&index[..index_len]);
+    value
+  }
+
+  pub(crate) fn from_value(value: &[u8]) -> Option<Self> {
+    if value.len() < Txid::LEN || value.len() > Txid::LEN + 4 {
+      return None;
+    }
+
+    let (txid, index) = value.split_at(Txid::LEN);
+
+    if index.last() == Some(&0) {
+      return None;
+    }
+
+    let mut index_bytes = [0; 4];
+    index_bytes[..index.len()].copy_from_slice(index);
+
+    Some(Self {
+      txid: Txid::from_slice(txid).ok()?,
+      index: u32::from_le_bytes(index_bytes),
+    })
+  }
+}
+
 impl From<Txid> for InscriptionId {
   fn from(txid: Txid) -> Self {
     Self { txid, index: 0 }
@@ -184,4 +218,46 @@ mod tests {
       Err(ParseError::Txid(_)),
     );
   }
+
+  #[test]
+  fn value() {
+    assert_eq!(
+      inscription_id(1).value(),
+      [[0x11; 32].as_slice(), &[1]].concat()
+    );
+    assert_eq!(InscriptionId::from(txid(1)).value(), [0x11; 32].to_vec());
+    assert_eq!(
+      InscriptionId {
+        txid: txid(1),
+        index: 0x0100,
+      }
+      .value(),
+      [[0x11; 32].as_slice(), &[0, 1]].concat()
+    );
+  }
+
+  #[test]
+  fn from_value() {
+    for id in [
+      inscription_id(1),
+      InscriptionId::from(txid(2)),
+      InscriptionId {
+        txid: txid(3),
+        index: u32::MAX,
+      },
+    ] {
+      assert_eq!(InscriptionId::from_value(&id.value()), Some(id));
+    }
+
+    assert_eq!(InscriptionId::from_value(&[0; 31]), None);
+    assert_eq!(InscriptionId::from_value(&[0; 37]), None);
+    assert_eq!(
+      InscriptionId::from_value(&[0x11; 33][..]).map(|id| id.index),
+      Some(0x11)
+    );
+    assert_eq!(
+      InscriptionId::from_value(&[[0x11; 32].as_slice(), &[1, 0]].concat()),
+      None
+    );
+  }
 }
@@ -15,12 +15,13 @@ use {
     arguments::Arguments,
     blocktime::Blocktime,
     config::Config,
+    content_policy::ContentPolicy,
     decimal::Decimal,
     degree::Degree,
     deserialize_from_str::DeserializeFromStr,
     epoch::Epoch,
     height::Height,
-    index::{Index, List},
+    index::{is_tracked_sat_range, Index, List},
     inscription::Inscription,
     inscription_id::InscriptionId,
     media::Media,
@@ -76,7 +77,7 @@ use {
 
 pub use crate::{
   fee_rate::FeeRate, object::Object, rarity::Rarity, sat::Sat, sat_point::SatPoint,
-  subcommand::wallet::transaction_builder::TransactionBuilder,
+  subcommand::wallet::{coin_control::CoinSelection, transaction_builder::TransactionBuilder},
 };
 
 #[cfg(test)]
@@ -100,6 +101,7 @@ mod arguments;
 mod blocktime;
 mod chain;
 mod config;
+mod content_policy;
 mod decimal;
 mod degree;
 mod deserialize_from_str;
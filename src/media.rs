@@ -42,6 +42,33 @@ impl Media {
     ("video/webm", Media::Video, &["webm"]),
   ];
 
+  const SIGNATURES: &'static [(usize, &'static [u8], &'static [&'static str])] = &[
+    (0, b"\x1a\x45\xdf\xa3", &["video/webm"]),
+    (0, b"\x89PNG\r\n\x1a\n", &["image/png", "image/apng"]),
+    (0, b"\xff\xd8\xff", &["image/jpeg"]),
+    (0, b"%PDF-", &["application/pdf"]),
+    (0, b"GIF87a", &["image/gif"]),
+    (0, b"GIF89a", &["image/gif"]),
+    (0, b"ID3", &["audio/mpeg"]),
+    (0, b"fLaC", &["audio/flac"]),
+    (0, b"glTF", &["model/gltf-binary"]),
+    (4, b"ftyp", &["video/mp4", "image/avif"]),
+    (8, b"WAVE", &["audio/wav"]),
+    (8, b"WEBP", &["image/webp"]),
+  ];
+
+  pub(crate) fn sniff(body: &[u8]) -> Option<&'static [&'static str]> {
+    Self::SIGNATURES
+      .iter()
+      .find(|(offset, signature, _)| {
+        body
+          .get(*offset..)
+          .unwrap_or_default()
+          .starts_with(signature)
+      })
+      .map(|(_, _, content_types)| *content_types)
+  }
+
   pub(crate) fn content_type_for_path(path: &Path) -> Result<&'static str, Error> {
     let extension = path
       .extension()
@@ -135,6 +162,24 @@ mod tests {
     );
   }
 
+  #[test]
+  fn sniff() {
+    assert_eq!(
+      Media::sniff(b"\x89PNG\r\n\x1a\n\0\0"),
+      Some(["image/png", "image/apng"].as_slice())
+    );
+    assert_eq!(
+      Media::sniff(b"RIFF\0\0\0\0WEBPVP8 "),
+      Some(["image/webp"].as_slice())
+    );
+    assert_eq!(
+      Media::sniff(b"\0\0\0\x18ftypmp42"),
+      Some(["video/mp4", "image/avif"].as_slice())
+    );
+    assert_eq!(Media::sniff(b"hello"), None);
+    assert_eq!(Media::sniff(b""), None);
+  }
+
   #[test]
   fn h264_in_mp4_is_allowed() {
     assert!(Media::check_mp4_codec(Path::new("examples/h264.mp4")).is_ok(),);
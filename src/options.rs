@@ -42,8 +42,19 @@ pub(crate) struct Options {
   pub(crate) height_limit: Option<u64>,
   #[clap(long, help = "Use index at <INDEX>.")]
   pub(crate) index: Option<PathBuf>,
+  #[clap(
+    long,
+    help = "Track unspent outputs of every address, so watch-only wallets work without Bitcoin Core."
+  )]
+  pub(crate) index_addresses: bool,
   #[clap(long, help = "Track location of all satoshis.")]
   pub(crate) index_sats: bool,
+  #[clap(
+    long,
+    requires = "index-sats",
+    help = "Only keep sat ranges of outputs holding sats of at least <INDEX_SATS_MIN_RARITY>. The sat of an inscription is only known if it was inscribed in such an output."
+  )]
+  pub(crate) index_sats_min_rarity: Option<Rarity>,
   #[clap(long, short, help = "Use regtest. Equivalent to `--chain regtest`.")]
   pub(crate) regtest: bool,
   #[clap(long, help = "Connect to Bitcoin Core RPC at <RPC_URL>.")]
@@ -54,6 +65,22 @@ pub(crate) struct Options {
   pub(crate) testnet: bool,
   #[clap(long, default_value = "ord", help = "Use wallet named <WALLET>.")]
   pub(crate) wallet: String,
+  #[clap(
+    long,
+    help = "Watch outputs of <WATCH_ADDRESS> instead of using a Bitcoin Core wallet. May be repeated."
+  )]
+  pub(crate) watch_address: Vec<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Watch outputs of output descriptor <WATCH_DESCRIPTOR> instead of using a Bitcoin Core wallet. May be repeated."
+  )]
+  pub(crate) watch_descriptor: Vec<String>,
+  #[clap(
+    long,
+    default_value = "2000",
+    help = "Ignore cardinal wallet outputs of <MIN_CARDINAL_VALUE> sats or less when listing and spending outputs. Outputs holding inscriptions are always listed."
+  )]
+  pub(crate) min_cardinal_value: u64,
 }
 
 impl Options {
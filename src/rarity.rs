@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
 pub enum Rarity {
   Common,
   Uncommon,
@@ -27,6 +27,28 @@ impl Display for Rarity {
   }
 }
 
+impl From<Rarity> for u64 {
+  fn from(rarity: Rarity) -> Self {
+    rarity as u64
+  }
+}
+
+impl TryFrom<u64> for Rarity {
+  type Error = Error;
+
+  fn try_from(rarity: u64) -> Result<Self, Self::Error> {
+    match rarity {
+      0 => Ok(Self::Common),
+      1 => Ok(Self::Uncommon),
+      2 => Ok(Self::Rare),
+      3 => Ok(Self::Epic),
+      4 => Ok(Self::Legendary),
+      5 => Ok(Self::Mythic),
+      n => Err(anyhow!("invalid rarity: {n}")),
+    }
+  }
+}
+
 impl From<Sat> for Rarity {
   fn from(sat: Sat) -> Self {
     let Degree {
@@ -150,6 +172,22 @@ mod tests {
     case("mythic", Rarity::Mythic);
   }
 
+  #[test]
+  fn u64_round_trip() {
+    for rarity in [
+      Rarity::Common,
+      Rarity::Uncommon,
+      Rarity::Rare,
+      Rarity::Epic,
+      Rarity::Legendary,
+      Rarity::Mythic,
+    ] {
+      assert_eq!(Rarity::try_from(u64::from(rarity)).unwrap(), rarity);
+    }
+
+    Rarity::try_from(6).unwrap_err();
+  }
+
   #[test]
   fn from_str_err() {
     "abc".parse::<Rarity>().unwrap_err();
@@ -0,0 +1,141 @@
+use {super::*, crate::inscription::Curse};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Decode {
+  #[clap(
+    help = "Decode inscription envelopes in <TRANSACTION>, given as a txid, raw transaction hex, or path to a file containing a raw or hex-encoded transaction."
+  )]
+  transaction: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+  pub input: u32,
+  pub offset: u32,
+  pub valid: bool,
+  pub error: Option<String>,
+  pub inscription_id: Option<String>,
+  pub content_type: Option<String>,
+  pub content_length: Option<usize>,
+  pub curse: Option<String>,
+  pub number: Option<i64>,
+  pub unbound: Option<bool>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub txid: Txid,
+  pub envelopes: Vec<Envelope>,
+}
+
+impl Decode {
+  pub(crate) fn run(self, options: Options) -> Result {
+    if let Ok(txid) = self.transaction.parse::<Txid>() {
+      let index = Index::open(&options)?;
+      index.update()?;
+
+      let transaction = index
+        .get_transaction(txid)?
+        .ok_or_else(|| anyhow!("transaction {txid} not found"))?;
+
+      print_json(decode(&transaction, Some(&index))?)
+    } else {
+      print_json(decode(&Self::parse_transaction(&self.transaction)?, None)?)
+    }
+  }
+
+  fn parse_transaction(transaction: &str) -> Result<Transaction> {
+    if let Ok(bytes) = hex::decode(transaction) {
+      return consensus::deserialize(&bytes).context("failed to decode raw transaction");
+    }
+
+    let bytes = fs::read(transaction).with_context(|| {
+      format!("`{transaction}` is neither a txid, raw transaction hex, nor a readable file")
+    })?;
+
+    let bytes = match std::str::from_utf8(&bytes).map(|text| hex::decode(text.trim())) {
+      Ok(Ok(decoded)) => decoded,
+      _ => bytes,
+    };
+
+    consensus::deserialize(&bytes)
+      .with_context(|| format!("failed to decode raw transaction in `{transaction}`"))
+  }
+}
+
+pub(crate) fn decode(transaction: &Transaction, index: Option<&Index>) -> Result<Output> {
+  let txid = transaction.txid();
+
+  let mut envelopes = Vec::new();
+  let mut id_counter = 0;
+
+  for (input, tx_in) in transaction.input.iter().enumerate() {
+    let input = u32::try_from(input).unwrap();
+
+    let Ok(results) = Inscription::envelopes_from_witness(&tx_in.witness) else {
+      continue;
+    };
+
+    // a single invalid envelope causes every envelope in the input to be ignored
+    let input_is_valid = results.iter().all(Result::is_ok);
+
+    for (offset, result) in results.into_iter().enumerate() {
+      let offset = u32::try_from(offset).unwrap();
+
+      let mut envelope = Envelope {
+        input,
+        offset,
+        valid: false,
+        error: None,
+        inscription_id: None,
+        content_type: None,
+        content_length: None,
+        curse: None,
+        number: None,
+        unbound: None,
+      };
+
+      match result {
+        Err(err) => envelope.error = Some(err.to_string()),
+        Ok(inscription) => {
+          envelope.content_type = inscription.content_type().map(str::to_string);
+          envelope.content_length = inscription.content_length();
+
+          if input_is_valid {
+            let inscription_id = InscriptionId {
+              txid,
+              index: id_counter,
+            };
+            id_counter += 1;
+
+            envelope.valid = true;
+            envelope.inscription_id = Some(inscription_id.to_string());
+
+            let entry = match index {
+              Some(index) => index.get_inscription_entry(inscription_id)?,
+              None => None,
+            };
+
+            if let (Some(index), Some(entry)) = (index, entry) {
+              envelope.number = Some(entry.number);
+              envelope.curse = entry.curse.map(|curse| curse.to_string());
+
+              envelope.unbound = index
+                .get_inscription_satpoint_by_id(inscription_id)?
+                .map(|satpoint| satpoint.outpoint == unbound_outpoint());
+            } else {
+              // without an index, reinscriptions cannot be detected
+              envelope.curse = Curse::from_position(input, offset).map(|curse| curse.to_string());
+            }
+          } else {
+            envelope.error = Some("another envelope in this input is invalid".into());
+          }
+        }
+      }
+
+      envelopes.push(envelope);
+    }
+  }
+
+  Ok(Output { txid, envelopes })
+}
@@ -4,10 +4,13 @@ use super::*;
 pub(crate) struct FindAddr {
   #[clap(help = "Find inscribe by id.")]
   id: String,
+  #[clap(long, help = "Look up location and owner as of block <AT_HEIGHT>.")]
+  at_height: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
+  pub satpoint: SatPoint,
   pub output_address: String,
   pub input_address: String
 }
@@ -20,16 +23,22 @@ impl FindAddr {
 
     let inscription_id = self.id.parse::<InscriptionId>().unwrap();
 
-    let satpoint = index
-      .get_inscription_satpoint_by_id(inscription_id)
-      .unwrap()
-      .unwrap();
+    let satpoint = match self.at_height {
+      Some(height) => index
+        .get_inscription_satpoint_at_height(inscription_id, height)?
+        .ok_or_else(|| anyhow!("inscription {inscription_id} not found at height {height}"))?,
+      None => index
+        .get_inscription_satpoint_by_id(inscription_id)
+        .unwrap()
+        .unwrap(),
+    };
 
     let tx = index.get_transaction(satpoint.outpoint.txid).unwrap().unwrap();
     let output_address = get_address_from_tx(satpoint.outpoint, &index);
     let input_address = get_address_from_tx(tx.input[0].previous_output, &index);
 
     print_json(Output {
+      satpoint,
       output_address,
       input_address
     })
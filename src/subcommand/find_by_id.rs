@@ -4,10 +4,13 @@ use super::*;
 pub(crate) struct FindById {
   #[clap(help = "Find inscribe by id.")]
   id: String,
+  #[clap(long, help = "Look up location and owner as of block <AT_HEIGHT>.")]
+  at_height: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
+  pub satpoint: SatPoint,
   pub content: Vec<u8>,
   pub content_type: String,
   pub inscribe_num: i64,
@@ -24,10 +27,15 @@ impl FindById {
     let inscription_id = self.id.parse::<InscriptionId>().unwrap();
     let entry = index.get_inscription_entry(inscription_id).unwrap();
 
-    let satpoint = index
-      .get_inscription_satpoint_by_id(inscription_id)
-      .unwrap()
-      .unwrap();
+    let satpoint = match self.at_height {
+      Some(height) => index
+        .get_inscription_satpoint_at_height(inscription_id, height)?
+        .ok_or_else(|| anyhow!("inscription {inscription_id} not found at height {height}"))?,
+      None => index
+        .get_inscription_satpoint_by_id(inscription_id)
+        .unwrap()
+        .unwrap(),
+    };
 
     let tx = index.get_transaction(satpoint.outpoint.txid).unwrap().unwrap();
     let output_address = get_address_from_tx(options.chain().network(),satpoint.outpoint, &index);
@@ -37,6 +45,7 @@ impl FindById {
     if content.is_some() {
       let content_value = content.unwrap();
       print_json(Output {
+        satpoint,
         content: content_value.clone().into_body().unwrap(),
         content_type: (&content_value.content_type().unwrap()).to_string(),
         inscribe_num: entry.unwrap().number,
@@ -4,10 +4,13 @@ use super::*;
 pub(crate) struct FindNumber {
   #[clap(help = "Find inscribe by number.")]
   number: i64,
+  #[clap(long, help = "Look up location and owner as of block <AT_HEIGHT>.")]
+  at_height: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
+  pub satpoint: SatPoint,
   pub content: Vec<u8>,
   pub inscribe_num: i64,
   pub inscribe_id: String,
@@ -42,10 +45,15 @@ impl FindNumber {
     //   0
     // };
 
-    let satpoint = index
-      .get_inscription_satpoint_by_id(inscription_id)
-      .unwrap()
-      .unwrap();
+    let satpoint = match self.at_height {
+      Some(height) => index
+        .get_inscription_satpoint_at_height(inscription_id, height)?
+        .ok_or_else(|| anyhow!("inscription {inscription_id} not found at height {height}"))?,
+      None => index
+        .get_inscription_satpoint_by_id(inscription_id)
+        .unwrap()
+        .unwrap(),
+    };
 
     // println!("satpoint: {:?}", satpoint);
     let tx = index.get_transaction(satpoint.outpoint.txid).unwrap().unwrap();
@@ -55,6 +63,7 @@ impl FindNumber {
     let content = index.get_inscription_by_id(inscription_id).unwrap();
     if content.is_some() {
       print_json(Output {
+        satpoint,
         content: content.unwrap().into_body().unwrap(),
         inscribe_num: self.number,
         inscribe_id: inscription_id.to_string(),
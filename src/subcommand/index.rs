@@ -1,18 +1,30 @@
-use super::*;
+use {
+  super::*,
+  crate::index::{Discrepancy, TableInfo},
+};
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
+  #[clap(about = "Compact the index file to reclaim fragmented space")]
+  Compact,
   #[clap(about = "Write inscription numbers and ids to a tab-separated file")]
   Export(Export),
   #[clap(about = "Update the index")]
   Run,
+  #[clap(about = "Display index size statistics")]
+  Stats(Stats),
+  #[clap(about = "Check the index tables for consistency")]
+  Verify,
 }
 
 impl IndexSubcommand {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
+      Self::Compact => compact(options),
       Self::Export(export) => export.run(options),
       Self::Run => index::run(options),
+      Self::Stats(stats) => stats.run(options),
+      Self::Verify => verify(options),
     }
   }
 }
@@ -47,3 +59,87 @@ pub(crate) fn run(options: Options) -> Result {
 
   Ok(())
 }
+
+#[derive(Debug, Parser)]
+pub(crate) struct Stats {
+  #[clap(long, help = "Include row counts and approximate sizes for each table")]
+  tables: bool,
+}
+
+#[derive(Serialize)]
+struct StatsOutput {
+  index_file_size: u64,
+  fragmented_bytes: u64,
+  metadata_bytes: u64,
+  stored_bytes: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  tables: Option<Vec<TableInfo>>,
+}
+
+impl Stats {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+
+    let info = index.info()?;
+
+    print_json(StatsOutput {
+      index_file_size: info.index_file_size,
+      fragmented_bytes: info.fragmented_bytes,
+      metadata_bytes: info.metadata_bytes,
+      stored_bytes: info.stored_bytes,
+      tables: if self.tables {
+        Some(index.table_info()?)
+      } else {
+        None
+      },
+    })?;
+
+    Ok(())
+  }
+}
+
+#[derive(Serialize)]
+struct CompactOutput {
+  compacted: bool,
+  index_file_size_before: u64,
+  index_file_size_after: u64,
+}
+
+pub(crate) fn compact(options: Options) -> Result {
+  let mut index = Index::open(&options)?;
+
+  let index_file_size_before = index.info()?.index_file_size;
+
+  let compacted = index.compact()?;
+
+  let index_file_size_after = index.info()?.index_file_size;
+
+  print_json(CompactOutput {
+    compacted,
+    index_file_size_before,
+    index_file_size_after,
+  })?;
+
+  Ok(())
+}
+
+#[derive(Serialize)]
+struct VerifyOutput {
+  discrepancies: Vec<Discrepancy>,
+}
+
+pub(crate) fn verify(options: Options) -> Result {
+  let index = Index::open(&options)?;
+
+  let discrepancies = index.verify()?;
+
+  let count = discrepancies.len();
+
+  print_json(VerifyOutput { discrepancies })?;
+
+  if count > 0 {
+    bail!("index verification found {count} discrepancies");
+  }
+
+  Ok(())
+}
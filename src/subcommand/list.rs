@@ -61,9 +61,9 @@ fn list(outpoint: OutPoint, ranges: Vec<(u64, u64)>) -> Vec<Output> {
   let mut offset = 0;
   ranges
     .into_iter()
-    .map(|(start, end)| {
+    .filter_map(|(start, end)| {
       let size = end - start;
-      let output = Output {
+      let output = is_tracked_sat_range(&(start, end)).then(|| Output {
         output: outpoint,
         start,
         end,
@@ -71,7 +71,7 @@ fn list(outpoint: OutPoint, ranges: Vec<(u64, u64)>) -> Vec<Output> {
         offset,
         name: Sat(start).name(),
         rarity: Sat(start).rarity(),
-      };
+      });
 
       offset += size;
 
@@ -150,4 +150,24 @@ mod tests {
       ]
     )
   }
+
+  #[test]
+  fn list_skips_untracked_ranges() {
+    let outpoint =
+      OutPoint::from_str("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:5")
+        .unwrap();
+    let ranges = vec![(Sat::SUPPLY, Sat::SUPPLY + 100), (10, 100)];
+    assert_eq!(
+      list(outpoint, ranges),
+      vec![output(
+        outpoint,
+        10,
+        100,
+        90,
+        100,
+        Rarity::Common,
+        "nvtdijuwxlf".to_string()
+      )]
+    )
+  }
 }
@@ -87,6 +87,13 @@ impl Preview {
             dry_run: false,
             no_limit: false,
             destination: None,
+            metadata: None,
+            metaprotocol: None,
+            parent: None,
+            compress: false,
+            psbt_out: None,
+            policy: ContentPolicy::default(),
+            coin_control: Default::default(),
             un_safe:None,
           },
         )),
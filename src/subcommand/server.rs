@@ -4,8 +4,10 @@ use serde_json::json;
 
 use {
   self::{
+    accept_encoding::AcceptEncoding,
     deserialize_from_str::DeserializeFromStr,
     error::{OptionExt, ServerError, ServerResult},
+    metrics::Metrics,
   },
   super::*,
   crate::api::*,
@@ -20,9 +22,10 @@ use {
     extract::{Extension, Path, Query},
     headers::UserAgent,
     http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    middleware,
     response::{IntoResponse, Redirect, Response},
     routing::get,
-    Router, TypedHeader,
+    Json, Router, TypedHeader,
   },
   axum_server::Handle,
   rust_embed::RustEmbed,
@@ -32,7 +35,7 @@ use {
     caches::DirCache,
     AcmeConfig,
   },
-  std::{cmp::Ordering, str},
+  std::{borrow::Cow, cmp::Ordering, io::Read, str},
   tokio_stream::StreamExt,
   tower_http::{
     compression::CompressionLayer,
@@ -41,7 +44,9 @@ use {
   },
 };
 
+mod accept_encoding;
 mod error;
+mod metrics;
 
 enum BlockQuery {
   Height(u64),
@@ -71,6 +76,17 @@ struct Search {
   query: String,
 }
 
+#[derive(Deserialize)]
+struct HeightQuery {
+  height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct InscriptionsQuery {
+  metaprotocol: Option<String>,
+  from: Option<i64>,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -126,6 +142,8 @@ pub(crate) struct Server {
   https: bool,
   #[clap(long, help = "Redirect HTTP traffic to HTTPS.")]
   redirect_http_to_https: bool,
+  #[clap(long, help = "Serve Prometheus metrics at /metrics.")]
+  enable_metrics: bool,
 }
 
 impl Server {
@@ -151,7 +169,7 @@ impl Server {
         domain: acme_domains.first().cloned(),
       });
 
-      let router = Router::new()
+      let mut router = Router::new()
         .route("/", get(Self::home))
         .route("/block/:query", get(Self::block))
         .route("/blockcount", get(Self::block_count))
@@ -173,6 +191,15 @@ impl Server {
         .route("/ordinal/:sat", get(Self::ordinal))
         .route("/output/:output", get(Self::output))
         .route("/preview/:inscription_id", get(Self::preview))
+        .route("/r/blockhash/:height", get(Self::recursive_block_hash))
+        .route("/r/blockheight", get(Self::recursive_block_height))
+        .route("/r/blocktime", get(Self::recursive_block_time))
+        .route("/r/children/:inscription_id", get(Self::recursive_children))
+        .route(
+          "/r/metadata/:inscription_id",
+          get(Self::api_v1_inscription_metadata),
+        )
+        .route("/r/sat/:sat", get(Self::recursive_sat))
         .route("/range/:start/:end", get(Self::range))
         .route("/rare.txt", get(Self::rare_txt))
         .route("/sat/:sat", get(Self::sat))
@@ -187,6 +214,22 @@ impl Server {
         .route("/api/ins_content_type/:inscription_id", get(Self::api_inscription_content_type))
         .route("/api/inscription_total", get(Self::api_inscription_total))
         .route("/api/first_owner/:inscription_id", get(Self::api_first_owner))
+        .route("/api/v1/inscription/:inscription_id", get(Self::api_v1_inscription))
+        .route("/api/v1/inscriptions", get(Self::api_v1_inscriptions))
+        .route("/api/v1/tx/:txid/inscriptions", get(Self::api_v1_tx_inscriptions))
+        .route(
+          "/api/v1/inscription/:inscription_id/metadata",
+          get(Self::api_v1_inscription_metadata),
+        );
+
+      if self.enable_metrics {
+        router = router
+          .route("/metrics", get(Self::metrics))
+          .route_layer(middleware::from_fn(Metrics::track))
+          .layer(Extension(Arc::new(Metrics::default())));
+      }
+
+      let router = router
         .layer(Extension(index))
         .layer(Extension(page_config))
         .layer(Extension(Arc::new(config)))
@@ -446,6 +489,119 @@ impl Server {
     Ok(serde_json::to_vec(&data).unwrap().into_response())
   }
 
+  async fn api_v1_inscription(
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+    Query(query): Query<HeightQuery>,
+  ) -> ServerResult<Json<InscriptionDetail>> {
+    let entry = index
+      .get_inscription_entry(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    let inscription = index
+      .get_inscription_by_id(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    let index_height = index
+      .block_height()?
+      .ok_or_not_found(|| "index height")?
+      .n();
+
+    let (height, satpoint) = match query.height {
+      Some(height) => {
+        if height > index_height {
+          return Err(ServerError::BadRequest(format!(
+            "height {height} is above index height {index_height}"
+          )));
+        }
+
+        (
+          height,
+          index
+            .get_inscription_satpoint_at_height(inscription_id, height)?
+            .ok_or_not_found(|| format!("inscription {inscription_id} at height {height}"))?,
+        )
+      }
+      None => (
+        index_height,
+        index
+          .get_inscription_satpoint_by_id(inscription_id)?
+          .ok_or_not_found(|| format!("inscription {inscription_id}"))?,
+      ),
+    };
+
+    Ok(Json(InscriptionDetail {
+      inscription_id: inscription_id.to_string(),
+      number: entry.number,
+      genesis_height: entry.height,
+      genesis_fee: entry.fee,
+      timestamp: entry.timestamp,
+      content_type: inscription.content_type().map(str::to_string),
+      content_length: inscription.content_length(),
+      delegate: inscription.delegate().map(|delegate| delegate.to_string()),
+      metaprotocol: inscription.metaprotocol().map(str::to_string),
+      sat: entry.sat.map(Sat::n),
+      height,
+      satpoint: satpoint.to_string(),
+      address: index
+        .get_output_address(satpoint.outpoint)?
+        .map(|address| address.to_string()),
+    }))
+  }
+
+  /// List up to 100 inscriptions, optionally only those with `metaprotocol`,
+  /// newest first. Pages start at inscription number `from`, and `next` is the
+  /// number of the following, older page.
+  async fn api_v1_inscriptions(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<InscriptionsQuery>,
+  ) -> ServerResult<Json<InscriptionList>> {
+    let (inscriptions, next) = match query.metaprotocol {
+      Some(metaprotocol) => {
+        index.get_inscriptions_by_metaprotocol(&metaprotocol, 100, query.from)?
+      }
+      None => {
+        let (inscriptions, prev, _next) =
+          index.get_latest_inscriptions_with_prev_and_next(100, query.from)?;
+        (inscriptions, prev)
+      }
+    };
+
+    Ok(Json(InscriptionList {
+      inscriptions: inscriptions
+        .into_iter()
+        .map(|inscription_id| inscription_id.to_string())
+        .collect(),
+      next,
+    }))
+  }
+
+  async fn api_v1_tx_inscriptions(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+  ) -> ServerResult<Json<decode::Output>> {
+    let transaction = index
+      .get_transaction(txid)?
+      .ok_or_not_found(|| format!("transaction {txid}"))?;
+
+    Ok(Json(decode::decode(&transaction, Some(&index))?))
+  }
+
+  async fn api_v1_inscription_metadata(
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult<Json<serde_json::Value>> {
+    let inscription = index
+      .get_inscription_by_id(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    Ok(Json(
+      inscription
+        .metadata_json()
+        .ok_or_not_found(|| format!("inscription {inscription_id} metadata"))?,
+    ))
+  }
+
   async fn api_inscription_content_type(Extension(index): Extension<Arc<Index>>, Path(inscription_id): Path<InscriptionId>) -> ServerResult<Response> {
     
     let satpoint = index
@@ -779,6 +935,19 @@ impl Server {
     }
   }
 
+  async fn metrics(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(metrics): Extension<Arc<Metrics>>,
+  ) -> ServerResult<Response> {
+    Ok(
+      (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.render(&index)?,
+      )
+        .into_response(),
+    )
+  }
+
   async fn search_by_query(
     Extension(index): Extension<Arc<Index>>,
     Query(search): Query<Search>,
@@ -951,6 +1120,74 @@ impl Server {
     )
   }
 
+  async fn recursive_block_height(
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Json<u64>> {
+    Ok(Json(
+      index.block_height()?.ok_or_not_found(|| "blockheight")?.n(),
+    ))
+  }
+
+  async fn recursive_block_hash(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u64>,
+  ) -> ServerResult<Json<String>> {
+    Ok(Json(
+      index
+        .block_hash(Some(height))?
+        .ok_or_not_found(|| format!("block {height}"))?
+        .to_string(),
+    ))
+  }
+
+  async fn recursive_block_time(
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Json<i64>> {
+    Ok(Json(
+      index
+        .block_time(index.block_height()?.ok_or_not_found(|| "blocktime")?)?
+        .unix_timestamp(),
+    ))
+  }
+
+  async fn recursive_children(
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult<Json<InscriptionList>> {
+    index
+      .get_inscription_entry(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    Ok(Json(InscriptionList {
+      inscriptions: index
+        .get_children_by_inscription_id(inscription_id)?
+        .into_iter()
+        .map(|child| child.to_string())
+        .collect(),
+      next: None,
+    }))
+  }
+
+  async fn recursive_sat(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<Sat>>,
+  ) -> ServerResult<Json<InscriptionList>> {
+    if !index.has_sat_index()? {
+      return Err(ServerError::NotFound(
+        "this server has no sat index".to_string(),
+      ));
+    }
+
+    Ok(Json(InscriptionList {
+      inscriptions: index
+        .get_inscription_ids_by_sat(sat)?
+        .into_iter()
+        .map(|inscription_id| inscription_id.to_string())
+        .collect(),
+      next: None,
+    }))
+  }
+
   async fn input(
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -989,6 +1226,7 @@ impl Server {
     Extension(index): Extension<Arc<Index>>,
     Extension(config): Extension<Arc<Config>>,
     Path(inscription_id): Path<InscriptionId>,
+    accept_encoding: AcceptEncoding,
   ) -> ServerResult<Response> {
     if config.is_hidden(inscription_id) {
       return Ok(PreviewUnknownHtml.into_response());
@@ -998,14 +1236,62 @@ impl Server {
       .get_inscription_by_id(inscription_id)?
       .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
+    let Some(inscription) = Self::resolve_delegate(&index, &config, inscription)? else {
+      return Ok(PreviewUnknownHtml.into_response());
+    };
+
     Ok(
-      Self::content_response(inscription)
+      Self::content_response(inscription, accept_encoding)?
         .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
         .into_response(),
     )
   }
 
-  fn content_response(inscription: Inscription) -> Option<(HeaderMap, Vec<u8>)> {
+  /// Resolve `inscription` to its delegate, if it has one. Returns `None` if
+  /// the delegate is hidden, so that it can't be served through inscriptions
+  /// delegating to it.
+  fn resolve_delegate(
+    index: &Index,
+    config: &Config,
+    inscription: Inscription,
+  ) -> ServerResult<Option<Inscription>> {
+    match inscription.delegate() {
+      Some(delegate) if config.is_hidden(delegate) => Ok(None),
+      Some(delegate) => Ok(Some(
+        index
+          .get_inscription_by_id(delegate)?
+          .ok_or_not_found(|| format!("delegate {delegate}"))?,
+      )),
+      None => Ok(Some(inscription)),
+    }
+  }
+
+  fn decompress(content_encoding: &str, body: &[u8]) -> Result<Option<Vec<u8>>> {
+    const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+    let reader: Box<dyn Read + '_> = match content_encoding {
+      "br" => Box::new(brotli::Decompressor::new(body, 4096)),
+      "gzip" => Box::new(flate2::read::GzDecoder::new(body)),
+      "identity" => return Ok(Some(body.to_vec())),
+      _ => return Ok(None),
+    };
+
+    let mut decompressed = Vec::new();
+    reader
+      .take(MAX_DECOMPRESSED_SIZE + 1)
+      .read_to_end(&mut decompressed)?;
+
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_SIZE {
+      bail!("decompressed content exceeds {MAX_DECOMPRESSED_SIZE} bytes");
+    }
+
+    Ok(Some(decompressed))
+  }
+
+  fn content_response(
+    inscription: Inscription,
+    accept_encoding: AcceptEncoding,
+  ) -> ServerResult<Option<(HeaderMap, Vec<u8>)>> {
     let mut headers = HeaderMap::new();
 
     headers.insert(
@@ -1022,9 +1308,11 @@ impl Server {
     );
     headers.append(
       header::CONTENT_SECURITY_POLICY,
-      HeaderValue::from_static("default-src *:*/content/ *:*/blockheight *:*/blockhash *:*/blockhash/ *:*/sat/ *:*/blocktime 'unsafe-eval' 'unsafe-inline' data:"),
+      HeaderValue::from_static("default-src *:*/content/ *:*/blockheight *:*/blockhash *:*/blockhash/ *:*/sat/ *:*/blocktime *:*/r/ 'unsafe-eval' 'unsafe-inline' data:"),
     );
 
+    let content_encoding = inscription.content_encoding().map(str::to_string);
+
     let body = inscription.into_body();
     let cache_control = match body {
       Some(_) => "max-age=31536000, immutable",
@@ -1035,13 +1323,37 @@ impl Server {
       HeaderValue::from_str(cache_control).unwrap(),
     );
 
-    Some((headers, body?))
+    let Some(mut body) = body else {
+      return Ok(None);
+    };
+
+    if let Some(content_encoding) = content_encoding {
+      headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+      match HeaderValue::from_str(&content_encoding) {
+        Ok(value) if accept_encoding.is_acceptable(&content_encoding) => {
+          headers.insert(header::CONTENT_ENCODING, value);
+        }
+        _ => match Self::decompress(&content_encoding, &body)? {
+          Some(decompressed) => body = decompressed,
+          None => {
+            return Err(ServerError::NotAcceptable {
+              accept_encoding,
+              content_encoding,
+            })
+          }
+        },
+      }
+    }
+
+    Ok(Some((headers, body)))
   }
 
   async fn preview(
     Extension(index): Extension<Arc<Index>>,
     Extension(config): Extension<Arc<Config>>,
     Path(inscription_id): Path<InscriptionId>,
+    accept_encoding: AcceptEncoding,
   ) -> ServerResult<Response> {
     if config.is_hidden(inscription_id) {
       return Ok(PreviewUnknownHtml.into_response());
@@ -1051,10 +1363,14 @@ impl Server {
       .get_inscription_by_id(inscription_id)?
       .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
+    let Some(inscription) = Self::resolve_delegate(&index, &config, inscription)? else {
+      return Ok(PreviewUnknownHtml.into_response());
+    };
+
     match inscription.media() {
       Media::Audio => Ok(PreviewAudioHtml { inscription_id }.into_response()),
       Media::Iframe => Ok(
-        Self::content_response(inscription)
+        Self::content_response(inscription, accept_encoding)?
           .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
           .into_response(),
       ),
@@ -1079,12 +1395,21 @@ impl Server {
           .into_response(),
       ),
       Media::Text => {
-        let content = inscription
+        let body = inscription
           .body()
           .ok_or_not_found(|| format!("inscription {inscription_id} content"))?;
+
+        let content = match inscription.content_encoding() {
+          Some(content_encoding) => match Self::decompress(content_encoding, body)? {
+            Some(decompressed) => Cow::Owned(decompressed),
+            None => return Ok(PreviewUnknownHtml.into_response()),
+          },
+          None => Cow::Borrowed(body),
+        };
+
         Ok(
           PreviewTextHtml {
-            text: str::from_utf8(content)
+            text: str::from_utf8(&content)
               .map_err(|err| anyhow!("Failed to decode {inscription_id} text: {err}"))?,
           }
           .into_response(),
@@ -1130,9 +1455,23 @@ impl Server {
 
     let next = index.get_inscription_id_by_inscription_number(entry.number + 1)?;
 
+    let children = index.get_children_by_inscription_id(inscription_id)?;
+
+    let parent = match inscription.parent() {
+      Some(parent)
+        if index
+          .get_children_by_inscription_id(parent)?
+          .contains(&inscription_id) =>
+      {
+        Some(parent)
+      }
+      _ => None,
+    };
+
     Ok(
       InscriptionHtml {
         chain: page_config.chain,
+        children,
         genesis_fee: entry.fee,
         genesis_height: entry.height,
         inscription,
@@ -1140,6 +1479,7 @@ impl Server {
         next,
         number: entry.number,
         output,
+        parent,
         previous,
         sat: entry.sat,
         satpoint,
@@ -1194,7 +1534,7 @@ impl Server {
 
 #[cfg(test)]
 mod tests {
-  use {super::*, reqwest::Url, std::net::TcpListener};
+  use {super::*, reqwest::Url, std::io::Write, std::net::TcpListener};
 
   struct TestServer {
     bitcoin_rpc_server: test_bitcoincore_rpc::Handle,
@@ -1677,6 +2017,36 @@ mod tests {
     TestServer::new().assert_response("/status", StatusCode::OK, "OK");
   }
 
+  #[test]
+  fn metrics_are_disabled_by_default() {
+    TestServer::new().assert_response("/metrics", StatusCode::NOT_FOUND, "");
+  }
+
+  #[test]
+  fn metrics() {
+    let test_server = TestServer::new_with_args(&[], &["--enable-metrics"]);
+
+    test_server.assert_response("/blockcount", StatusCode::OK, "1");
+    test_server.assert_response("/api/inscription_total", StatusCode::OK, r#"{"total":0}"#);
+
+    test_server.assert_response_regex(
+      "/metrics",
+      StatusCode::OK,
+      r#".*
+ord_index_height 1
+.*
+ord_index_tip_lag 0
+.*
+ord_index_statistic\{statistic="commits"\} 1
+.*
+ord_http_requests_total\{kind="api",route="/api/inscription_total",status="200"\} 1
+ord_http_requests_total\{kind="html",route="/blockcount",status="200"\} 1
+.*
+ord_http_request_duration_seconds_count\{kind="html",route="/blockcount",status="200"\} 1
+.*"#,
+    );
+  }
+
   #[test]
   fn block_count_endpoint() {
     let test_server = TestServer::new();
@@ -1747,6 +2117,195 @@ mod tests {
     assert_eq!(response.text().unwrap(), "1231006505");
   }
 
+  #[test]
+  fn recursive_block_endpoints() {
+    let test_server = TestServer::new();
+
+    test_server.assert_response("/r/blockheight", StatusCode::OK, "0");
+    test_server.assert_response(
+      "/r/blockhash/0",
+      StatusCode::OK,
+      "\"000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f\"",
+    );
+    test_server.assert_response("/r/blocktime", StatusCode::OK, "1231006505");
+    test_server.assert_response("/r/blockhash/1", StatusCode::NOT_FOUND, "block 1 not found");
+
+    test_server.mine_blocks(2);
+
+    test_server.assert_response("/r/blockheight", StatusCode::OK, "2");
+    test_server.assert_response_regex("/r/blockhash/1", StatusCode::OK, r#""[[:xdigit:]]{64}""#);
+  }
+
+  #[test]
+  fn recursive_children() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let parent = InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain", "parent").to_witness(),
+      ..Default::default()
+    }));
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/r/children/{parent}"),
+      StatusCode::OK,
+      r#"{"inscriptions":[]}"#,
+    );
+
+    let child = InscriptionId::from(
+      server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0), (2, 1, 0)],
+        witness: inscription("text/plain", "child")
+          .with_parent(parent)
+          .to_witness(),
+        ..Default::default()
+      }),
+    );
+
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      format!("/r/children/{parent}"),
+      StatusCode::OK,
+      format!(r#"\{{"inscriptions":\[.*"{child}".*\]\}}"#),
+    );
+
+    server.assert_response(
+      format!("/r/children/{child}"),
+      StatusCode::OK,
+      r#"{"inscriptions":[]}"#,
+    );
+
+    let unknown = InscriptionId::from(txid(1));
+
+    server.assert_response(
+      format!("/r/children/{unknown}"),
+      StatusCode::NOT_FOUND,
+      &format!("inscription {unknown} not found"),
+    );
+  }
+
+  #[test]
+  fn recursive_metadata() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let mut metadata = Vec::new();
+    ciborium::into_writer(&serde_json::json!({"name": "foo"}), &mut metadata).unwrap();
+
+    let inscription_id = InscriptionId::from(
+      server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain;charset=utf-8", "hello")
+          .with_metadata(Some(metadata))
+          .to_witness(),
+        ..Default::default()
+      }),
+    );
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/r/metadata/{inscription_id}"),
+      StatusCode::OK,
+      r#"{"name":"foo"}"#,
+    );
+  }
+
+  #[test]
+  fn recursive_sat() {
+    let server = TestServer::new_with_regtest_with_index_sats();
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/r/sat/5000000000",
+      StatusCode::OK,
+      r#"{"inscriptions":[]}"#,
+    );
+
+    let inscription_id =
+      InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      }));
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/r/sat/5000000000",
+      StatusCode::OK,
+      &format!(r#"{{"inscriptions":["{inscription_id}"]}}"#),
+    );
+  }
+
+  #[test]
+  fn recursive_sat_requires_sat_index() {
+    TestServer::new().assert_response(
+      "/r/sat/0",
+      StatusCode::NOT_FOUND,
+      "this server has no sat index",
+    );
+  }
+
+  #[test]
+  fn recursive_endpoints_are_allowed_by_content_security_policy() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId::from(
+      server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription(
+          "text/html;charset=utf-8",
+          "<script>fetch('/r/blockheight')</script>",
+        )
+        .to_witness(),
+        ..Default::default()
+      }),
+    );
+
+    server.mine_blocks(1);
+
+    for path in [
+      format!("/content/{inscription_id}"),
+      format!("/preview/{inscription_id}"),
+    ] {
+      let response = server.get(&path);
+      assert_eq!(response.status(), StatusCode::OK);
+
+      let csps = response
+        .headers()
+        .get_all(header::CONTENT_SECURITY_POLICY)
+        .iter()
+        .map(|csp| csp.to_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+
+      // browsers enforce every policy, so each one must allow `/r/` fetches
+      assert!(
+        csps.iter().any(|csp| csp.contains(" *:*/r/ ")),
+        "{path}: {csps:?}"
+      );
+      assert!(
+        csps
+          .iter()
+          .any(|csp| csp.starts_with("default-src 'self' ")),
+        "{path}: {csps:?}"
+      );
+      assert!(
+        csps
+          .iter()
+          .all(|csp| csp.starts_with("default-src 'self' ") || csp.contains(" *:*/r/ ")),
+        "{path}: {csps:?}"
+      );
+    }
+
+    server.assert_response("/r/blockheight", StatusCode::OK, "2");
+  }
+
   #[test]
   fn range_end_before_range_start_returns_400() {
     TestServer::new().assert_response(
@@ -2365,20 +2924,22 @@ mod tests {
   #[test]
   fn content_response_no_content() {
     assert_eq!(
-      Server::content_response(Inscription::new(
-        Some("text/plain".as_bytes().to_vec()),
-        None
-      )),
+      Server::content_response(
+        Inscription::new(Some("text/plain".as_bytes().to_vec()), None),
+        AcceptEncoding::default()
+      )
+      .unwrap(),
       None
     );
   }
 
   #[test]
   fn content_response_with_content() {
-    let (headers, body) = Server::content_response(Inscription::new(
-      Some("text/plain".as_bytes().to_vec()),
-      Some(vec![1, 2, 3]),
-    ))
+    let (headers, body) = Server::content_response(
+      Inscription::new(Some("text/plain".as_bytes().to_vec()), Some(vec![1, 2, 3])),
+      AcceptEncoding::default(),
+    )
+    .unwrap()
     .unwrap();
 
     assert_eq!(headers["content-type"], "text/plain");
@@ -2387,8 +2948,12 @@ mod tests {
 
   #[test]
   fn content_response_no_content_type() {
-    let (headers, body) =
-      Server::content_response(Inscription::new(None, Some(Vec::new()))).unwrap();
+    let (headers, body) = Server::content_response(
+      Inscription::new(None, Some(Vec::new())),
+      AcceptEncoding::default(),
+    )
+    .unwrap()
+    .unwrap();
 
     assert_eq!(headers["content-type"], "application/octet-stream");
     assert!(body.is_empty());
@@ -2871,4 +3436,524 @@ mod tests {
       &fs::read_to_string("templates/preview-unknown.html").unwrap(),
     );
   }
+
+  #[test]
+  fn api_v1_inscription_at_height() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    });
+    let inscription_id = InscriptionId::from(txid);
+
+    server.mine_blocks(1);
+
+    let send_txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0)],
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let detail = |path: String| {
+      let response = server.get(path);
+      assert_eq!(response.status(), StatusCode::OK);
+      serde_json::from_str::<InscriptionDetail>(&response.text().unwrap()).unwrap()
+    };
+
+    let current = detail(format!("/api/v1/inscription/{inscription_id}"));
+    assert_eq!(current.height, 3);
+    assert_eq!(current.satpoint, format!("{send_txid}:0:0"));
+    assert_eq!(current.genesis_height, 2);
+    assert_eq!(
+      current.content_type,
+      Some("text/plain;charset=utf-8".into())
+    );
+
+    let historical = detail(format!("/api/v1/inscription/{inscription_id}?height=2"));
+    assert_eq!(historical.height, 2);
+    assert_eq!(historical.satpoint, format!("{txid}:0:0"));
+
+    server.assert_response(
+      format!("/api/v1/inscription/{inscription_id}?height=1"),
+      StatusCode::NOT_FOUND,
+      &format!("inscription {inscription_id} at height 1 not found"),
+    );
+
+    server.assert_response(
+      format!("/api/v1/inscription/{inscription_id}?height=4"),
+      StatusCode::BAD_REQUEST,
+      "height 4 is above index height 3",
+    );
+  }
+
+  #[test]
+  fn inscription_metadata() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let mut metadata = Vec::new();
+    ciborium::into_writer(&serde_json::json!({"name": "foo"}), &mut metadata).unwrap();
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello")
+        .with_metadata(Some(metadata))
+        .to_witness(),
+      ..Default::default()
+    });
+    let inscription_id = InscriptionId::from(txid);
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/api/v1/inscription/{inscription_id}/metadata"),
+      StatusCode::OK,
+      r#"{"name":"foo"}"#,
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{inscription_id}"),
+      StatusCode::OK,
+      r#".*<dt>metadata</dt>
+  <dd><pre class=monospace>\{
+  &quot;name&quot;: &quot;foo&quot;
+\}</pre></dd>.*"#,
+    );
+  }
+
+  #[test]
+  fn inscription_without_metadata() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    });
+    let inscription_id = InscriptionId::from(txid);
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/api/v1/inscription/{inscription_id}/metadata"),
+      StatusCode::NOT_FOUND,
+      &format!("inscription {inscription_id} metadata not found"),
+    );
+  }
+
+  #[test]
+  fn inscriptions_can_be_filtered_by_metaprotocol() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello")
+        .with_metaprotocol("foo")
+        .to_witness(),
+      ..Default::default()
+    });
+    let foo = InscriptionId::from(txid);
+
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    });
+    let other = InscriptionId::from(txid);
+
+    server.mine_blocks(1);
+
+    let list = |path: &str| {
+      let response = server.get(path);
+      assert_eq!(response.status(), StatusCode::OK);
+      serde_json::from_str::<InscriptionList>(&response.text().unwrap())
+        .unwrap()
+        .inscriptions
+    };
+
+    assert_eq!(
+      list("/api/v1/inscriptions?metaprotocol=foo"),
+      [foo.to_string()]
+    );
+    assert_eq!(
+      list("/api/v1/inscriptions?metaprotocol=bar"),
+      Vec::<String>::new()
+    );
+    assert_eq!(
+      list("/api/v1/inscriptions"),
+      [other.to_string(), foo.to_string()]
+    );
+
+    assert_eq!(
+      serde_json::from_str::<InscriptionDetail>(
+        &server
+          .get(format!("/api/v1/inscription/{foo}"))
+          .text()
+          .unwrap()
+      )
+      .unwrap()
+      .metaprotocol,
+      Some("foo".into())
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{foo}"),
+      StatusCode::OK,
+      ".*<dt>metaprotocol</dt>\n  <dd>foo</dd>.*",
+    );
+  }
+
+  #[test]
+  fn delegate_content_is_served() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
+
+    let delegate =
+      InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+        ..Default::default()
+      }));
+
+    server.mine_blocks(1);
+
+    let inscription_id =
+      InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0)],
+        witness: Inscription::from_delegate(delegate, None).to_witness(),
+        ..Default::default()
+      }));
+
+    server.mine_blocks(1);
+
+    let response = server.get(format!("/content/{inscription_id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.text().unwrap(), "hello");
+
+    server.assert_response_regex(
+      format!("/preview/{inscription_id}"),
+      StatusCode::OK,
+      ".*<pre>hello</pre>.*",
+    );
+
+    assert_eq!(
+      serde_json::from_str::<InscriptionDetail>(
+        &server
+          .get(format!("/api/v1/inscription/{inscription_id}"))
+          .text()
+          .unwrap()
+      )
+      .unwrap()
+      .delegate,
+      Some(delegate.to_string())
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{inscription_id}"),
+      StatusCode::OK,
+      format!(".*<dt>delegate</dt>\n  <dd><a class=monospace href=/inscription/{delegate}>{delegate}</a></dd>.*"),
+    );
+  }
+
+  #[test]
+  fn hidden_delegate_is_not_served_through_delegating_inscription() {
+    let bitcoin_rpc_server = test_bitcoincore_rpc::builder()
+      .network(bitcoin::Network::Regtest)
+      .build();
+    bitcoin_rpc_server.mine_blocks(2);
+
+    let delegate = InscriptionId::from(bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    }));
+
+    bitcoin_rpc_server.mine_blocks(1);
+
+    let inscription_id =
+      InscriptionId::from(bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0)],
+        witness: Inscription::from_delegate(delegate, None).to_witness(),
+        ..Default::default()
+      }));
+
+    bitcoin_rpc_server.mine_blocks(1);
+
+    let server = TestServer::new_server(
+      bitcoin_rpc_server,
+      Some(format!("\"hidden\":\n - {delegate}")),
+      &["--chain", "regtest"],
+      &[],
+    );
+
+    for path in ["content", "preview"] {
+      server.assert_response(
+        format!("/{path}/{inscription_id}"),
+        StatusCode::OK,
+        &fs::read_to_string("templates/preview-unknown.html").unwrap(),
+      );
+    }
+  }
+
+  #[test]
+  fn missing_delegate_is_not_found() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let delegate = InscriptionId::from(txid(1));
+
+    let inscription_id =
+      InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: Inscription::from_delegate(delegate, None).to_witness(),
+        ..Default::default()
+      }));
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/content/{inscription_id}"),
+      StatusCode::NOT_FOUND,
+      &format!("delegate {delegate} not found"),
+    );
+  }
+
+  #[test]
+  fn tx_inscriptions_reports_curses_and_unbound_envelopes() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
+
+    let mut witness = Witness::new();
+    witness.push(Inscription::append_batch_reveal_script(
+      &[
+        inscription("text/plain", "foo"),
+        inscription("text/plain", "bar"),
+      ],
+      script::Builder::new(),
+    ));
+    witness.push([]);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0), (2, 0, 0)],
+      witness,
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = server.get(format!("/api/v1/tx/{txid}/inscriptions"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let output = serde_json::from_str::<decode::Output>(&response.text().unwrap()).unwrap();
+
+    assert_eq!(output.txid, txid);
+
+    assert_eq!(
+      output
+        .envelopes
+        .iter()
+        .map(|envelope| (
+          envelope.input,
+          envelope.offset,
+          envelope.inscription_id.clone().unwrap(),
+          envelope.curse.clone(),
+          envelope.unbound,
+        ))
+        .collect::<Vec<(u32, u32, String, Option<String>, Option<bool>)>>(),
+      [
+        (0, 0, format!("{txid}i0"), None, Some(false)),
+        (
+          0,
+          1,
+          format!("{txid}i1"),
+          Some("not at offset zero".into()),
+          Some(true)
+        ),
+        (
+          1,
+          0,
+          format!("{txid}i2"),
+          Some("not in first input".into()),
+          Some(false)
+        ),
+        (
+          1,
+          1,
+          format!("{txid}i3"),
+          Some("not in first input".into()),
+          Some(true)
+        ),
+      ]
+    );
+
+    assert_eq!(output.envelopes[0].number, Some(0));
+
+    for envelope in &output.envelopes[1..] {
+      assert!(envelope.number.unwrap() < 0);
+    }
+  }
+
+  #[test]
+  fn tx_inscriptions_reports_invalid_envelopes() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let mut witness = Witness::new();
+    witness.push(
+      script::Builder::new()
+        .push_opcode(bitcoin::opcodes::OP_FALSE)
+        .push_opcode(bitcoin::opcodes::all::OP_IF)
+        .push_slice(b"ord")
+        .push_slice([4])
+        .push_slice([0])
+        .push_opcode(bitcoin::opcodes::all::OP_ENDIF)
+        .into_script(),
+    );
+    witness.push([]);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness,
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = server.get(format!("/api/v1/tx/{txid}/inscriptions"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let output = serde_json::from_str::<decode::Output>(&response.text().unwrap()).unwrap();
+
+    assert_eq!(output.envelopes.len(), 1);
+    assert!(!output.envelopes[0].valid);
+    assert_eq!(
+      output.envelopes[0].error,
+      Some("unrecognized even field".into())
+    );
+    assert_eq!(output.envelopes[0].inscription_id, None);
+  }
+
+  #[test]
+  fn tx_inscriptions_not_found() {
+    let server = TestServer::new_with_regtest();
+
+    let txid = txid(1);
+
+    server.assert_response(
+      format!("/api/v1/tx/{txid}/inscriptions"),
+      StatusCode::NOT_FOUND,
+      &format!("transaction {txid} not found"),
+    );
+  }
+
+  fn brotli(content: &str) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    brotli::CompressorWriter::new(&mut compressed, 4096, 11, 24)
+      .write_all(content.as_bytes())
+      .unwrap();
+    compressed
+  }
+
+  #[test]
+  fn content_encoding_is_served_if_accepted() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let body = brotli("hello");
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: Inscription::new(Some("text/plain".into()), Some(body.clone()))
+        .with_content_encoding("br")
+        .to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = reqwest::blocking::Client::new()
+      .get(server.join_url(&format!("/content/{}", InscriptionId::from(txid))))
+      .header(header::ACCEPT_ENCODING, "gzip, br")
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_ENCODING).unwrap(),
+      "br"
+    );
+    assert_eq!(response.bytes().unwrap(), body);
+  }
+
+  #[test]
+  fn content_is_decompressed_if_encoding_is_not_accepted() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: Inscription::new(
+        Some("text/plain;charset=utf-8".into()),
+        Some(brotli("hello")),
+      )
+      .with_content_encoding("br")
+      .to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId::from(txid);
+
+    let response = server.get(format!("/content/{inscription_id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(header::CONTENT_ENCODING), None);
+    assert_eq!(response.text().unwrap(), "hello");
+
+    server.assert_response_regex(
+      format!("/preview/{inscription_id}"),
+      StatusCode::OK,
+      ".*<pre>hello</pre>.*",
+    );
+  }
+
+  #[test]
+  fn unknown_content_encoding_is_not_acceptable() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: Inscription::new(Some("text/plain".into()), Some(b"hello".to_vec()))
+        .with_content_encoding("foo")
+        .to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = reqwest::blocking::Client::new()
+      .get(server.join_url(&format!("/content/{}", InscriptionId::from(txid))))
+      .header(header::ACCEPT_ENCODING, "identity")
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(
+      response.text().unwrap(),
+      "inscription content encoding `foo` is not acceptable. `Accept-Encoding` header: `identity`"
+    );
+  }
 }
@@ -0,0 +1,99 @@
+use {
+  super::*,
+  axum::{async_trait, extract::FromRequestParts, http::request::Parts},
+};
+
+#[derive(Default, Debug)]
+pub(super) struct AcceptEncoding(Option<String>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AcceptEncoding
+where
+  S: Send + Sync,
+{
+  type Rejection = ServerError;
+
+  async fn from_request_parts(parts: &mut Parts, _state: &S) -> ServerResult<Self> {
+    let values = parts
+      .headers
+      .get_all(header::ACCEPT_ENCODING)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .collect::<Vec<&str>>();
+
+    Ok(Self(if values.is_empty() {
+      None
+    } else {
+      Some(values.join(","))
+    }))
+  }
+}
+
+impl Display for AcceptEncoding {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}", self.0.as_deref().unwrap_or_default())
+  }
+}
+
+impl AcceptEncoding {
+  pub(super) fn is_acceptable(&self, encoding: &str) -> bool {
+    let mut wildcard = None;
+
+    for item in self.0.as_deref().unwrap_or_default().split(',') {
+      let mut params = item.split(';');
+
+      let coding = params.next().unwrap_or_default().trim();
+
+      let quality = params
+        .find_map(|param| param.trim().strip_prefix("q="))
+        .map(|quality| quality.trim().parse::<f32>().unwrap_or_default())
+        .unwrap_or(1.0);
+
+      if coding.eq_ignore_ascii_case(encoding) {
+        return quality > 0.0;
+      }
+
+      if coding == "*" {
+        wildcard = Some(quality > 0.0);
+      }
+    }
+
+    wildcard.unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn accept_encoding(value: &str) -> AcceptEncoding {
+    AcceptEncoding(Some(value.into()))
+  }
+
+  #[test]
+  fn missing_header_accepts_nothing() {
+    assert!(!AcceptEncoding::default().is_acceptable("br"));
+  }
+
+  #[test]
+  fn listed_encodings_are_acceptable() {
+    assert!(accept_encoding("gzip, deflate, br").is_acceptable("br"));
+    assert!(accept_encoding("gzip, deflate, br").is_acceptable("gzip"));
+    assert!(!accept_encoding("gzip, deflate").is_acceptable("br"));
+    assert!(accept_encoding("BR").is_acceptable("br"));
+  }
+
+  #[test]
+  fn zero_quality_is_not_acceptable() {
+    assert!(!accept_encoding("br;q=0").is_acceptable("br"));
+    assert!(accept_encoding("br;q=0.5").is_acceptable("br"));
+    assert!(!accept_encoding("*, br;q=0").is_acceptable("br"));
+  }
+
+  #[test]
+  fn wildcard_is_acceptable() {
+    assert!(accept_encoding("*").is_acceptable("br"));
+    assert!(!accept_encoding("*;q=0").is_acceptable("br"));
+    assert!(accept_encoding("*;q=0, br").is_acceptable("br"));
+  }
+}
@@ -1,8 +1,13 @@
 use super::*;
 
+#[derive(Debug)]
 pub(super) enum ServerError {
   Internal(Error),
   BadRequest(String),
+  NotAcceptable {
+    accept_encoding: AcceptEncoding,
+    content_encoding: String,
+  },
   NotFound(String),
 }
 
@@ -21,6 +26,17 @@ impl IntoResponse for ServerError {
         )
           .into_response()
       }
+      Self::NotAcceptable {
+        accept_encoding,
+        content_encoding,
+      } => (
+        StatusCode::NOT_ACCEPTABLE,
+        format!(
+          "inscription content encoding `{content_encoding}` is not acceptable. \
+          `Accept-Encoding` header: `{accept_encoding}`"
+        ),
+      )
+        .into_response(),
       Self::NotFound(message) => (StatusCode::NOT_FOUND, message).into_response(),
       Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
     }
@@ -0,0 +1,154 @@
+use {
+  super::*,
+  axum::{extract::MatchedPath, http::Request, middleware::Next},
+  std::fmt::Write as _,
+};
+
+#[derive(Default)]
+struct RouteStats {
+  count: u64,
+  seconds: f64,
+}
+
+#[derive(Default)]
+pub(super) struct Metrics {
+  routes: Mutex<BTreeMap<(&'static str, String, u16), RouteStats>>,
+}
+
+impl Metrics {
+  pub(super) async fn track<B>(
+    Extension(metrics): Extension<Arc<Metrics>>,
+    request: Request<B>,
+    next: Next<B>,
+  ) -> Response {
+    let route = request
+      .extensions()
+      .get::<MatchedPath>()
+      .map(|path| path.as_str().to_string())
+      .unwrap_or_else(|| request.uri().path().to_string());
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = start.elapsed();
+
+    let kind = if route.starts_with("/api/") {
+      "api"
+    } else {
+      "html"
+    };
+
+    let mut routes = metrics.routes.lock().unwrap();
+    let stats = routes
+      .entry((kind, route, response.status().as_u16()))
+      .or_default();
+    stats.count += 1;
+    stats.seconds += elapsed.as_secs_f64();
+
+    response
+  }
+
+  pub(super) fn render(&self, index: &Index) -> Result<String> {
+    let index_metrics = index.metrics()?;
+
+    let mut output = String::new();
+
+    Self::gauge(
+      &mut output,
+      "ord_index_height",
+      "Number of blocks in the index.",
+      index_metrics.blocks_indexed,
+    );
+    Self::gauge(
+      &mut output,
+      "ord_index_tip_lag",
+      "Number of blocks Bitcoin Core is ahead of the index.",
+      index_metrics
+        .chain_blocks
+        .saturating_sub(index_metrics.blocks_indexed),
+    );
+    Self::gauge(
+      &mut output,
+      "ord_index_blocks_per_second",
+      "Blocks per second indexed during the last update that indexed blocks.",
+      index_metrics.blocks_per_second,
+    );
+    Self::counter(
+      &mut output,
+      "ord_index_blocks_indexed_total",
+      "Blocks indexed since the server started.",
+      index_metrics.blocks_indexed_total,
+    );
+    Self::gauge(
+      &mut output,
+      "ord_index_last_commit_duration_seconds",
+      "Duration of the last index commit.",
+      index_metrics.last_commit_duration.as_secs_f64(),
+    );
+    Self::gauge(
+      &mut output,
+      "ord_index_file_size_bytes",
+      "Size of the index file.",
+      index_metrics.index_file_size,
+    );
+
+    writeln!(
+      output,
+      "# HELP ord_index_statistic Index statistic counters.\n# TYPE ord_index_statistic gauge"
+    )?;
+    for (statistic, value) in index_metrics.statistics {
+      writeln!(
+        output,
+        "ord_index_statistic{{statistic=\"{statistic}\"}} {value}"
+      )?;
+    }
+
+    let routes = self.routes.lock().unwrap();
+
+    writeln!(
+      output,
+      "# HELP ord_http_requests_total Requests served by route.\n# TYPE ord_http_requests_total counter"
+    )?;
+    for ((kind, route, status), stats) in routes.iter() {
+      writeln!(
+        output,
+        "ord_http_requests_total{{kind=\"{kind}\",route=\"{route}\",status=\"{status}\"}} {}",
+        stats.count
+      )?;
+    }
+
+    writeln!(
+      output,
+      "# HELP ord_http_request_duration_seconds Time spent serving requests by route.\n# TYPE ord_http_request_duration_seconds summary"
+    )?;
+    for ((kind, route, status), stats) in routes.iter() {
+      writeln!(
+        output,
+        "ord_http_request_duration_seconds_sum{{kind=\"{kind}\",route=\"{route}\",status=\"{status}\"}} {}",
+        stats.seconds
+      )?;
+      writeln!(
+        output,
+        "ord_http_request_duration_seconds_count{{kind=\"{kind}\",route=\"{route}\",status=\"{status}\"}} {}",
+        stats.count
+      )?;
+    }
+
+    Ok(output)
+  }
+
+  fn counter(output: &mut String, name: &str, help: &str, value: impl Display) {
+    Self::metric(output, name, "counter", help, value);
+  }
+
+  fn gauge(output: &mut String, name: &str, help: &str, value: impl Display) {
+    Self::metric(output, name, "gauge", help, value);
+  }
+
+  fn metric(output: &mut String, name: &str, kind: &str, help: &str, value: impl Display) {
+    writeln!(
+      output,
+      "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}"
+    )
+    .unwrap();
+  }
+}
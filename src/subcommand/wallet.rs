@@ -9,22 +9,29 @@ use {
     Network,
   },
   bitcoincore_rpc::bitcoincore_rpc_json::{ImportDescriptors, Timestamp},
+  coin_control::{CoinControl, CoinSelection},
   fee_rate::FeeRate,
   miniscript::descriptor::{Descriptor, DescriptorSecretKey, DescriptorXKey, Wildcard},
   transaction_builder::TransactionBuilder,
 };
 
 pub mod balance;
+pub mod broadcast;
+pub mod bump_fee;
 pub mod cardinals;
+pub(crate) mod coin_control;
 pub mod create;
 pub(crate) mod inscribe;
 pub(crate) mod inscribes;
 pub mod inscriptions;
+mod key_journal;
 pub mod outputs;
 pub mod receive;
+pub mod recover_commit;
 mod restore;
 pub mod sats;
 pub mod send;
+pub mod send_many;
 pub(crate) mod transaction_builder;
 pub mod transactions;
 
@@ -32,6 +39,10 @@ pub mod transactions;
 pub(crate) enum Wallet {
   #[clap(about = "Get wallet balance")]
   Balance,
+  #[clap(about = "Broadcast externally signed PSBT")]
+  Broadcast(broadcast::Broadcast),
+  #[clap(about = "Bump fee of stuck commit or reveal transaction")]
+  BumpFee(bump_fee::BumpFee),
   #[clap(about = "Create new wallet")]
   Create(create::Create),
   #[clap(about = "Create inscription")]
@@ -42,16 +53,20 @@ pub(crate) enum Wallet {
   Inscriptions,
   #[clap(about = "Generate receive address")]
   Receive,
+  #[clap(about = "Sweep stuck commit transaction outputs back to wallet")]
+  RecoverCommit(recover_commit::RecoverCommit),
   #[clap(about = "Restore wallet")]
   Restore(restore::Restore),
   #[clap(about = "List wallet satoshis")]
   Sats(sats::Sats),
   #[clap(about = "Send sat or inscription")]
   Send(send::Send),
+  #[clap(about = "Send multiple sats or inscriptions in one transaction")]
+  SendMany(send_many::SendMany),
   #[clap(about = "See wallet transactions")]
   Transactions(transactions::Transactions),
   #[clap(about = "List all unspent outputs in wallet")]
-  Outputs,
+  Outputs(outputs::Outputs),
   #[clap(about = "List unspent cardinal outputs in wallet")]
   Cardinals,
 }
@@ -60,16 +75,20 @@ impl Wallet {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
       Self::Balance => balance::run(options),
+      Self::Broadcast(broadcast) => broadcast.run(options),
+      Self::BumpFee(bump_fee) => bump_fee.run(options),
       Self::Create(create) => create.run(options),
       Self::Inscribe(inscribe) => inscribe.run(options),
       Self::Inscribes(inscribes) => inscribes.run(options),
       Self::Inscriptions => inscriptions::run(options),
       Self::Receive => receive::run(options),
+      Self::RecoverCommit(recover_commit) => recover_commit.run(options),
       Self::Restore(restore) => restore.run(options),
       Self::Sats(sats) => sats.run(options),
       Self::Send(send) => send.run(options),
+      Self::SendMany(send_many) => send_many.run(options),
       Self::Transactions(transactions) => transactions.run(options),
-      Self::Outputs => outputs::run(options),
+      Self::Outputs(outputs) => outputs.run(options),
       Self::Cardinals => cardinals::run(options),
     }
   }
@@ -0,0 +1,125 @@
+use {
+  super::*,
+  base64::Engine,
+  bitcoin::{consensus::deserialize, key::PrivateKey, psbt::PartiallySignedTransaction},
+  bitcoincore_rpc::RawTx,
+  inscribes::job,
+  key_journal::KeyJournal,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Broadcast {
+  #[clap(
+    long,
+    help = "Finalize and broadcast externally signed <PSBT> written by `--psbt-out`, followed by any reveals recorded for it."
+  )]
+  psbt: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+  pub reveals: Vec<Txid>,
+  pub inscriptions: Vec<InscriptionId>,
+}
+
+/// Write `unsigned_tx` to `path` as a base64 PSBT, with the wallet filling in
+/// the spent outputs and the key origins an external signer needs.
+pub(crate) fn write_psbt(client: &Client, unsigned_tx: &Transaction, path: &Path) -> Result {
+  let psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx.clone())?;
+
+  let processed = client.wallet_process_psbt(
+    &base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+    Some(false),
+    None,
+    Some(true),
+  )?;
+
+  fs::write(path, processed.psbt)
+    .with_context(|| format!("failed to write PSBT to {}", path.display()))?;
+
+  Ok(())
+}
+
+fn read_psbt(path: &Path) -> Result<String> {
+  let bytes =
+    fs::read(path).with_context(|| format!("failed to read PSBT from {}", path.display()))?;
+
+  // signers save PSBTs either in binary or as base64 text
+  let psbt = match PartiallySignedTransaction::deserialize(&bytes) {
+    Ok(psbt) => psbt,
+    Err(_) => PartiallySignedTransaction::deserialize(
+      &base64::engine::general_purpose::STANDARD
+        .decode(String::from_utf8(bytes)?.trim())
+        .with_context(|| format!("{} is not a PSBT", path.display()))?,
+    )?,
+  };
+
+  Ok(base64::engine::general_purpose::STANDARD.encode(psbt.serialize()))
+}
+
+impl Broadcast {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let finalized = client.finalize_psbt(&read_psbt(&self.psbt)?, Some(true))?;
+
+    let tx = match finalized.hex {
+      Some(hex) if finalized.complete => deserialize::<Transaction>(&hex)?,
+      _ => bail!("PSBT {} is not fully signed", self.psbt.display()),
+    };
+
+    let txid = tx.txid();
+
+    if !job::Job::exists(&options, txid)? {
+      inscribes::broadcast::send(&client, &tx, txid, "signed")?;
+
+      return print_json(Output {
+        transaction: txid,
+        reveals: Vec::new(),
+        inscriptions: Vec::new(),
+      });
+    }
+
+    let mut job = job::Job::load(&options, txid)?;
+    let journal = KeyJournal::load(&options, &client, txid)?;
+
+    job.commit_tx = tx.raw_hex();
+
+    // reveals are signed with the recovery keys again against the finalized
+    // commit, so they stay valid whatever the signer filled in
+    for reveal in &mut job.reveals {
+      let reveal_tx = deserialize::<Transaction>(&hex::decode(&reveal.tx)?)?;
+
+      let commit = reveal_tx.input[0].previous_output;
+
+      let recovery_key = journal
+        .keys
+        .iter()
+        .find(|recovery_key| recovery_key.vout == commit.vout)
+        .with_context(|| format!("no recovery key for output {} of {txid}", commit.vout))?;
+
+      reveal.tx = bump_fee::resign_reveal(
+        &reveal_tx,
+        commit,
+        &tx.output[usize::try_from(commit.vout).unwrap()],
+        &PrivateKey::from_wif(&recovery_key.key)?,
+      )?
+      .raw_hex();
+    }
+
+    job.refresh(&client)?;
+    job.save(&options)?;
+    job.resume(&options, &client)?;
+
+    print_json(Output {
+      transaction: txid,
+      reveals: job.reveals.iter().map(|reveal| reveal.txid).collect(),
+      inscriptions: job
+        .reveals
+        .iter()
+        .flat_map(|reveal| reveal.inscriptions.clone())
+        .collect(),
+    })
+  }
+}
@@ -0,0 +1,528 @@
+use {
+  super::*,
+  bitcoin::{
+    consensus::deserialize,
+    key::{KeyPair, PrivateKey},
+    locktime::absolute::LockTime,
+    secp256k1::{Message, Secp256k1},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot::{ControlBlock, LeafVersion, TapLeafHash, TapNodeHash, TapTweakHash},
+    Witness,
+  },
+  bitcoincore_rpc::RawTx,
+  inscribes::{broadcast, job},
+  key_journal::KeyJournal,
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct BumpFee {
+  #[clap(
+    help = "Bump fee of unconfirmed <TXID>, either a commit recorded by `wallet inscribes` or an inscription reveal."
+  )]
+  txid: Txid,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "method")]
+pub enum Output {
+  Rbf {
+    original: Txid,
+    commit: Txid,
+    reveals: Vec<Txid>,
+    inscriptions: Vec<InscriptionId>,
+    fee: u64,
+  },
+  Cpfp {
+    parent: Txid,
+    child: Txid,
+    fee: u64,
+  },
+}
+
+impl BumpFee {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    if client
+      .get_raw_transaction_info(&self.txid, None)?
+      .confirmations
+      .unwrap_or_default()
+      > 0
+    {
+      bail!("transaction {} is already confirmed", self.txid);
+    }
+
+    let output = if job::Job::exists(&options, self.txid)? {
+      self.replace_commit(&options, &client)?
+    } else {
+      self.attach_child(&options, &client)?
+    };
+
+    print_json(output)
+  }
+
+  /// Replace a batch inscribe commit with one paying `fee_rate` out of its
+  /// change. The commit outputs are unchanged, so the reveals only need to be
+  /// re-signed for the new commit txid.
+  fn replace_commit(&self, options: &Options, client: &Client) -> Result<Output> {
+    let original = job::Job::load(options, self.txid)?;
+    let journal = KeyJournal::load(options, client, self.txid)?;
+
+    let commit_tx = deserialize::<Transaction>(&hex::decode(&original.commit_tx)?)?;
+
+    let reveal_txs = original
+      .reveals
+      .iter()
+      .map(|reveal| Ok(deserialize::<Transaction>(&hex::decode(&reveal.tx)?)?))
+      .collect::<Result<Vec<Transaction>>>()?;
+
+    let revealed = reveal_txs
+      .iter()
+      .map(|reveal_tx| reveal_tx.input[0].previous_output.vout)
+      .collect::<BTreeSet<u32>>();
+
+    let change = (0..commit_tx.output.len())
+      .rev()
+      .find(|vout| !revealed.contains(&u32::try_from(*vout).unwrap()))
+      .with_context(|| {
+        format!(
+          "commit {} has no change output to pay a higher fee",
+          self.txid
+        )
+      })?;
+
+    let original_fee = input_value(client, &commit_tx)?
+      - commit_tx
+        .output
+        .iter()
+        .map(|output| output.value)
+        .sum::<u64>();
+
+    let entry = client.get_mempool_entry(&self.txid)?;
+
+    // the replacement evicts every descendant of the commit, but only its
+    // reveals are re-signed and broadcast again
+    let reveals_in_mempool = entry
+      .spent_by
+      .iter()
+      .filter(|txid| {
+        reveal_txs
+          .iter()
+          .any(|reveal_tx| reveal_tx.txid() == **txid)
+      })
+      .count();
+
+    if entry.descendant_count > u64::try_from(reveals_in_mempool).unwrap() + 1 {
+      bail!(
+        "commit {} has unconfirmed descendants other than its reveals, which replacing it would drop",
+        self.txid
+      );
+    }
+
+    let vsize = commit_tx.vsize();
+
+    let fee = self.fee_rate.fee(vsize).to_sat();
+
+    let replaced_fee = entry.fees.descendant.to_sat();
+
+    // BIP 125 replacements must pay for every transaction they evict and also
+    // for their own relay at 1 sat/vB
+    if fee < replaced_fee + u64::try_from(vsize).unwrap() {
+      bail!(
+        "fee of {fee} sats too low to replace commit {} and its reveals paying {replaced_fee} sats",
+        self.txid
+      );
+    }
+
+    let mut unsigned_commit_tx = commit_tx.clone();
+
+    for input in &mut unsigned_commit_tx.input {
+      input.witness = Witness::new();
+    }
+
+    let change = &mut unsigned_commit_tx.output[change];
+
+    change.value = change
+      .value
+      .checked_sub(fee - original_fee)
+      .filter(|value| *value >= change.script_pubkey.dust_value().to_sat())
+      .with_context(|| {
+        format!(
+          "change output of commit {} cannot pay additional fee of {} sats",
+          self.txid,
+          fee - original_fee
+        )
+      })?;
+
+    let signed = client.sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?;
+
+    if !signed.complete {
+      bail!("wallet could not sign replacement for commit {}", self.txid);
+    }
+
+    let replacement = signed.transaction()?;
+
+    let reveal_txs = reveal_txs
+      .iter()
+      .map(|reveal_tx| {
+        let vout = reveal_tx.input[0].previous_output.vout;
+
+        let recovery_key = journal
+          .keys
+          .iter()
+          .find(|recovery_key| recovery_key.vout == vout)
+          .with_context(|| format!("no recovery key for output {vout} of {}", self.txid))?;
+
+        resign_reveal(
+          reveal_tx,
+          OutPoint {
+            txid: replacement.txid(),
+            vout,
+          },
+          &replacement.output[usize::try_from(vout).unwrap()],
+          &PrivateKey::from_wif(&recovery_key.key)?,
+        )
+      })
+      .collect::<Result<Vec<Transaction>>>()?;
+
+    KeyJournal {
+      commit: replacement.txid(),
+      keys: journal.keys,
+    }
+    .save(options, client)?;
+
+    let mut job = job::Job {
+      commit: replacement.txid(),
+      commit_tx: replacement.raw_hex(),
+      commit_state: job::State::Unsent,
+      recovery_keys_backed_up: original.recovery_keys_backed_up,
+      reveals: original
+        .reveals
+        .iter()
+        .zip(&reveal_txs)
+        .map(|(reveal, reveal_tx)| job::Reveal {
+          txid: reveal_tx.txid(),
+          tx: reveal_tx.raw_hex(),
+          inscriptions: reveal
+            .inscriptions
+            .iter()
+            .map(|inscription| InscriptionId {
+              txid: reveal_tx.txid(),
+              index: inscription.index,
+            })
+            .collect(),
+          recovery_address: reveal.recovery_address.clone(),
+          state: job::State::Unsent,
+        })
+        .collect(),
+    };
+
+    job.save(options)?;
+
+    if let Err(err) = broadcast::send(client, &replacement, job.commit, "commit") {
+      job::Job::remove(options, job.commit)?;
+      KeyJournal::remove(options, job.commit)?;
+      return Err(err);
+    }
+    job.commit_state = job::State::Broadcast;
+    job.save(options)?;
+
+    // reveals held back with --only-commit stay unsent until `inscribes resume`
+    for (i, reveal) in original.reveals.iter().enumerate() {
+      if reveal.state == job::State::Unsent {
+        continue;
+      }
+
+      broadcast::send(client, &reveal_txs[i], job.reveals[i].txid, "reveal")?;
+      job.reveals[i].state = job::State::Broadcast;
+      job.save(options)?;
+    }
+
+    Ok(Output::Rbf {
+      original: self.txid,
+      commit: job.commit,
+      reveals: job.reveals.iter().map(|reveal| reveal.txid).collect(),
+      inscriptions: job
+        .reveals
+        .iter()
+        .flat_map(|reveal| reveal.inscriptions.clone())
+        .collect(),
+      fee,
+    })
+  }
+
+  /// Attach a child to a stuck reveal that spends its first output back to
+  /// the same owner, paying enough from a wallet cardinal for the package to
+  /// reach `fee_rate`.
+  fn attach_child(&self, options: &Options, client: &Client) -> Result<Output> {
+    let parent = client.get_raw_transaction(&self.txid, None)?;
+
+    if Inscription::from_transaction(&parent).is_empty() {
+      bail!(
+        "transaction {} is neither a commit recorded by `wallet inscribes` nor an inscription reveal",
+        self.txid
+      );
+    }
+
+    let parent_fee =
+      input_value(client, &parent)? - parent.output.iter().map(|output| output.value).sum::<u64>();
+
+    let index = Index::open(options)?;
+    index.update()?;
+
+    let inscribed = index
+      .get_inscriptions(None)?
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let (cardinal, amount) = index
+      .get_unspent_outputs(crate::wallet::Wallet::load(options)?)?
+      .into_iter()
+      .filter(|(outpoint, _)| !inscribed.contains(outpoint) && outpoint.txid != self.txid)
+      .max_by_key(|(_, amount)| *amount)
+      .ok_or_else(|| anyhow!("wallet contains no cardinal utxos"))?;
+
+    let mut child = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: [
+        OutPoint {
+          txid: self.txid,
+          vout: 0,
+        },
+        cardinal,
+      ]
+      .into_iter()
+      .map(|previous_output| TxIn {
+        previous_output,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      })
+      .collect(),
+      output: vec![
+        parent.output[0].clone(),
+        TxOut {
+          value: amount.to_sat(),
+          script_pubkey: get_change_address(client, options)?.script_pubkey(),
+        },
+      ],
+    };
+
+    // sign once to learn the child's size, since the fee does not change it
+    let vsize = Self::sign(client, &child)?.vsize();
+
+    let fee = self
+      .fee_rate
+      .fee(parent.vsize() + vsize)
+      .to_sat()
+      .checked_sub(parent_fee)
+      .filter(|fee| *fee >= u64::try_from(vsize).unwrap())
+      .with_context(|| format!("reveal {} already pays the requested fee rate", self.txid))?;
+
+    child.output[1].value = amount
+      .to_sat()
+      .checked_sub(fee)
+      .filter(|value| *value >= child.output[1].script_pubkey.dust_value().to_sat())
+      .with_context(|| format!("cardinal {cardinal} cannot pay child fee of {fee} sats"))?;
+
+    let child = Self::sign(client, &child)?;
+
+    broadcast::send(client, &child, child.txid(), "child")?;
+
+    Ok(Output::Cpfp {
+      parent: self.txid,
+      child: child.txid(),
+      fee,
+    })
+  }
+
+  fn sign(client: &Client, tx: &Transaction) -> Result<Transaction> {
+    let signed = client.sign_raw_transaction_with_wallet(tx, None, None)?;
+
+    if !signed.complete {
+      bail!("wallet could not sign child transaction, is the inscription owned by this wallet?");
+    }
+
+    Ok(signed.transaction()?)
+  }
+}
+
+fn input_value(client: &Client, tx: &Transaction) -> Result<u64> {
+  tx.input
+    .iter()
+    .map(|input| {
+      let previous = client.get_raw_transaction(&input.previous_output.txid, None)?;
+      Ok(previous.output[usize::try_from(input.previous_output.vout).unwrap()].value)
+    })
+    .sum()
+}
+
+/// Recover the reveal script's internal key from a commit output's recovery
+/// key, which is the internal key with the taproot tweak added.
+fn internal_key_pair(
+  recovery_key: &PrivateKey,
+  control_block: &ControlBlock,
+  script: &Script,
+) -> Result<KeyPair> {
+  let secp256k1 = Secp256k1::new();
+
+  let tweak = TapTweakHash::from_key_and_tweak(
+    control_block.internal_key,
+    Some(TapNodeHash::from_script(script, LeafVersion::TapScript)),
+  )
+  .to_scalar();
+
+  for tweaked in [recovery_key.inner, recovery_key.inner.negate()] {
+    let Ok(untweaked) = tweaked.negate().add_tweak(&tweak) else {
+      continue;
+    };
+
+    let key_pair = KeyPair::from_secret_key(&secp256k1, &untweaked.negate());
+
+    if key_pair.x_only_public_key().0 == control_block.internal_key {
+      return Ok(key_pair);
+    }
+  }
+
+  bail!("recovery key does not match reveal script")
+}
+
+pub(crate) fn resign_reveal(
+  reveal_tx: &Transaction,
+  commit: OutPoint,
+  commit_output: &TxOut,
+  recovery_key: &PrivateKey,
+) -> Result<Transaction> {
+  let witness = &reveal_tx.input[0].witness;
+
+  let (Some(script), Some(control_block)) = (witness.nth(1), witness.nth(2)) else {
+    bail!("reveal {} is not a script path spend", reveal_tx.txid());
+  };
+
+  let script = Script::from_bytes(script);
+  let control_block = ControlBlock::decode(control_block)?;
+
+  let key_pair = internal_key_pair(recovery_key, &control_block, script)?;
+
+  let mut reveal_tx = reveal_tx.clone();
+  reveal_tx.input[0].previous_output = commit;
+
+  let mut sighash_cache = SighashCache::new(&mut reveal_tx);
+
+  let signature_hash = sighash_cache
+    .taproot_script_spend_signature_hash(
+      0,
+      &Prevouts::All(&[commit_output]),
+      TapLeafHash::from_script(script, LeafVersion::TapScript),
+      TapSighashType::Default,
+    )
+    .expect("signature hash should compute");
+
+  let signature = Secp256k1::new().sign_schnorr(
+    &Message::from_slice(signature_hash.as_ref()).expect("should be cryptographically secure hash"),
+    &key_pair,
+  );
+
+  *sighash_cache.witness_mut(0).unwrap() = Witness::from_slice(&[
+    signature.as_ref(),
+    script.as_bytes(),
+    &control_block.serialize(),
+  ]);
+
+  Ok(reveal_tx)
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{
+      blockdata::{opcodes, script},
+      key::{TapTweak, UntweakedKeyPair},
+      secp256k1::XOnlyPublicKey,
+      taproot::TaprootBuilder,
+    },
+  };
+
+  #[test]
+  fn internal_key_is_recovered_from_recovery_key() {
+    let secp256k1 = Secp256k1::new();
+
+    for _ in 0..16 {
+      let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+      let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+      let script = inscription("text/plain", "foo").append_reveal_script(
+        script::Builder::new()
+          .push_slice(public_key.serialize())
+          .push_opcode(opcodes::all::OP_CHECKSIG),
+      );
+
+      let taproot_spend_info = TaprootBuilder::new()
+        .add_leaf(0, script.clone())
+        .unwrap()
+        .finalize(&secp256k1, public_key)
+        .unwrap();
+
+      let control_block = taproot_spend_info
+        .control_block(&(script.clone(), LeafVersion::TapScript))
+        .unwrap();
+
+      let recovery_key = PrivateKey::new(
+        key_pair
+          .tap_tweak(&secp256k1, taproot_spend_info.merkle_root())
+          .to_inner()
+          .secret_key(),
+        Network::Bitcoin,
+      );
+
+      assert_eq!(
+        internal_key_pair(&recovery_key, &control_block, &script)
+          .unwrap()
+          .x_only_public_key()
+          .0,
+        public_key
+      );
+    }
+  }
+
+  #[test]
+  fn mismatched_recovery_key_is_rejected() {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let script = inscription("text/plain", "foo").append_reveal_script(
+      script::Builder::new()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let control_block = TaprootBuilder::new()
+      .add_leaf(0, script.clone())
+      .unwrap()
+      .finalize(&secp256k1, public_key)
+      .unwrap()
+      .control_block(&(script.clone(), LeafVersion::TapScript))
+      .unwrap();
+
+    let other = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+
+    assert_eq!(
+      internal_key_pair(
+        &PrivateKey::new(other.secret_key(), Network::Bitcoin),
+        &control_block,
+        &script
+      )
+      .unwrap_err()
+      .to_string(),
+      "recovery key does not match reveal script"
+    );
+  }
+}
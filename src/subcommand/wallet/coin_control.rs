@@ -0,0 +1,337 @@
+use {super::*, clap::ValueEnum};
+
+#[derive(Default, ValueEnum, Copy, Clone, Debug, PartialEq)]
+pub enum CoinSelection {
+  #[default]
+  BranchAndBound,
+  LargestFirst,
+}
+
+#[derive(Debug, Default, Parser)]
+pub(crate) struct CoinControl {
+  #[clap(
+    long,
+    arg_enum,
+    default_value = "branch-and-bound",
+    help = "Select cardinal inputs with <COIN_SELECTION>. Branch-and-bound looks for the set of inputs that wastes the least, falling back to largest-first if no set avoids change."
+  )]
+  pub(crate) coin_selection: CoinSelection,
+  #[clap(long, help = "Only spend cardinal output <UTXO>. May be repeated.")]
+  pub(crate) utxo: Vec<OutPoint>,
+  #[clap(long, help = "Never spend output <EXCLUDE_UTXO>. May be repeated.")]
+  pub(crate) exclude_utxo: Vec<OutPoint>,
+  #[clap(
+    long,
+    default_value = "0",
+    help = "Only spend outputs with at least <MIN_CONF> confirmations."
+  )]
+  pub(crate) min_conf: u32,
+}
+
+impl CoinControl {
+  /// Narrow `utxos` down to the outputs that may be spent. `outgoing` are the
+  /// outputs being sent or inscribed, which the user chose explicitly, so they
+  /// are never filtered out.
+  pub(crate) fn filter(
+    &self,
+    client: &Client,
+    utxos: BTreeMap<OutPoint, Amount>,
+    outgoing: &[OutPoint],
+  ) -> Result<BTreeMap<OutPoint, Amount>> {
+    for outpoint in &self.utxo {
+      if !utxos.contains_key(outpoint) {
+        bail!("--utxo {outpoint} is not an unspent output in wallet");
+      }
+    }
+
+    let mut filtered = BTreeMap::new();
+
+    for (outpoint, amount) in utxos {
+      if !outgoing.contains(&outpoint) {
+        if !self.utxo.is_empty() && !self.utxo.contains(&outpoint) {
+          continue;
+        }
+
+        if self.exclude_utxo.contains(&outpoint) {
+          continue;
+        }
+
+        if self.min_conf > 0 {
+          let confirmations = client
+            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+            .map(|tx_out| tx_out.confirmations)
+            .unwrap_or_default();
+
+          if confirmations < self.min_conf {
+            continue;
+          }
+        }
+      }
+
+      filtered.insert(outpoint, amount);
+    }
+
+    Ok(filtered)
+  }
+}
+
+impl CoinSelection {
+  const MAX_TRIES: usize = 100_000;
+
+  /// Select inputs from `utxos` whose value, after paying for their own
+  /// inclusion at `fee_rate`, covers `target`. Branch-and-bound only accepts
+  /// sets that overshoot `target` by at most `cost_of_change`.
+  pub(crate) fn select(
+    self,
+    utxos: &BTreeMap<OutPoint, Amount>,
+    target: Amount,
+    fee_rate: FeeRate,
+    cost_of_change: Amount,
+  ) -> Option<BTreeMap<OutPoint, Amount>> {
+    let input_fee = fee_rate.fee(TransactionBuilder::ADDITIONAL_INPUT_VBYTES);
+
+    let mut candidates = utxos
+      .iter()
+      .filter(|(_, amount)| **amount > input_fee)
+      .map(|(outpoint, amount)| (*outpoint, *amount, (*amount - input_fee).to_sat()))
+      .collect::<Vec<(OutPoint, Amount, u64)>>();
+
+    candidates.sort_by_key(|(_, _, effective)| std::cmp::Reverse(*effective));
+
+    let selected = match self {
+      Self::BranchAndBound => {
+        Self::branch_and_bound(&candidates, target.to_sat(), cost_of_change.to_sat())
+          .or_else(|| Self::largest_first(&candidates, target.to_sat()))
+      }
+      Self::LargestFirst => Self::largest_first(&candidates, target.to_sat()),
+    }?;
+
+    Some(
+      selected
+        .into_iter()
+        .map(|i| (candidates[i].0, candidates[i].1))
+        .collect(),
+    )
+  }
+
+  /// Pick a single input from `candidates`, each of which covers the value
+  /// still needed on its own.
+  pub(crate) fn select_one(
+    self,
+    candidates: impl Iterator<Item = (OutPoint, Amount)>,
+  ) -> Option<(OutPoint, Amount)> {
+    match self {
+      Self::BranchAndBound => candidates.min_by_key(|(_, amount)| *amount),
+      Self::LargestFirst => candidates.fold(None, |largest, (outpoint, amount)| match largest {
+        Some((_, value)) if value >= amount => largest,
+        _ => Some((outpoint, amount)),
+      }),
+    }
+  }
+
+  fn largest_first(candidates: &[(OutPoint, Amount, u64)], target: u64) -> Option<Vec<usize>> {
+    let mut total = 0;
+
+    for (i, (.., effective)) in candidates.iter().enumerate() {
+      total += effective;
+      if total >= target {
+        return Some((0..=i).collect());
+      }
+    }
+
+    None
+  }
+
+  // depth-first search over include/omit decisions, with candidates sorted by
+  // descending effective value, keeping the match that wastes the least
+  fn branch_and_bound(
+    candidates: &[(OutPoint, Amount, u64)],
+    target: u64,
+    cost_of_change: u64,
+  ) -> Option<Vec<usize>> {
+    let mut available = candidates
+      .iter()
+      .map(|(.., effective)| effective)
+      .sum::<u64>();
+
+    if available < target {
+      return None;
+    }
+
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    let mut selected = Vec::new();
+    let mut current = 0;
+    let mut next = 0;
+
+    for _ in 0..Self::MAX_TRIES {
+      let backtrack = if current + available < target || current > target + cost_of_change {
+        true
+      } else if current >= target {
+        let waste = current - target;
+        if best.as_ref().map_or(true, |(best, _)| waste < *best) {
+          best = Some((waste, selected.clone()));
+        }
+        if waste == 0 {
+          break;
+        }
+        true
+      } else {
+        false
+      };
+
+      if backtrack {
+        let Some(last) = selected.pop() else {
+          break;
+        };
+
+        while next > last + 1 {
+          next -= 1;
+          available += candidates[next].2;
+        }
+
+        current -= candidates[last].2;
+      } else {
+        available -= candidates[next].2;
+        current += candidates[next].2;
+        selected.push(next);
+        next += 1;
+      }
+    }
+
+    best.map(|(_, selected)| selected)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn utxos(values: &[u64]) -> BTreeMap<OutPoint, Amount> {
+    values
+      .iter()
+      .enumerate()
+      .map(|(i, value)| (outpoint(i as u64 + 1), Amount::from_sat(*value)))
+      .collect()
+  }
+
+  fn values(selected: BTreeMap<OutPoint, Amount>) -> Vec<u64> {
+    let mut values = selected
+      .values()
+      .map(|amount| amount.to_sat())
+      .collect::<Vec<u64>>();
+    values.sort();
+    values
+  }
+
+  #[test]
+  fn branch_and_bound_finds_changeless_match() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    assert_eq!(
+      values(
+        CoinSelection::BranchAndBound
+          .select(
+            &utxos(&[100_058, 30_058, 20_058, 10_058]),
+            Amount::from_sat(50_000),
+            fee_rate,
+            Amount::from_sat(100),
+          )
+          .unwrap()
+      ),
+      [20_058, 30_058],
+    );
+  }
+
+  #[test]
+  fn branch_and_bound_falls_back_to_largest_first() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    assert_eq!(
+      values(
+        CoinSelection::BranchAndBound
+          .select(
+            &utxos(&[100_058, 30_058, 20_058]),
+            Amount::from_sat(45_000),
+            fee_rate,
+            Amount::from_sat(100),
+          )
+          .unwrap()
+      ),
+      [100_058],
+    );
+  }
+
+  #[test]
+  fn largest_first_adds_largest_inputs_until_target_is_met() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    assert_eq!(
+      values(
+        CoinSelection::LargestFirst
+          .select(
+            &utxos(&[10_058, 30_058, 20_058]),
+            Amount::from_sat(45_000),
+            fee_rate,
+            Amount::from_sat(100),
+          )
+          .unwrap()
+      ),
+      [20_058, 30_058],
+    );
+  }
+
+  #[test]
+  fn selection_fails_if_inputs_do_not_cover_target() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    for coin_selection in [CoinSelection::BranchAndBound, CoinSelection::LargestFirst] {
+      assert_eq!(
+        coin_selection.select(
+          &utxos(&[10_058, 20_058]),
+          Amount::from_sat(30_001),
+          fee_rate,
+          Amount::from_sat(100),
+        ),
+        None,
+      );
+    }
+  }
+
+  #[test]
+  fn inputs_that_cannot_pay_for_themselves_are_not_selected() {
+    let fee_rate = FeeRate::try_from(10.0).unwrap();
+
+    assert_eq!(
+      values(
+        CoinSelection::LargestFirst
+          .select(
+            &utxos(&[500, 20_580]),
+            Amount::from_sat(20_000),
+            fee_rate,
+            Amount::from_sat(100),
+          )
+          .unwrap()
+      ),
+      [20_580],
+    );
+  }
+
+  #[test]
+  fn select_one() {
+    let candidates = [
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(20_000)),
+      (outpoint(3), Amount::from_sat(10_000)),
+    ];
+
+    assert_eq!(
+      CoinSelection::BranchAndBound.select_one(candidates.into_iter()),
+      Some(candidates[0]),
+    );
+
+    assert_eq!(
+      CoinSelection::LargestFirst.select_one(candidates.into_iter()),
+      Some(candidates[1]),
+    );
+  }
+}
@@ -15,7 +15,9 @@ use {
     ScriptBuf, Witness,
   },
   bitcoincore_rpc::bitcoincore_rpc_json::{ImportDescriptors, Timestamp},
-  bitcoincore_rpc::Client,
+  bitcoincore_rpc::{Client, RawTx},
+  inscribes::job,
+  key_journal::{KeyJournal, RecoveryKey},
   std::collections::BTreeSet,
 };
 
@@ -27,6 +29,14 @@ struct Output {
   fees: u64,
 }
 
+#[derive(Clone, Debug)]
+struct ParentInfo {
+  change: Address,
+  destination: Address,
+  location: SatPoint,
+  tx_out: TxOut,
+}
+
 #[derive(Debug, Parser)]
 pub(crate) struct Inscribe {
   #[clap(long, help = "Inscribe <SATPOINT>")]
@@ -53,11 +63,49 @@ pub(crate) struct Inscribe {
   pub(crate) un_safe: Option<bool>,
   #[clap(long, help = "Send inscription to <DESTINATION>.")]
   pub(crate) destination: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Include CBOR metadata from <METADATA>. JSON files ending in .json are converted to CBOR."
+  )]
+  pub(crate) metadata: Option<PathBuf>,
+  #[clap(long, help = "Set inscription metaprotocol to <METAPROTOCOL>.")]
+  pub(crate) metaprotocol: Option<String>,
+  #[clap(long, help = "Make inscription a child of <PARENT>.")]
+  pub(crate) parent: Option<InscriptionId>,
+  #[clap(
+    long,
+    help = "Compress inscription content with brotli if it makes it smaller."
+  )]
+  pub(crate) compress: bool,
+  #[clap(
+    long,
+    conflicts_with_all = &["dry-run", "parent"],
+    help = "Write unsigned commit transaction to <PSBT_OUT> for an external signer instead of signing and broadcasting it. The signed reveal is recorded for `wallet broadcast`."
+  )]
+  pub(crate) psbt_out: Option<PathBuf>,
+  #[clap(flatten)]
+  pub(crate) policy: ContentPolicy,
+  #[clap(flatten)]
+  pub(crate) coin_control: CoinControl,
 }
 
 impl Inscribe {
   pub(crate) fn run(self, options: Options) -> Result {
-    let inscription = Inscription::from_file(options.chain(), &self.file)?;
+    let metadata = self
+      .metadata
+      .as_ref()
+      .map(Inscription::metadata_from_file)
+      .transpose()?;
+
+    let inscription = Inscription::from_file(
+      options.chain(),
+      &self.file,
+      self.parent,
+      metadata,
+      self.metaprotocol,
+      self.compress,
+      &self.policy,
+    )?;
 
     let index = Index::open(&options)?;
     index.update()?;
@@ -74,7 +122,17 @@ impl Inscribe {
     } else {
         utxos = index.get_unspent_outputs(Wallet::load(&options)?)?;
     }
-    
+
+    utxos = self.coin_control.filter(
+      &client,
+      utxos,
+      &self
+        .satpoint
+        .map(|satpoint| satpoint.outpoint)
+        .into_iter()
+        .collect::<Vec<OutPoint>>(),
+    )?;
+
     let inscriptions = index.get_inscriptions(None)?;
 
     let commit_tx_change = [
@@ -87,6 +145,36 @@ impl Inscribe {
       None => get_change_address(&client, &options)?,
     };
 
+    let parent_info = if let Some(parent_id) = self.parent {
+      let location = index
+        .get_inscription_satpoint_by_id(parent_id)?
+        .ok_or_else(|| anyhow!("parent {parent_id} does not exist"))?;
+
+      if !client
+        .list_unspent(None, None, None, None, None)?
+        .into_iter()
+        .any(|utxo| OutPoint::new(utxo.txid, utxo.vout) == location.outpoint)
+      {
+        bail!("parent {parent_id} not in wallet");
+      }
+
+      let tx_out = index
+        .get_transaction(location.outpoint.txid)?
+        .and_then(|tx| tx.output.into_iter().nth(location.outpoint.vout as usize))
+        .ok_or_else(|| anyhow!("parent output {} not found", location.outpoint))?;
+
+      utxos.insert(location.outpoint, Amount::from_sat(tx_out.value));
+
+      Some(ParentInfo {
+        change: get_change_address(&client, &options)?,
+        destination: get_change_address(&client, &options)?,
+        location,
+        tx_out,
+      })
+    } else {
+      None
+    };
+
     let (unsigned_commit_tx, reveal_tx, recovery_key_pair) =
       Inscribe::create_inscription_transactions(
         self.satpoint,
@@ -99,14 +187,18 @@ impl Inscribe {
         self.commit_fee_rate.unwrap_or(self.fee_rate),
         self.fee_rate,
         self.no_limit,
+        parent_info,
+        self.coin_control.coin_selection,
       )?;
 
-    utxos.insert(
-      reveal_tx.input[0].previous_output,
-      Amount::from_sat(
-        unsigned_commit_tx.output[reveal_tx.input[0].previous_output.vout as usize].value,
-      ),
-    );
+    for txin in &reveal_tx.input {
+      if txin.previous_output.txid == unsigned_commit_tx.txid() {
+        utxos.insert(
+          txin.previous_output,
+          Amount::from_sat(unsigned_commit_tx.output[txin.previous_output.vout as usize].value),
+        );
+      }
+    }
 
     let fees =
       Self::calculate_fee(&unsigned_commit_tx, &utxos) + Self::calculate_fee(&reveal_tx, &utxos);
@@ -123,6 +215,49 @@ impl Inscribe {
         Inscribe::backup_recovery_key(&client, recovery_key_pair, options.chain().network())?;
       }
 
+      if let Some(psbt_out) = &self.psbt_out {
+        let commit = unsigned_commit_tx.txid();
+        let vout = reveal_tx.input[0].previous_output.vout;
+
+        KeyJournal {
+          commit,
+          keys: vec![RecoveryKey::new(
+            vout,
+            &recovery_key_pair,
+            options.chain().network(),
+          )],
+        }
+        .save(&options, &client)?;
+
+        job::Job {
+          commit,
+          commit_tx: unsigned_commit_tx.raw_hex(),
+          commit_state: job::State::Unsent,
+          recovery_keys_backed_up: !self.no_backup,
+          reveals: vec![job::Reveal {
+            txid: reveal_tx.txid(),
+            tx: reveal_tx.raw_hex(),
+            inscriptions: vec![reveal_tx.txid().into()],
+            recovery_address: Address::from_script(
+              &unsigned_commit_tx.output[usize::try_from(vout).unwrap()].script_pubkey,
+              options.chain().network(),
+            )?
+            .to_string(),
+            state: job::State::Unsent,
+          }],
+        }
+        .save(&options)?;
+
+        broadcast::write_psbt(&client, &unsigned_commit_tx, psbt_out)?;
+
+        return print_json(Output {
+          commit,
+          reveal: reveal_tx.txid(),
+          inscription: reveal_tx.txid().into(),
+          fees,
+        });
+      }
+
       let signed_raw_commit_tx = client
         .sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?
         .hex;
@@ -131,9 +266,19 @@ impl Inscribe {
         .send_raw_transaction(&signed_raw_commit_tx)
         .context("Failed to send commit transaction")?;
 
-      let reveal = client
-        .send_raw_transaction(&reveal_tx)
-        .context("Failed to send reveal transaction")?;
+      let reveal = if self.parent.is_some() {
+        let signed_raw_reveal_tx = client
+          .sign_raw_transaction_with_wallet(&reveal_tx, None, None)?
+          .hex;
+
+        client
+          .send_raw_transaction(&signed_raw_reveal_tx)
+          .context("Failed to send reveal transaction")?
+      } else {
+        client
+          .send_raw_transaction(&reveal_tx)
+          .context("Failed to send reveal transaction")?
+      };
 
       print_json(Output {
         commit,
@@ -166,6 +311,8 @@ impl Inscribe {
     commit_fee_rate: FeeRate,
     reveal_fee_rate: FeeRate,
     no_limit: bool,
+    parent_info: Option<ParentInfo>,
+    coin_selection: CoinSelection,
   ) -> Result<(Transaction, Transaction, TweakedKeyPair)> {
     let satpoint = if let Some(satpoint) = satpoint {
       satpoint
@@ -220,17 +367,48 @@ impl Inscribe {
 
     let commit_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), network);
 
+    let mut reveal_inputs = vec![OutPoint::null()];
+    let mut reveal_outputs = vec![TxOut {
+      script_pubkey: destination.script_pubkey(),
+      value: 0,
+    }];
+
+    // sats are assigned to outputs in order, so when spending a parent the
+    // reveal fee is paid by a third input, the commit transaction's change,
+    // which keeps the parent sat at its current offset
+    if let Some(ParentInfo {
+      change,
+      destination,
+      location,
+      tx_out,
+    }) = &parent_info
+    {
+      reveal_inputs.push(location.outpoint);
+      reveal_inputs.push(OutPoint::null());
+      reveal_outputs.push(TxOut {
+        script_pubkey: destination.script_pubkey(),
+        value: tx_out.value,
+      });
+      reveal_outputs.push(TxOut {
+        script_pubkey: change.script_pubkey(),
+        value: 0,
+      });
+    }
+
     let (_, reveal_fee) = Self::build_reveal_transaction(
       &control_block,
       reveal_fee_rate,
-      OutPoint::null(),
-      TxOut {
-        script_pubkey: destination.script_pubkey(),
-        value: 0,
-      },
+      reveal_inputs.clone(),
+      reveal_outputs.clone(),
       &reveal_script,
     );
 
+    let commit_value = if parent_info.is_some() {
+      TransactionBuilder::TARGET_POSTAGE
+    } else {
+      reveal_fee + TransactionBuilder::TARGET_POSTAGE
+    };
+
     let unsigned_commit_tx = TransactionBuilder::build_transaction_with_value(
       satpoint,
       inscriptions,
@@ -238,7 +416,8 @@ impl Inscribe {
       commit_tx_address.clone(),
       change,
       commit_fee_rate,
-      reveal_fee + TransactionBuilder::TARGET_POSTAGE,
+      commit_value,
+      coin_selection,
     )?;
 
     let (vout, output) = unsigned_commit_tx
@@ -248,27 +427,56 @@ impl Inscribe {
       .find(|(_vout, output)| output.script_pubkey == commit_tx_address.script_pubkey())
       .expect("should find sat commit/inscription output");
 
+    reveal_inputs[0] = OutPoint {
+      txid: unsigned_commit_tx.txid(),
+      vout: vout.try_into().unwrap(),
+    };
+    reveal_outputs[0].value = output.value;
+
     let (mut reveal_tx, fee) = Self::build_reveal_transaction(
       &control_block,
       reveal_fee_rate,
-      OutPoint {
-        txid: unsigned_commit_tx.txid(),
-        vout: vout.try_into().unwrap(),
-      },
-      TxOut {
-        script_pubkey: destination.script_pubkey(),
-        value: output.value,
-      },
+      reveal_inputs,
+      reveal_outputs,
       &reveal_script,
     );
 
-    reveal_tx.output[0].value = reveal_tx.output[0]
-      .value
-      .checked_sub(fee.to_sat())
-      .context("commit transaction output value insufficient to pay transaction fee")?;
+    let mut prevouts = vec![output.clone()];
+
+    if let Some(ParentInfo { tx_out, .. }) = parent_info {
+      let (change_vout, change_output) = unsigned_commit_tx
+        .output
+        .iter()
+        .enumerate()
+        .filter(|(_vout, output)| output.script_pubkey != commit_tx_address.script_pubkey())
+        .max_by_key(|(_vout, output)| output.value)
+        .context("commit transaction has no change output to pay reveal transaction fee")?;
+
+      reveal_tx.input[2].previous_output = OutPoint {
+        txid: unsigned_commit_tx.txid(),
+        vout: change_vout.try_into().unwrap(),
+      };
+
+      reveal_tx.output[2].value = change_output
+        .value
+        .checked_sub(fee.to_sat())
+        .context("commit transaction change insufficient to pay reveal transaction fee")?;
 
-    if reveal_tx.output[0].value < reveal_tx.output[0].script_pubkey.dust_value().to_sat() {
-      bail!("commit transaction output would be dust");
+      if reveal_tx.output[2].value < reveal_tx.output[2].script_pubkey.dust_value().to_sat() {
+        bail!("reveal transaction change would be dust");
+      }
+
+      prevouts.push(tx_out);
+      prevouts.push(change_output.clone());
+    } else {
+      reveal_tx.output[0].value = reveal_tx.output[0]
+        .value
+        .checked_sub(fee.to_sat())
+        .context("commit transaction output value insufficient to pay transaction fee")?;
+
+      if reveal_tx.output[0].value < reveal_tx.output[0].script_pubkey.dust_value().to_sat() {
+        bail!("commit transaction output would be dust");
+      }
     }
 
     let mut sighash_cache = SighashCache::new(&mut reveal_tx);
@@ -276,7 +484,7 @@ impl Inscribe {
     let signature_hash = sighash_cache
       .taproot_script_spend_signature_hash(
         0,
-        &Prevouts::All(&[output]),
+        &Prevouts::All(&prevouts),
         TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
         TapSighashType::Default,
       )
@@ -348,18 +556,21 @@ impl Inscribe {
   fn build_reveal_transaction(
     control_block: &ControlBlock,
     fee_rate: FeeRate,
-    input: OutPoint,
-    output: TxOut,
+    inputs: Vec<OutPoint>,
+    outputs: Vec<TxOut>,
     script: &Script,
   ) -> (Transaction, Amount) {
     let reveal_tx = Transaction {
-      input: vec![TxIn {
-        previous_output: input,
-        script_sig: script::Builder::new().into_script(),
-        witness: Witness::new(),
-        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
-      }],
-      output: vec![output],
+      input: inputs
+        .into_iter()
+        .map(|previous_output| TxIn {
+          previous_output,
+          script_sig: script::Builder::new().into_script(),
+          witness: Witness::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        })
+        .collect(),
+      output: outputs,
       lock_time: LockTime::ZERO,
       version: 1,
     };
@@ -367,11 +578,14 @@ impl Inscribe {
     let fee = {
       let mut reveal_tx = reveal_tx.clone();
 
-      reveal_tx.input[0].witness.push(
-        Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
-          .unwrap()
-          .as_ref(),
-      );
+      for txin in &mut reveal_tx.input {
+        txin.witness.push(
+          Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
+            .unwrap()
+            .as_ref(),
+        );
+      }
+
       reveal_tx.input[0].witness.push(script);
       reveal_tx.input[0].witness.push(&control_block.serialize());
 
@@ -404,6 +618,8 @@ mod tests {
       FeeRate::try_from(1.0).unwrap(),
       FeeRate::try_from(1.0).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .unwrap();
 
@@ -417,6 +633,54 @@ mod tests {
     );
   }
 
+  #[test]
+  fn reveal_transaction_with_parent_keeps_parent_value() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(20_000)),
+      (outpoint(2), Amount::from_sat(546)),
+    ];
+
+    let mut inscriptions = BTreeMap::new();
+    inscriptions.insert(satpoint(2, 0), inscription_id(1));
+
+    let (commit_tx, reveal_tx, _) = Inscribe::create_inscription_transactions(
+      Some(satpoint(1, 0)),
+      inscription("text/plain", "ord").with_parent(inscription_id(1)),
+      inscriptions,
+      Network::Testnet,
+      utxos.into_iter().collect(),
+      [change(0), change(1)],
+      recipient(),
+      FeeRate::try_from(1.0).unwrap(),
+      FeeRate::try_from(1.0).unwrap(),
+      false,
+      Some(ParentInfo {
+        change: change(2),
+        destination: address(),
+        location: satpoint(2, 0),
+        tx_out: tx_out(546, address()),
+      }),
+      CoinSelection::default(),
+    )
+    .unwrap();
+
+    assert_eq!(reveal_tx.input[0].previous_output.txid, commit_tx.txid());
+    assert_eq!(reveal_tx.input[1].previous_output, outpoint(2));
+    assert_eq!(reveal_tx.input[2].previous_output.txid, commit_tx.txid());
+
+    assert_eq!(
+      reveal_tx.output[0].value,
+      TransactionBuilder::TARGET_POSTAGE.to_sat()
+    );
+    assert_eq!(
+      reveal_tx.output[0].script_pubkey,
+      recipient().script_pubkey()
+    );
+    assert_eq!(reveal_tx.output[1].value, 546);
+    assert_eq!(reveal_tx.output[1].script_pubkey, address().script_pubkey());
+    assert_eq!(reveal_tx.output[2].script_pubkey, change(2).script_pubkey());
+  }
+
   #[test]
   fn inscript_tansactions_opt_in_to_rbf() {
     let utxos = vec![(outpoint(1), Amount::from_sat(20000))];
@@ -435,6 +699,8 @@ mod tests {
       FeeRate::try_from(1.0).unwrap(),
       FeeRate::try_from(1.0).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .unwrap();
 
@@ -470,6 +736,8 @@ mod tests {
       FeeRate::try_from(1.0).unwrap(),
       FeeRate::try_from(1.0).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .unwrap_err()
     .to_string();
@@ -512,6 +780,8 @@ mod tests {
       FeeRate::try_from(1.0).unwrap(),
       FeeRate::try_from(1.0).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .is_ok())
   }
@@ -548,6 +818,8 @@ mod tests {
       FeeRate::try_from(fee_rate).unwrap(),
       FeeRate::try_from(fee_rate).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .unwrap();
 
@@ -610,6 +882,8 @@ mod tests {
       FeeRate::try_from(commit_fee_rate).unwrap(),
       FeeRate::try_from(fee_rate).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .unwrap();
 
@@ -659,6 +933,8 @@ mod tests {
       FeeRate::try_from(1.0).unwrap(),
       FeeRate::try_from(1.0).unwrap(),
       false,
+      None,
+      CoinSelection::default(),
     )
     .unwrap_err()
     .to_string();
@@ -690,6 +966,8 @@ mod tests {
       FeeRate::try_from(1.0).unwrap(),
       FeeRate::try_from(1.0).unwrap(),
       true,
+      None,
+      CoinSelection::default(),
     )
     .unwrap();
 
@@ -1,7 +1,8 @@
-use std::io::Write;
 use bitcoincore_rpc::RawTx;
 use {
   super::*,
+  super::key_journal::{KeyJournal, RecoveryKey},
+  bitcoincore_rpc::bitcoincore_rpc_json::ImportMultiResult,
   crate::wallet::Wallet,
   bitcoin::{
     blockdata::{opcodes, script},
@@ -20,25 +21,36 @@ use {
   log::info
 };
 
+pub(crate) mod broadcast;
+pub(crate) mod job;
+mod manifest;
+
 #[derive(Serialize)]
 struct Output {
   commit: Txid,
+  commits: Vec<Txid>,
   inscription: Vec<InscriptionId>,
   reveal: Vec<Txid>,
   fees: u64,
 }
 
 #[derive(Debug, Parser)]
+#[clap(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 pub(crate) struct Inscribes {
-  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
-  pub(crate) fee_rate: FeeRate,
+  #[clap(subcommand)]
+  pub(crate) job: Option<job::JobCommand>,
+  #[clap(long, required = true, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  pub(crate) fee_rate: Option<FeeRate>,
   #[clap(
     long,
     help = "Use <COMMIT_FEE_RATE> sats/vbyte for commit transaction.\nDefaults to <FEE_RATE> if unset."
   )]
   pub(crate) commit_fee_rate: Option<FeeRate>,
-  #[clap(help = "Inscribes sat with contents of <FILE>")]
-  pub(crate) file: PathBuf,
+  #[clap(
+    help = "Inscribes sat with contents of <FILE>",
+    required_unless_present_any = &["delegate", "batch", "manifest"]
+  )]
+  pub(crate) file: Option<PathBuf>,
   #[clap(long, help = "Do not back up recovery key.")]
   pub(crate) no_backup: bool,
   #[clap(
@@ -48,27 +60,138 @@ pub(crate) struct Inscribes {
   pub(crate) no_limit: bool,
   #[clap(long, help = "Don't sign or broadcast transactions.")]
   pub(crate) dry_run: bool,
-  #[clap(long, help = "Whether to use un-safe utxo.")]
-  pub(crate) mint_size: u64,
+  #[clap(
+    long,
+    required_unless_present_any = &["batch", "manifest"],
+    help = "Inscribe <MINT_SIZE> copies of <FILE>."
+  )]
+  pub(crate) mint_size: Option<u64>,
   #[clap(long, help = "Send inscription to <DESTINATION>.")]
   pub(crate) destination: Option<Address<NetworkUnchecked>>,
   #[clap(long, help = "Whether to use un-safe utxo.")]
   pub(crate) un_safe: Option<bool>,
-  #[clap(long, help = "Whether to use sleep. defualt 1.2 s, for exsample:1.3.")]
-  pub(crate) sleep: Option<f64>,
+  #[clap(
+    long,
+    default_value = "24",
+    help = "Split reveals across commits of at most <REVEALS_PER_COMMIT> reveals each, so no commit exceeds bitcoind's default descendant limit of 25 transactions. Each commit is funded from its own confirmed outputs."
+  )]
+  pub(crate) reveals_per_commit: usize,
   #[clap(long, help = "Whether to use only_commit. defualt false, for exsample: only-commit true")]
   pub(crate) only_commit: Option<bool>,
   #[clap(long, help = "Send change_address to <change_address>.")]
   pub(crate) change_address: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Include CBOR metadata from <METADATA>. JSON files ending in .json are converted to CBOR."
+  )]
+  pub(crate) metadata: Option<PathBuf>,
+  #[clap(
+    long,
+    conflicts_with = "file",
+    help = "Inscribe without content, delegating content to <DELEGATE>."
+  )]
+  pub(crate) delegate: Option<InscriptionId>,
+  #[clap(
+    long,
+    help = "Put all <MINT_SIZE> inscriptions in a single reveal transaction, each pointing to its own output."
+  )]
+  pub(crate) single_reveal: bool,
+  #[clap(
+    long,
+    conflicts_with_all = &["file", "delegate", "single-reveal"],
+    help = "Inscribe every file in directory <BATCH>, or every file listed one per line in manifest <BATCH>, packing as many inscriptions into each reveal transaction as MAX_STANDARD_TX_WEIGHT allows."
+  )]
+  pub(crate) batch: Option<PathBuf>,
+  #[clap(
+    long,
+    conflicts_with_all = &["file", "delegate", "batch", "single-reveal", "mint-size", "destination"],
+    help = "Inscribe every item in YAML or JSON manifest <MANIFEST>, each with its own file or text, content type, destination, postage and metadata. Each item gets its own reveal transaction, and items are split across commits of at most <REVEALS_PER_COMMIT> reveals each, so --psbt-out only accepts manifests that fit in a single commit. The whole manifest is validated before anything is signed."
+  )]
+  pub(crate) manifest: Option<PathBuf>,
+  #[clap(
+    long,
+    conflicts_with_all = &["dry-run", "only-commit"],
+    help = "Write unsigned commit transaction to <PSBT_OUT> for an external signer instead of signing and broadcasting it. The signed reveals are recorded for `wallet broadcast`."
+  )]
+  pub(crate) psbt_out: Option<PathBuf>,
+  #[clap(flatten)]
+  pub(crate) policy: ContentPolicy,
+  #[clap(flatten)]
+  pub(crate) coin_control: CoinControl,
+}
+
+enum Commit {
+  Batches(Vec<Vec<(Inscription, TxOut)>>),
+  Copies(u64),
 }
 
 impl Inscribes {
   pub(crate) fn run(self, options: Options) -> Result {
-    let inscription = Inscription::from_file(options.chain(), &self.file)?;
+    if let Some(job) = self.job {
+      return job.run(options);
+    }
+
+    let fee_rate = self
+      .fee_rate
+      .ok_or_else(|| anyhow!("--fee-rate is required"))?;
+
+    if self.reveals_per_commit == 0 {
+      bail!("--reveals-per-commit must be greater than zero");
+    }
+
+    let metadata = self
+      .metadata
+      .as_ref()
+      .map(Inscription::metadata_from_file)
+      .transpose()?;
+
+    let manifest = self
+      .manifest
+      .as_ref()
+      .map(|path| {
+        manifest::Manifest::load(path)?.reveals(
+          options.chain(),
+          path.parent().unwrap_or_else(|| Path::new("")),
+          metadata.clone(),
+          &self.policy,
+        )
+      })
+      .transpose()?;
+
+    let new_inscriptions = match (&manifest, &self.batch, self.delegate, &self.file) {
+      (Some(reveals), ..) => reveals
+        .iter()
+        .map(|(inscription, _)| inscription.clone())
+        .collect(),
+      (None, Some(batch), _, _) => {
+        Self::load_batch(options.chain(), batch, metadata, &self.policy)?
+      }
+      (None, None, Some(delegate), _) => vec![Inscription::from_delegate(delegate, metadata)],
+      (None, None, None, Some(file)) => vec![Inscription::from_file(
+        options.chain(),
+        file,
+        None,
+        metadata,
+        None,
+        false,
+        &self.policy,
+      )?],
+      (None, None, None, None) => {
+        bail!("either <FILE>, --delegate, --batch or --manifest must be given")
+      }
+    };
+
+    let mint_size = self.mint_size.unwrap_or(1);
 
     let index = Index::open(&options)?;
     index.update()?;
 
+    if let Some(delegate) = self.delegate {
+      if index.get_inscription_entry(delegate)?.is_none() {
+        bail!("delegate {delegate} does not exist");
+      }
+    }
+
     let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
 
     let mut utxos;
@@ -103,138 +226,278 @@ impl Inscribes {
     .filter(|(outpoint, _)| !inscribesd_utxos.contains(outpoint))
     .map(|(outpoint, amount)| (*outpoint, *amount))
     .collect();
+    utxos = self.coin_control.filter(&client, utxos, &[])?;
     info!("inscribes utxos:{:?}",utxos);
     if utxos.is_empty() {
       return Err(anyhow!(
         "wallet contains no cardinal utxos"
       ));
     }
-    let (unsigned_commit_tx, reveal_tx_vec,recovery_key_pairs) =
-      Inscribes::create_inscription_transactions(
-        inscription,
-        options.chain().network(),
-        utxos.clone(),
-        reveal_tx_destination,
-        self.mint_size,
-        self.commit_fee_rate.unwrap_or(self.fee_rate),
-        self.fee_rate,
-        self.no_limit,
-        commit_tx_change,
-      )?;
-    let mut fees =Self::calculate_fee(&unsigned_commit_tx, &utxos);
-    for reveal_tx in &reveal_tx_vec {
-      utxos.insert(
-        reveal_tx.input[0].previous_output,
-        Amount::from_sat(
-          unsigned_commit_tx.output[reveal_tx.input[0].previous_output.vout as usize].value,
-        ),
-      );
-      fees=fees+Self::calculate_fee(&reveal_tx, &utxos);
-    }
-    info!("unsigned_commit_tx :{:?} , fees:{:?}",&unsigned_commit_tx.txid(),&fees);
-    if self.dry_run {
-      let mut tx_id_vec=Vec::new();
-      let mut inscription_vec=Vec::new();
-      for reveal_tx in &reveal_tx_vec {
-        tx_id_vec.push(reveal_tx.txid());
-        inscription_vec.push(reveal_tx.txid().into());
-      }
-      print_json(Output {
-        commit: unsigned_commit_tx.txid(),
-        reveal: tx_id_vec,
-        inscription: inscription_vec,
-        fees,
-      })?;
+    let commit_fee_rate = self.commit_fee_rate.unwrap_or(fee_rate);
+
+    let inscription = new_inscriptions[0].clone();
+
+    let postage = TxOut {
+      script_pubkey: reveal_tx_destination.script_pubkey(),
+      value: TransactionBuilder::TARGET_POSTAGE_330.to_sat(),
+    };
+
+    let commits = if let Some(reveals) = manifest {
+      reveals
+        .chunks(self.reveals_per_commit)
+        .map(|reveals| {
+          Commit::Batches(reveals.iter().map(|reveal| vec![reveal.clone()]).collect())
+        })
+        .collect()
+    } else if self.batch.is_some() {
+      let reveals = new_inscriptions
+        .into_iter()
+        .map(|inscription| (inscription, postage.clone()))
+        .collect::<Vec<(Inscription, TxOut)>>();
+
+      let batches = if self.no_limit {
+        vec![reveals]
+      } else {
+        Inscribes::pack_inscriptions(reveals, fee_rate)
+      };
+
+      batches
+        .chunks(self.reveals_per_commit)
+        .map(|batches| Commit::Batches(batches.to_vec()))
+        .collect()
+    } else if self.single_reveal {
+      vec![Commit::Batches(vec![vec![
+        (inscription.clone(), postage);
+        mint_size.try_into().unwrap()
+      ]])]
     } else {
-      if !self.no_backup {
-        for ele in &recovery_key_pairs {
-          backup_recovery_key(&client, ele.clone(), options.chain().network())?; 
-          thread::sleep(Duration::from_millis(100));
+      let reveals_per_commit = u64::try_from(self.reveals_per_commit).unwrap();
+      (0..mint_size)
+        .step_by(self.reveals_per_commit)
+        .map(|start| Commit::Copies(reveals_per_commit.min(mint_size - start)))
+        .collect::<Vec<Commit>>()
+    };
+
+    if commits.is_empty() {
+      bail!("mint size must be greater than zero");
+    }
+
+    // each commit and its reveals are a separate chain of descendants, so
+    // when there are several commits they must not share unconfirmed
+    // ancestors, or together they would exceed bitcoind's descendant limit
+    if commits.len() > 1 {
+      let mut confirmed = BTreeMap::new();
+      for (outpoint, amount) in utxos {
+        let confirmations = client
+          .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+          .map(|tx_out| tx_out.confirmations)
+          .unwrap_or_default();
+
+        if confirmations > 0 {
+          confirmed.insert(outpoint, amount);
         }
       }
-      info!("backup_recovery_key end ");
-      let mut file = match File::create(format!("output_{:?}.txt",&unsigned_commit_tx.txid())) {
-        Ok(file) => file,
-        Err(e) => {
-            info!("create file error: {}", e);
-            return Err(anyhow!(
-              "create file error，commit id:{:?}",&unsigned_commit_tx.txid()
-            ));
-        }
-     };
-      let mut x=0;
+      utxos = confirmed;
+    }
+
+    let mut transactions = Vec::new();
+    let mut fees = 0;
+
+    let commit_count = commits.len();
+
+    for commit in commits {
+      if utxos.is_empty() {
+        bail!(
+          "wallet does not contain enough confirmed cardinal UTXOs to fund {commit_count} commit transactions separately, please add additional funds to wallet."
+        );
+      }
+
+      let (unsigned_commit_tx, reveal_tx_vec, recovery_key_pairs) = match commit {
+        Commit::Batches(batches) => Inscribes::create_batch_transactions(
+          batches,
+          options.chain().network(),
+          utxos.clone(),
+          commit_fee_rate,
+          fee_rate,
+          self.no_limit,
+          commit_tx_change.clone(),
+          self.coin_control.coin_selection,
+        )?,
+        Commit::Copies(copies) => Inscribes::create_inscription_transactions(
+          inscription.clone(),
+          options.chain().network(),
+          utxos.clone(),
+          reveal_tx_destination.clone(),
+          copies,
+          commit_fee_rate,
+          fee_rate,
+          self.no_limit,
+          commit_tx_change.clone(),
+          self.coin_control.coin_selection,
+        )?,
+      };
+
+      fees += Self::calculate_fee(&unsigned_commit_tx, &utxos);
       for reveal_tx in &reveal_tx_vec {
-        let tx_id=reveal_tx.txid();
-        let hex_str=reveal_tx.raw_hex();
-        //写入文件
-        let privekey=recovery_key_pairs.get(x).unwrap().to_inner().secret_key().secret_bytes();
-        x=x+1;
-        match writeln!(file, "{} {} {:?}", tx_id,hex_str,privekey) {
-            Ok(_) => (), // 写入成功
-            Err(e) => {
-              info!("write file error: {}", e);
-              return Err(anyhow!(
-                "write error id:{:?}",&unsigned_commit_tx.txid()
-              ));
-            }
-        }
+        utxos.insert(
+          reveal_tx.input[0].previous_output,
+          Amount::from_sat(
+            unsigned_commit_tx.output[reveal_tx.input[0].previous_output.vout as usize].value,
+          ),
+        );
+        fees += Self::calculate_fee(reveal_tx, &utxos);
       }
-      // 关闭文件
-      match file.flush() {
-          Ok(_) => (), // 刷新缓冲区成功
-          Err(e) => {
-            info!("flush file error: {}", e);
-            return Err(anyhow!(
-              "flush error id:{:?}",&unsigned_commit_tx.txid()
-            ));
-          }
+
+      // later commits are funded from the remaining confirmed outputs, never
+      // from this commit's change, so no two commits share a descendant chain
+      for txin in unsigned_commit_tx
+        .input
+        .iter()
+        .chain(reveal_tx_vec.iter().map(|reveal_tx| &reveal_tx.input[0]))
+      {
+        utxos.remove(&txin.previous_output);
       }
-      info!("flush file success :{:?}",unsigned_commit_tx.txid());
-      let signed_raw_commit_tx = client
-        .sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?
-        .hex;
-      let commit = client
-      .send_raw_transaction(&signed_raw_commit_tx)
-      .context("Failed to send commit transaction")?;
-      info!("commit send success:{:?}",&commit);
-      let mut tx_id_vec=Vec::new();
-      let mut inscription_vec=Vec::new();
-      let mut only_commit= false;
-      if !self.only_commit.is_none() {
-         if self.only_commit.unwrap() {
-          only_commit=true;
-         }
+
+      transactions.push((unsigned_commit_tx, reveal_tx_vec, recovery_key_pairs));
+    }
+
+    info!(
+      "unsigned commit txs: {:?}, fees: {fees}",
+      transactions
+        .iter()
+        .map(|(commit_tx, ..)| commit_tx.txid())
+        .collect::<Vec<Txid>>()
+    );
+
+    let mut commit_vec = Vec::new();
+    let mut tx_id_vec = Vec::new();
+    let mut inscription_vec = Vec::new();
+
+    if self.dry_run {
+      for (unsigned_commit_tx, reveal_tx_vec, _) in &transactions {
+        commit_vec.push(unsigned_commit_tx.txid());
+        for reveal_tx in reveal_tx_vec {
+          tx_id_vec.push(reveal_tx.txid());
+          inscription_vec.extend(Self::inscription_ids(reveal_tx));
+        }
       }
-      if !only_commit {
-        let mut sleep=1200f64 ;
-        if !self.sleep.is_none() {
-          sleep=self.sleep.unwrap()*1000.0;
+    } else {
+      if self.psbt_out.is_some() && transactions.len() > 1 {
+        bail!(
+          "--psbt-out needs a single commit but inscriptions are split across {} commits",
+          transactions.len()
+        );
+      }
+
+      for (unsigned_commit_tx, reveal_tx_vec, recovery_key_pairs) in &transactions {
+        if !self.no_backup {
+          backup_recovery_keys(&client, recovery_key_pairs, options.chain().network())?;
+        }
+
+        KeyJournal {
+          commit: unsigned_commit_tx.txid(),
+          keys: reveal_tx_vec
+            .iter()
+            .zip(recovery_key_pairs)
+            .map(|(reveal_tx, key_pair)| {
+              RecoveryKey::new(
+                reveal_tx.input[0].previous_output.vout,
+                key_pair,
+                options.chain().network(),
+              )
+            })
+            .collect(),
         }
-        let mut i=0;
-        for reveal_tx in &reveal_tx_vec {
-          let reveal = client
-            .send_raw_transaction(reveal_tx)
-            .context("Failed to send reveal transaction")?;
-          tx_id_vec.push(reveal);
-          inscription_vec.push(reveal.into());
-          thread::sleep(Duration::from_millis(1200));
-          i=i+1;
-          if i%20 == 0 {
-            info!("reveal_tx 20 send success , last tx:{:?}",&reveal_tx);
-            thread::sleep(Duration::from_millis(sleep as u64));
+        .save(&options, &client)?;
+
+        let signed_raw_commit_tx = match &self.psbt_out {
+          Some(_) => bitcoin::consensus::serialize(unsigned_commit_tx),
+          None => {
+            client
+              .sign_raw_transaction_with_wallet(unsigned_commit_tx, None, None)?
+              .hex
+          }
+        };
+
+        let mut job = job::Job {
+          commit: unsigned_commit_tx.txid(),
+          commit_tx: hex::encode(&signed_raw_commit_tx),
+          commit_state: job::State::Unsent,
+          recovery_keys_backed_up: !self.no_backup,
+          reveals: reveal_tx_vec
+            .iter()
+            .map(|reveal_tx| {
+              let vout = reveal_tx.input[0].previous_output.vout;
+              Ok(job::Reveal {
+                txid: reveal_tx.txid(),
+                tx: reveal_tx.raw_hex(),
+                inscriptions: Self::inscription_ids(reveal_tx),
+                recovery_address: Address::from_script(
+                  &unsigned_commit_tx.output[vout as usize].script_pubkey,
+                  options.chain().network(),
+                )?
+                .to_string(),
+                state: job::State::Unsent,
+              })
+            })
+            .collect::<Result<Vec<job::Reveal>>>()?,
+        };
+
+        job.save(&options)?;
+
+        if let Some(psbt_out) = &self.psbt_out {
+          super::broadcast::write_psbt(&client, unsigned_commit_tx, psbt_out)?;
+          commit_vec.push(job.commit);
+          for reveal_tx in reveal_tx_vec {
+            tx_id_vec.push(reveal_tx.txid());
+            inscription_vec.extend(Self::inscription_ids(reveal_tx));
           }
+          continue;
+        }
+
+        broadcast::send(&client, &signed_raw_commit_tx, job.commit, "commit")?;
+
+        job.commit_state = job::State::Broadcast;
+        job.save(&options)?;
+
+        info!("commit send success:{:?}", job.commit);
+        commit_vec.push(job.commit);
+
+        if self.only_commit.unwrap_or_default() {
+          continue;
+        }
+
+        for (i, reveal_tx) in reveal_tx_vec.iter().enumerate() {
+          broadcast::send(&client, reveal_tx, reveal_tx.txid(), "reveal")?;
+          job.reveals[i].state = job::State::Broadcast;
+          job.save(&options)?;
+          tx_id_vec.push(reveal_tx.txid());
+          inscription_vec.extend(Self::inscription_ids(reveal_tx));
         }
       }
-      print_json(Output {
-        commit,
-        reveal: tx_id_vec,
-        inscription: inscription_vec,
-        fees,
-      })?;
     }
+
+    print_json(Output {
+      commit: commit_vec[0],
+      commits: commit_vec,
+      reveal: tx_id_vec,
+      inscription: inscription_vec,
+      fees,
+    })?;
+
     Ok(())
   }
 
+  fn inscription_ids(reveal_tx: &Transaction) -> Vec<InscriptionId> {
+    let txid = reveal_tx.txid();
+    (0..reveal_tx.output.len())
+      .map(|index| InscriptionId {
+        txid,
+        index: index.try_into().unwrap(),
+      })
+      .collect()
+  }
+
   fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, Amount>) -> u64 {
     tx.input
       .iter()
@@ -254,6 +517,7 @@ impl Inscribes {
     reveal_fee_rate: FeeRate,
     no_limit: bool,
     change_address: Address,
+    coin_selection: CoinSelection,
   ) -> Result<(Transaction, Vec<Transaction>,Vec<TweakedKeyPair>)> {
     
     let secp256k1 = Secp256k1::new();
@@ -279,19 +543,13 @@ impl Inscribes {
       &rng_control_block,
       reveal_fee_rate,
       OutPoint::null(),
-      TxOut {
+      vec![TxOut {
         script_pubkey: destination.script_pubkey(),
         value: 0,
-      },
+      }],
       &reveal_script_fee,
     );
 
-    //计算reveal_fee 计算拆分多少个utxo
-    let sum_amount = &utxos
-      .iter()
-      .map(|(_address, amount)| *amount)
-      .sum::<Amount>();
-
     let mint_amount = reveal_fee + TransactionBuilder::TARGET_POSTAGE_330;
     let mut outputs: Vec<(Address, Amount)> = Vec::new();
     let mut key_pairs=Vec::new();
@@ -313,6 +571,26 @@ impl Inscribes {
       let split_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), network);
       outputs.push((split_tx_address.clone(), mint_amount));
     }
+
+    let utxos = Self::select_utxos(
+      &utxos,
+      outputs
+        .iter()
+        .map(|(address, amount)| TxOut {
+          value: amount.to_sat(),
+          script_pubkey: address.script_pubkey(),
+        })
+        .collect(),
+      &change_address,
+      commit_fee_rate,
+      coin_selection,
+    )?;
+
+    //计算reveal_fee 计算拆分多少个utxo
+    let sum_amount = &utxos
+      .iter()
+      .map(|(_address, amount)| *amount)
+      .sum::<Amount>();
     //减去gas费 剩余的打回 找零地址
     let mut fee_outputs=outputs.clone();
     fee_outputs.push((change_address.clone(),Amount::from_sat(sum_amount.to_sat() - mint_amount.to_sat()*mint_size)));
@@ -337,14 +615,16 @@ impl Inscribes {
         })
         .collect(),
     };
-    let fee=commit_fee_rate.fee(transaction.vsize()+17);
+    let fee = Self::commit_fee(&transaction, commit_fee_rate);
     let change_fee = Amount::from_sat(sum_amount.to_sat() - mint_amount.to_sat()*mint_size-fee.to_sat());
     if change_fee.to_sat()> i64::MAX as u64 {
       return Err(anyhow!(
         "wallet contains no cardinal utxos"
       ));
     }
-    outputs.push((change_address.clone(),change_fee));
+    if change_fee >= change_address.script_pubkey().dust_value() {
+      outputs.push((change_address.clone(), change_fee));
+    }
     info!("transaction vsize:{:?} ,reveal_fee_rate:{:?} fee :{:?}",transaction.vsize()+17,reveal_fee_rate,fee);
     //真实的消息
     let transaction = Transaction {
@@ -402,10 +682,10 @@ impl Inscribes {
           txid: transaction.txid(),
           vout: ele.0 as u32,
         },
-        TxOut {
+        vec![TxOut {
           script_pubkey: destination.script_pubkey(),
           value: ele.1.value,
-        },
+        }],
         &reveal_script,
       );
 
@@ -463,11 +743,368 @@ impl Inscribes {
     Ok((transaction, reveal_tx_vec,recovery_key_pairs))
   }
 
+  fn load_batch(
+    chain: Chain,
+    path: &Path,
+    metadata: Option<Vec<u8>>,
+    policy: &ContentPolicy,
+  ) -> Result<Vec<Inscription>> {
+    let files = if path.is_dir() {
+      let mut files = fs::read_dir(path)
+        .with_context(|| format!("failed to read batch directory {}", path.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?
+        .into_iter()
+        .filter(|path| path.is_file())
+        .collect::<Vec<PathBuf>>();
+      files.sort();
+      files
+    } else {
+      let manifest = fs::read_to_string(path)
+        .with_context(|| format!("failed to read batch manifest {}", path.display()))?;
+      let base = path.parent().unwrap_or_else(|| Path::new(""));
+      manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .collect()
+    };
+
+    if files.is_empty() {
+      bail!("batch {} contains no files", path.display());
+    }
+
+    files
+      .iter()
+      .map(|file| Inscription::from_file(chain, file, None, metadata.clone(), None, false, policy))
+      .collect()
+  }
+
+  fn batch_reveal_script(reveals: &[(Inscription, TxOut)], public_key: XOnlyPublicKey) -> ScriptBuf {
+    let mut offset = 0;
+
+    // every inscription after the first points to the first sat of its own output
+    let inscriptions = reveals
+      .iter()
+      .enumerate()
+      .map(|(i, (inscription, output))| {
+        let inscription = match i {
+          0 => inscription.clone(),
+          _ => inscription.clone().with_pointer(offset),
+        };
+        offset += output.value;
+        inscription
+      })
+      .collect::<Vec<Inscription>>();
+
+    Inscription::append_batch_reveal_script(
+      &inscriptions,
+      script::Builder::new()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    )
+  }
+
+  fn batch_reveal_outputs(reveals: &[(Inscription, TxOut)]) -> Vec<TxOut> {
+    reveals.iter().map(|(_, output)| output.clone()).collect()
+  }
+
+  fn pack_inscriptions(
+    reveals: Vec<(Inscription, TxOut)>,
+    fee_rate: FeeRate,
+  ) -> Vec<Vec<(Inscription, TxOut)>> {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let max_weight = bitcoin::Weight::from_wu(MAX_STANDARD_TX_WEIGHT.into());
+
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+
+    for reveal in reveals {
+      batch.push(reveal);
+
+      if batch.len() < 2 {
+        continue;
+      }
+
+      let reveal_script = Self::batch_reveal_script(&batch, public_key);
+
+      let control_block = TaprootBuilder::new()
+        .add_leaf(0, reveal_script.clone())
+        .expect("adding leaf should work")
+        .finalize(&secp256k1, public_key)
+        .expect("finalizing taproot builder should work")
+        .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+        .expect("should compute control block");
+
+      let (mut reveal_tx, _) = Self::build_reveal_transaction(
+        &control_block,
+        fee_rate,
+        OutPoint::null(),
+        Self::batch_reveal_outputs(&batch),
+        &reveal_script,
+      );
+
+      reveal_tx.input[0].witness.push(
+        Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
+          .unwrap()
+          .as_ref(),
+      );
+      reveal_tx.input[0].witness.push(reveal_script);
+      reveal_tx.input[0].witness.push(control_block.serialize());
+
+      if reveal_tx.weight() > max_weight {
+        let last = batch.pop().unwrap();
+        batches.push(std::mem::take(&mut batch));
+        batch.push(last);
+      }
+    }
+
+    if !batch.is_empty() {
+      batches.push(batch);
+    }
+
+    batches
+  }
+
+  fn create_batch_transactions(
+    batches: Vec<Vec<(Inscription, TxOut)>>,
+    network: Network,
+    utxos: BTreeMap<OutPoint, Amount>,
+    commit_fee_rate: FeeRate,
+    reveal_fee_rate: FeeRate,
+    no_limit: bool,
+    change_address: Address,
+    coin_selection: CoinSelection,
+  ) -> Result<(Transaction, Vec<Transaction>, Vec<TweakedKeyPair>)> {
+    if batches.is_empty() || batches.iter().any(Vec::is_empty) {
+      bail!("mint size must be greater than zero");
+    }
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut reveals = Vec::new();
+
+    for batch in &batches {
+      let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+      let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+      let reveal_script = Self::batch_reveal_script(batch, public_key);
+
+      let taproot_spend_info = TaprootBuilder::new()
+        .add_leaf(0, reveal_script.clone())
+        .expect("adding leaf should work")
+        .finalize(&secp256k1, public_key)
+        .expect("finalizing taproot builder should work");
+
+      let control_block = taproot_spend_info
+        .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+        .expect("should compute control block");
+
+      let reveal_outputs = Self::batch_reveal_outputs(batch);
+
+      let (_, reveal_fee) = Self::build_reveal_transaction(
+        &control_block,
+        reveal_fee_rate,
+        OutPoint::null(),
+        reveal_outputs.clone(),
+        &reveal_script,
+      );
+
+      let commit_output = TxOut {
+        script_pubkey: Address::p2tr_tweaked(taproot_spend_info.output_key(), network)
+          .script_pubkey(),
+        value: reveal_outputs
+          .iter()
+          .map(|output| output.value)
+          .sum::<u64>()
+          + reveal_fee.to_sat(),
+      };
+
+      reveals.push((
+        key_pair,
+        reveal_script,
+        taproot_spend_info,
+        control_block,
+        reveal_outputs,
+        commit_output,
+      ));
+    }
+
+    let utxos = Self::select_utxos(
+      &utxos,
+      reveals
+        .iter()
+        .map(|(.., commit_output)| commit_output.clone())
+        .collect(),
+      &change_address,
+      commit_fee_rate,
+      coin_selection,
+    )?;
+
+    let input_value = utxos.values().map(|amount| amount.to_sat()).sum::<u64>();
+
+    let mut commit_tx = Transaction {
+      version: 1,
+      lock_time: LockTime::ZERO,
+      input: utxos
+        .keys()
+        .map(|outpoint| TxIn {
+          previous_output: *outpoint,
+          script_sig: script::Builder::new().into_script(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: reveals
+        .iter()
+        .map(|(.., commit_output)| commit_output.clone())
+        .chain([TxOut {
+          script_pubkey: change_address.script_pubkey(),
+          value: 0,
+        }])
+        .collect(),
+    };
+
+    let commit_value = reveals
+      .iter()
+      .map(|(.., commit_output)| commit_output.value)
+      .sum::<u64>();
+
+    let commit_fee = Self::commit_fee(&commit_tx, commit_fee_rate);
+
+    let change = commit_tx.output.last_mut().unwrap();
+
+    change.value = input_value
+      .checked_sub(commit_value + commit_fee.to_sat())
+      .context(
+        "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.",
+      )?;
+
+    if change.value < change.script_pubkey.dust_value().to_sat() {
+      commit_tx.output.pop();
+    }
+
+    let commit_txid = commit_tx.txid();
+
+    let mut reveal_tx_vec = Vec::new();
+    let mut recovery_key_pairs = Vec::new();
+
+    for (
+      vout,
+      (key_pair, reveal_script, taproot_spend_info, control_block, reveal_outputs, commit_output),
+    ) in reveals.into_iter().enumerate()
+    {
+      let (mut reveal_tx, _) = Self::build_reveal_transaction(
+        &control_block,
+        reveal_fee_rate,
+        OutPoint {
+          txid: commit_txid,
+          vout: vout.try_into().unwrap(),
+        },
+        reveal_outputs,
+        &reveal_script,
+      );
+
+      let mut sighash_cache = SighashCache::new(&mut reveal_tx);
+
+      let signature_hash = sighash_cache
+        .taproot_script_spend_signature_hash(
+          0,
+          &Prevouts::All(&[commit_output]),
+          TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
+          TapSighashType::Default,
+        )
+        .expect("signature hash should compute");
+
+      let signature = secp256k1.sign_schnorr(
+        &secp256k1::Message::from_slice(signature_hash.as_ref())
+          .expect("should be cryptographically secure hash"),
+        &key_pair,
+      );
+
+      let witness = sighash_cache
+        .witness_mut(0)
+        .expect("getting mutable witness reference should work");
+      witness.push(signature.as_ref());
+      witness.push(reveal_script);
+      witness.push(control_block.serialize());
+
+      let reveal_weight = reveal_tx.weight();
+
+      if !no_limit && reveal_weight > bitcoin::Weight::from_wu(MAX_STANDARD_TX_WEIGHT.into()) {
+        bail!(
+          "reveal transaction weight greater than {MAX_STANDARD_TX_WEIGHT} (MAX_STANDARD_TX_WEIGHT): {reveal_weight}"
+        );
+      }
+
+      reveal_tx_vec.push(reveal_tx);
+      recovery_key_pairs.push(key_pair.tap_tweak(&secp256k1, taproot_spend_info.merkle_root()));
+    }
+
+    Ok((commit_tx, reveal_tx_vec, recovery_key_pairs))
+  }
+
+  fn select_utxos(
+    utxos: &BTreeMap<OutPoint, Amount>,
+    commit_outputs: Vec<TxOut>,
+    change_address: &Address,
+    commit_fee_rate: FeeRate,
+    coin_selection: CoinSelection,
+  ) -> Result<BTreeMap<OutPoint, Amount>> {
+    let commit_value = commit_outputs.iter().map(|output| output.value).sum::<u64>();
+
+    // the commit transaction without inputs, whose fee the selected inputs
+    // must cover on top of their own
+    let skeleton = Transaction {
+      version: 1,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: commit_outputs
+        .into_iter()
+        .chain([TxOut {
+          script_pubkey: change_address.script_pubkey(),
+          value: 0,
+        }])
+        .collect(),
+    };
+
+    coin_selection
+      .select(
+        utxos,
+        Amount::from_sat(commit_value) + Self::commit_fee(&skeleton, commit_fee_rate),
+        commit_fee_rate,
+        change_address.script_pubkey().dust_value()
+          + commit_fee_rate.fee(TransactionBuilder::ADDITIONAL_INPUT_VBYTES),
+      )
+      .ok_or_else(|| {
+        anyhow!(
+          "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet."
+        )
+      })
+  }
+
+  /// Fee for `commit_tx`, charging each input `ADDITIONAL_INPUT_VBYTES` so
+  /// that it includes the witness, the same as coin selection does.
+  fn commit_fee(commit_tx: &Transaction, fee_rate: FeeRate) -> Amount {
+    let skeleton = Transaction {
+      input: Vec::new(),
+      ..commit_tx.clone()
+    };
+
+    fee_rate.fee(skeleton.vsize() + 17)
+      + fee_rate.fee(TransactionBuilder::ADDITIONAL_INPUT_VBYTES)
+        * u64::try_from(commit_tx.input.len()).unwrap()
+  }
+
   fn build_reveal_transaction(
     control_block: &ControlBlock,
     fee_rate: FeeRate,
     input: OutPoint,
-    output: TxOut,
+    outputs: Vec<TxOut>,
     script: &Script,
   ) -> (Transaction, Amount) {
     let reveal_tx = Transaction {
@@ -477,7 +1114,7 @@ impl Inscribes {
         witness: Witness::new(),
         sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
       }],
-      output: vec![output],
+      output: outputs,
       lock_time: LockTime::ZERO,
       version: 1,
     };
@@ -500,24 +1137,37 @@ impl Inscribes {
   }
 }
 
-fn backup_recovery_key(
+fn backup_recovery_keys(
   client: &Client,
-  recovery_key_pair: TweakedKeyPair,
+  recovery_key_pairs: &[TweakedKeyPair],
   network: Network,
 ) -> Result {
-  let recovery_private_key = PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network);
-
-  let info = client.get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
-
-  let response = client.import_descriptors(ImportDescriptors {
-    descriptor: format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
-    timestamp: Timestamp::Now,
-    active: Some(false),
-    range: None,
-    next_index: None,
-    internal: Some(false),
-    label: Some("commit tx recovery key".to_string()),
-  })?;
+  let descriptors = recovery_key_pairs
+    .iter()
+    .map(|recovery_key_pair| {
+      let recovery_private_key =
+        PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network);
+
+      let info =
+        client.get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
+
+      Ok(ImportDescriptors {
+        descriptor: format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
+        timestamp: Timestamp::Now,
+        active: Some(false),
+        range: None,
+        next_index: None,
+        internal: Some(false),
+        label: Some("commit tx recovery key".to_string()),
+      })
+    })
+    .collect::<Result<Vec<ImportDescriptors>>>()?;
+
+  // import all keys in a single call, since each importdescriptors call triggers a rescan
+  let response = client.call::<Vec<ImportMultiResult>>(
+    "importdescriptors",
+    &[serde_json::to_value(descriptors)?],
+  )?;
 
   for result in response {
     if !result.success {
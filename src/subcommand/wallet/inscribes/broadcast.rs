@@ -0,0 +1,114 @@
+use {
+  super::*,
+  bitcoincore_rpc::jsonrpc::error::{Error as JsonRpcError, RpcError},
+};
+
+// bitcoind rejects transactions that fail mempool policy with these codes
+const RPC_VERIFY_ERROR: i32 = -25;
+const RPC_VERIFY_REJECTED: i32 = -26;
+const RPC_VERIFY_ALREADY_IN_CHAIN: i32 = -27;
+
+#[derive(Debug, PartialEq)]
+enum Rejection {
+  AlreadyInChain,
+  TooLongMempoolChain(String),
+  Policy(String),
+}
+
+impl Rejection {
+  fn from_error(err: &bitcoincore_rpc::Error) -> Option<Self> {
+    let bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError { code, message, .. })) = err
+    else {
+      return None;
+    };
+
+    match *code {
+      RPC_VERIFY_ALREADY_IN_CHAIN => Some(Self::AlreadyInChain),
+      RPC_VERIFY_REJECTED if message.starts_with("too-long-mempool-chain") => {
+        Some(Self::TooLongMempoolChain(message.clone()))
+      }
+      RPC_VERIFY_ERROR | RPC_VERIFY_REJECTED => Some(Self::Policy(message.clone())),
+      _ => None,
+    }
+  }
+}
+
+/// Broadcast `tx`, waiting for the next block whenever it would exceed
+/// bitcoind's ancestor or descendant limits and retrying once it arrives.
+pub(crate) fn send(client: &Client, tx: impl RawTx + Copy, txid: Txid, kind: &str) -> Result {
+  loop {
+    let err = match client.send_raw_transaction(tx) {
+      Ok(_) => return Ok(()),
+      Err(err) => err,
+    };
+
+    match Rejection::from_error(&err) {
+      Some(Rejection::AlreadyInChain) => return Ok(()),
+      Some(Rejection::TooLongMempoolChain(reason)) => wait_for_block(client, txid, kind, &reason)?,
+      Some(Rejection::Policy(reason)) => {
+        bail!("{kind} transaction {txid} rejected by mempool policy: {reason}")
+      }
+      None => return Err(err).with_context(|| format!("Failed to send {kind} transaction {txid}")),
+    }
+  }
+}
+
+fn wait_for_block(client: &Client, txid: Txid, kind: &str, reason: &str) -> Result {
+  let height = client.get_block_count()?;
+
+  eprintln!(
+    "{kind} transaction {txid} rejected with `{reason}`, waiting for block {}",
+    height + 1
+  );
+
+  let interval = if integration_test() {
+    Duration::from_millis(100)
+  } else {
+    Duration::from_secs(10)
+  };
+
+  while client.get_block_count()? <= height {
+    thread::sleep(interval);
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rpc_error(code: i32, message: &str) -> bitcoincore_rpc::Error {
+    bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError {
+      code,
+      message: message.into(),
+      data: None,
+    }))
+  }
+
+  #[test]
+  fn rejections() {
+    assert_eq!(
+      Rejection::from_error(&rpc_error(
+        -26,
+        "too-long-mempool-chain, too many descendants for tx foo [limit: 25]"
+      )),
+      Some(Rejection::TooLongMempoolChain(
+        "too-long-mempool-chain, too many descendants for tx foo [limit: 25]".into()
+      ))
+    );
+    assert_eq!(
+      Rejection::from_error(&rpc_error(-26, "min relay fee not met")),
+      Some(Rejection::Policy("min relay fee not met".into()))
+    );
+    assert_eq!(
+      Rejection::from_error(&rpc_error(-25, "bad-txns-inputs-missingorspent")),
+      Some(Rejection::Policy("bad-txns-inputs-missingorspent".into()))
+    );
+    assert_eq!(
+      Rejection::from_error(&rpc_error(-27, "Transaction already in block chain")),
+      Some(Rejection::AlreadyInChain)
+    );
+    assert_eq!(Rejection::from_error(&rpc_error(-8, "foo")), None);
+  }
+}
@@ -0,0 +1,197 @@
+use {
+  super::*,
+  bitcoincore_rpc::jsonrpc::error::{Error as JsonRpcError, RpcError},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum JobCommand {
+  #[clap(about = "Broadcast unsent or dropped transactions of batch inscribe job <JOB>")]
+  Resume { job: Txid },
+  #[clap(about = "Show broadcast and confirmation state of batch inscribe job <JOB>")]
+  Status { job: Txid },
+}
+
+impl JobCommand {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    match self {
+      Self::Resume { job } => {
+        let mut job = Job::load(&options, job)?;
+        job.refresh(&client)?;
+        job.save(&options)?;
+        job.resume(&options, &client)?;
+        print_json(job.status())
+      }
+      Self::Status { job } => {
+        let mut job = Job::load(&options, job)?;
+        job.refresh(&client)?;
+        job.save(&options)?;
+        print_json(job.status())
+      }
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum State {
+  Unsent,
+  Broadcast,
+  Confirmed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Reveal {
+  pub(crate) txid: Txid,
+  pub(crate) tx: String,
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) recovery_address: String,
+  pub(crate) state: State,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Job {
+  pub(crate) commit: Txid,
+  pub(crate) commit_tx: String,
+  pub(crate) commit_state: State,
+  pub(crate) recovery_keys_backed_up: bool,
+  pub(crate) reveals: Vec<Reveal>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RevealStatus {
+  pub txid: Txid,
+  pub state: State,
+  pub inscriptions: Vec<InscriptionId>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+  pub job: Txid,
+  pub commit_state: State,
+  pub reveals: Vec<RevealStatus>,
+}
+
+impl Job {
+  fn path(options: &Options, commit: Txid) -> Result<PathBuf> {
+    Ok(
+      options
+        .data_dir()?
+        .join("inscribes")
+        .join(format!("{commit}.json")),
+    )
+  }
+
+  pub(crate) fn exists(options: &Options, commit: Txid) -> Result<bool> {
+    Ok(Self::path(options, commit)?.is_file())
+  }
+
+  pub(crate) fn load(options: &Options, commit: Txid) -> Result<Self> {
+    let path = Self::path(options, commit)?;
+
+    let json = fs::read_to_string(&path)
+      .with_context(|| format!("no batch inscribe job {commit} in {}", path.display()))?;
+
+    serde_json::from_str(&json)
+      .with_context(|| format!("failed to parse batch inscribe job {}", path.display()))
+  }
+
+  pub(crate) fn save(&self, options: &Options) -> Result {
+    let path = Self::path(options, self.commit)?;
+
+    fs::create_dir_all(path.parent().unwrap())?;
+
+    // write to a temporary file first so a crash never leaves a truncated job behind
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+    fs::rename(&tmp, &path)?;
+
+    Ok(())
+  }
+
+  pub(crate) fn remove(options: &Options, commit: Txid) -> Result {
+    Ok(fs::remove_file(Self::path(options, commit)?)?)
+  }
+
+  fn state(client: &Client, txid: Txid) -> Result<State> {
+    match client.get_raw_transaction_info(&txid, None) {
+      Ok(info) if info.confirmations.unwrap_or_default() > 0 => Ok(State::Confirmed),
+      Ok(_) => Ok(State::Broadcast),
+      Err(bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError {
+        code: -5 | -8, ..
+      }))) => Self::confirmed_state(client, txid),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  // without -txindex bitcoind only looks up mempool transactions, so confirmed
+  // transactions must be found through the wallet or the UTXO set instead
+  fn confirmed_state(client: &Client, txid: Txid) -> Result<State> {
+    match client.get_transaction(&txid, None) {
+      Ok(info) if info.info.confirmations > 0 => return Ok(State::Confirmed),
+      Ok(_) => {}
+      Err(bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError {
+        code: -5 | -8, ..
+      }))) => {}
+      Err(err) => return Err(err.into()),
+    }
+
+    if client.get_tx_out(&txid, 0, Some(false))?.is_some() {
+      return Ok(State::Confirmed);
+    }
+
+    Ok(State::Unsent)
+  }
+
+  pub(crate) fn refresh(&mut self, client: &Client) -> Result {
+    self.commit_state = Self::state(client, self.commit)?;
+
+    for reveal in &mut self.reveals {
+      reveal.state = Self::state(client, reveal.txid)?;
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn resume(&mut self, options: &Options, client: &Client) -> Result {
+    if self.commit_state == State::Unsent {
+      broadcast::send(client, self.commit_tx.as_str(), self.commit, "commit")?;
+      self.commit_state = State::Broadcast;
+      self.save(options)?;
+    }
+
+    for i in 0..self.reveals.len() {
+      if self.reveals[i].state != State::Unsent {
+        continue;
+      }
+
+      broadcast::send(
+        client,
+        self.reveals[i].tx.as_str(),
+        self.reveals[i].txid,
+        "reveal",
+      )?;
+      self.reveals[i].state = State::Broadcast;
+      self.save(options)?;
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn status(&self) -> Status {
+    Status {
+      job: self.commit,
+      commit_state: self.commit_state,
+      reveals: self
+        .reveals
+        .iter()
+        .map(|reveal| RevealStatus {
+          txid: reveal.txid,
+          state: reveal.state,
+          inscriptions: reveal.inscriptions.clone(),
+        })
+        .collect(),
+    }
+  }
+}
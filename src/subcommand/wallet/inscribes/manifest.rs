@@ -0,0 +1,256 @@
+use super::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Manifest {
+  pub(crate) postage: Option<u64>,
+  pub(crate) inscriptions: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Item {
+  pub(crate) file: Option<PathBuf>,
+  pub(crate) text: Option<String>,
+  pub(crate) content_type: Option<String>,
+  pub(crate) destination: Address<NetworkUnchecked>,
+  pub(crate) postage: Option<u64>,
+  pub(crate) metadata: Option<serde_yaml::Value>,
+}
+
+impl Manifest {
+  pub(crate) fn load(path: &Path) -> Result<Self> {
+    // JSON manifests parse as YAML too
+    serde_yaml::from_reader(
+      File::open(path).with_context(|| format!("failed to open manifest {}", path.display()))?,
+    )
+    .with_context(|| format!("failed to parse manifest {}", path.display()))
+  }
+
+  /// Build one inscription and reveal output per item, checking every item
+  /// before anything is signed. Relative file paths are resolved against
+  /// `base`.
+  pub(crate) fn reveals(
+    self,
+    chain: Chain,
+    base: &Path,
+    metadata: Option<Vec<u8>>,
+    policy: &ContentPolicy,
+  ) -> Result<Vec<(Inscription, TxOut)>> {
+    if self.inscriptions.is_empty() {
+      bail!("manifest contains no inscriptions");
+    }
+
+    let mut names = BTreeMap::new();
+    let mut reveals = Vec::new();
+
+    for (i, item) in self.inscriptions.into_iter().enumerate() {
+      let destination = item
+        .destination
+        .require_network(chain.network())
+        .with_context(|| format!("manifest inscription {i} has destination for wrong network"))?;
+
+      let postage = Amount::from_sat(
+        item
+          .postage
+          .or(self.postage)
+          .unwrap_or(TransactionBuilder::TARGET_POSTAGE_330.to_sat()),
+      );
+
+      let dust = destination.script_pubkey().dust_value();
+
+      if postage < dust {
+        bail!("manifest inscription {i} postage of {postage} is below dust limit of {dust}");
+      }
+
+      let metadata = match item.metadata {
+        Some(value) => {
+          let mut cbor = Vec::new();
+          ciborium::into_writer(&value, &mut cbor)?;
+          Some(cbor)
+        }
+        None => metadata.clone(),
+      };
+
+      let policy = ContentPolicy {
+        content_type: item.content_type.or(policy.content_type.clone()),
+        max_body_size: policy.max_body_size,
+      };
+
+      let inscription = match (item.file, item.text) {
+        (Some(file), None) => {
+          Inscription::from_file(chain, base.join(file), None, metadata, None, false, &policy)?
+        }
+        (None, Some(text)) => Inscription::from_text(
+          chain,
+          text,
+          policy.content_type.clone(),
+          metadata,
+          &policy,
+        )?,
+        _ => bail!("manifest inscription {i} must have exactly one of `file` or `text`"),
+      };
+
+      if inscription
+        .content_type()
+        .map(|content_type| content_type.starts_with("text/plain"))
+        .unwrap_or_default()
+      {
+        if let Some(name) = inscription
+          .body()
+          .and_then(|body| std::str::from_utf8(body).ok())
+          .filter(|text| text.ends_with(".btc"))
+        {
+          if let Some(first) = names.insert(name.to_string(), i) {
+            bail!("manifest inscriptions {first} and {i} both inscribe name {name}");
+          }
+        }
+      }
+
+      reveals.push((
+        inscription,
+        TxOut {
+          script_pubkey: destination.script_pubkey(),
+          value: postage.to_sat(),
+        },
+      ));
+    }
+
+    Ok(reveals)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn reveals(manifest: &str) -> Result<Vec<(Inscription, TxOut)>> {
+    serde_yaml::from_str::<Manifest>(manifest).unwrap().reveals(
+      Chain::Testnet,
+      Path::new(""),
+      None,
+      &ContentPolicy::default(),
+    )
+  }
+
+  #[test]
+  fn items_have_own_destination_and_postage() {
+    let reveals = reveals(&format!(
+      "postage: 1000
+inscriptions:
+- text: foo.btc
+  destination: {}
+- text: bar.btc
+  destination: {}
+  postage: 2000
+",
+      recipient(),
+      change(0),
+    ))
+    .unwrap();
+
+    assert_eq!(reveals.len(), 2);
+    assert_eq!(reveals[0].0.body(), Some(b"foo.btc".as_slice()));
+    assert_eq!(
+      reveals[0].0.content_type(),
+      Some("text/plain;charset=utf-8")
+    );
+    assert_eq!(reveals[0].1.script_pubkey, recipient().script_pubkey());
+    assert_eq!(reveals[0].1.value, 1000);
+    assert_eq!(reveals[1].1.script_pubkey, change(0).script_pubkey());
+    assert_eq!(reveals[1].1.value, 2000);
+  }
+
+  #[test]
+  fn json_manifest() {
+    let reveals = reveals(&format!(
+      r#"{{"inscriptions": [{{"text": "foo.btc", "destination": "{}", "metadata": {{"foo": 1}}}}]}}"#,
+      recipient(),
+    ))
+    .unwrap();
+
+    assert_eq!(reveals.len(), 1);
+    assert_eq!(reveals[0].1.value, 330);
+
+    assert_eq!(
+      reveals[0].0.metadata(),
+      Some(ciborium::Value::Map(vec![("foo".into(), 1.into())]))
+    );
+  }
+
+  #[test]
+  fn duplicate_names_are_rejected() {
+    assert_eq!(
+      reveals(&format!(
+        "inscriptions:
+- text: foo.btc
+  destination: {0}
+- text: bar.btc
+  destination: {0}
+- text: foo.btc
+  destination: {0}
+",
+        recipient(),
+      ))
+      .unwrap_err()
+      .to_string(),
+      "manifest inscriptions 0 and 2 both inscribe name foo.btc",
+    );
+  }
+
+  #[test]
+  fn destination_network_mismatch_is_rejected() {
+    assert_eq!(
+      reveals(&format!(
+        "inscriptions:
+- text: foo.btc
+  destination: {}
+",
+        address(),
+      ))
+      .unwrap_err()
+      .to_string(),
+      "manifest inscription 0 has destination for wrong network",
+    );
+  }
+
+  #[test]
+  fn items_need_file_or_text() {
+    assert_eq!(
+      reveals(&format!(
+        "inscriptions:
+- destination: {}
+",
+        recipient(),
+      ))
+      .unwrap_err()
+      .to_string(),
+      "manifest inscription 0 must have exactly one of `file` or `text`",
+    );
+  }
+
+  #[test]
+  fn postage_below_dust_is_rejected() {
+    assert_eq!(
+      reveals(&format!(
+        "inscriptions:
+- text: foo.btc
+  destination: {}
+  postage: 1
+",
+        recipient(),
+      ))
+      .unwrap_err()
+      .to_string(),
+      "manifest inscription 0 postage of 0.00000001 BTC is below dust limit of 0.00000294 BTC",
+    );
+  }
+
+  #[test]
+  fn empty_manifest_is_rejected() {
+    assert_eq!(
+      reveals("inscriptions: []").unwrap_err().to_string(),
+      "manifest contains no inscriptions",
+    );
+  }
+}
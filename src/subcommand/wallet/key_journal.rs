@@ -0,0 +1,261 @@
+use {
+  super::*,
+  argon2::Argon2,
+  bitcoin::{
+    hashes::{sha256, Hash},
+    key::{PrivateKey, TweakedKeyPair},
+  },
+  chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+  },
+};
+
+const PASSPHRASE_VAR: &str = "ORD_KEY_JOURNAL_PASSPHRASE";
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Kdf {
+  Argon2id,
+  Wallet,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Encrypted {
+  commit: Txid,
+  kdf: Kdf,
+  salt: String,
+  nonce: String,
+  ciphertext: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecoveryKey {
+  pub(crate) vout: u32,
+  pub(crate) key: String,
+}
+
+impl RecoveryKey {
+  pub(crate) fn new(vout: u32, key_pair: &TweakedKeyPair, network: Network) -> Self {
+    Self {
+      vout,
+      key: PrivateKey::new(key_pair.to_inner().secret_key(), network).to_wif(),
+    }
+  }
+}
+
+/// Recovery keys of the outputs of a commit transaction, encrypted with either
+/// a passphrase from `ORD_KEY_JOURNAL_PASSPHRASE` or a key derived from the
+/// wallet's active private descriptors.
+#[derive(Debug, PartialEq)]
+pub(crate) struct KeyJournal {
+  pub(crate) commit: Txid,
+  pub(crate) keys: Vec<RecoveryKey>,
+}
+
+impl KeyJournal {
+  fn path(options: &Options, commit: Txid) -> Result<PathBuf> {
+    Ok(
+      options
+        .data_dir()?
+        .join("keys")
+        .join(format!("{commit}.json")),
+    )
+  }
+
+  pub(crate) fn save(&self, options: &Options, client: &Client) -> Result {
+    let passphrase = env::var(PASSPHRASE_VAR).ok();
+
+    let kdf = if passphrase.is_some() {
+      Kdf::Argon2id
+    } else {
+      Kdf::Wallet
+    };
+
+    let mut salt = [0; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    let key = match passphrase {
+      Some(passphrase) => Self::passphrase_key(&passphrase, &salt)?,
+      None => Self::wallet_key(client, &salt)?,
+    };
+
+    let encrypted = self.encrypt(kdf, &key, salt)?;
+
+    let path = Self::path(options, self.commit)?;
+
+    fs::create_dir_all(path.parent().unwrap())?;
+
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&encrypted)?)?;
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+    }
+
+    fs::rename(&tmp, &path)?;
+
+    Ok(())
+  }
+
+  pub(crate) fn remove(options: &Options, commit: Txid) -> Result {
+    Ok(fs::remove_file(Self::path(options, commit)?)?)
+  }
+
+  pub(crate) fn load(options: &Options, client: &Client, commit: Txid) -> Result<Self> {
+    let path = Self::path(options, commit)?;
+
+    let json = fs::read_to_string(&path)
+      .with_context(|| format!("no recovery key journal for {commit} in {}", path.display()))?;
+
+    let encrypted = serde_json::from_str::<Encrypted>(&json)
+      .with_context(|| format!("failed to parse recovery key journal {}", path.display()))?;
+
+    let salt = hex::decode(&encrypted.salt)?;
+
+    let key = match encrypted.kdf {
+      Kdf::Argon2id => {
+        let passphrase = env::var(PASSPHRASE_VAR).with_context(|| {
+          format!("recovery key journal for {commit} is encrypted, set {PASSPHRASE_VAR}")
+        })?;
+        Self::passphrase_key(&passphrase, &salt)?
+      }
+      Kdf::Wallet => Self::wallet_key(client, &salt)?,
+    };
+
+    Self::decrypt(&encrypted, &key)
+  }
+
+  fn passphrase_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0; 32];
+    Argon2::default()
+      .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+      .map_err(|err| anyhow!("failed to derive key journal key: {err}"))?;
+    Ok(key)
+  }
+
+  fn wallet_key(client: &Client, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut descriptors = client
+      .list_descriptors(Some(true))
+      .context(
+        "failed to list private descriptors, unlock the wallet or set ORD_KEY_JOURNAL_PASSPHRASE",
+      )?
+      .descriptors
+      .into_iter()
+      .filter(|descriptor| descriptor.active)
+      .map(|descriptor| descriptor.desc)
+      .collect::<Vec<String>>();
+
+    if descriptors.is_empty() {
+      bail!("wallet has no active descriptors to derive key journal key from");
+    }
+
+    descriptors.sort();
+
+    let mut preimage = salt.to_vec();
+    for descriptor in descriptors {
+      preimage.extend_from_slice(descriptor.as_bytes());
+      preimage.push(b'\n');
+    }
+
+    Ok(sha256::Hash::hash(&preimage).to_byte_array())
+  }
+
+  fn encrypt(&self, kdf: Kdf, key: &[u8; 32], salt: [u8; 16]) -> Result<Encrypted> {
+    let mut nonce = [0; 24];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let plaintext = serde_json::to_vec(&self.keys)?;
+
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+      .encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+      .map_err(|_| anyhow!("failed to encrypt recovery key journal"))?;
+
+    Ok(Encrypted {
+      commit: self.commit,
+      kdf,
+      salt: hex::encode(salt),
+      nonce: hex::encode(nonce),
+      ciphertext: hex::encode(ciphertext),
+    })
+  }
+
+  fn decrypt(encrypted: &Encrypted, key: &[u8; 32]) -> Result<Self> {
+    let nonce = hex::decode(&encrypted.nonce)?;
+
+    if nonce.len() != 24 {
+      bail!(
+        "recovery key journal nonce has invalid length {}",
+        nonce.len()
+      );
+    }
+
+    let plaintext = XChaCha20Poly1305::new(key.into())
+      .decrypt(
+        XNonce::from_slice(&nonce),
+        hex::decode(&encrypted.ciphertext)?.as_slice(),
+      )
+      .map_err(|_| {
+        anyhow!(
+          "failed to decrypt recovery key journal for {}, wrong passphrase or wallet",
+          encrypted.commit
+        )
+      })?;
+
+    Ok(Self {
+      commit: encrypted.commit,
+      keys: serde_json::from_slice(&plaintext)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn journal() -> KeyJournal {
+    KeyJournal {
+      commit: txid(1),
+      keys: vec![RecoveryKey {
+        vout: 0,
+        key: "cVt4o7BGAig1UXywgGSmARhxMdzP5qvQsxKkSsc1XEkw3tDTQFpy".into(),
+      }],
+    }
+  }
+
+  #[test]
+  fn round_trip() {
+    let key = KeyJournal::passphrase_key("foo", &[0; 16]).unwrap();
+    let encrypted = journal().encrypt(Kdf::Argon2id, &key, [0; 16]).unwrap();
+    assert!(!encrypted
+      .ciphertext
+      .contains(&hex::encode(&journal().keys[0].key)));
+    assert_eq!(KeyJournal::decrypt(&encrypted, &key).unwrap(), journal());
+  }
+
+  #[test]
+  fn wrong_passphrase() {
+    let encrypted = journal()
+      .encrypt(
+        Kdf::Argon2id,
+        &KeyJournal::passphrase_key("foo", &[0; 16]).unwrap(),
+        [0; 16],
+      )
+      .unwrap();
+
+    assert_eq!(
+      KeyJournal::decrypt(
+        &encrypted,
+        &KeyJournal::passphrase_key("bar", &[0; 16]).unwrap()
+      )
+      .unwrap_err()
+      .to_string(),
+      format!(
+        "failed to decrypt recovery key journal for {}, wrong passphrase or wallet",
+        txid(1)
+      )
+    );
+  }
+}
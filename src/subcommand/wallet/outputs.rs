@@ -1,24 +1,42 @@
 use {super::*, crate::wallet::Wallet};
 
+#[derive(Debug, Parser)]
+pub(crate) struct Outputs {
+  #[clap(
+    long,
+    help = "List all unspent outputs, including those ignored by the wallet, with the reason they are ignored."
+  )]
+  all: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Output {
   pub output: OutPoint,
   pub amount: u64,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub excluded: Option<String>,
 }
 
-pub(crate) fn run(options: Options) -> Result {
-  let index = Index::open(&options)?;
-  index.update()?;
+impl Outputs {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
 
-  let mut outputs = Vec::new();
-  for (output, amount) in index.get_unspent_outputs(Wallet::load(&options)?)? {
-    outputs.push(Output {
-      output,
-      amount: amount.to_sat(),
-    });
-  }
+    let mut outputs = Vec::new();
+    for (output, (amount, excluded)) in
+      index.get_unspent_outputs_with_exclusions(Wallet::load(&options)?, false)?
+    {
+      if excluded.is_none() || self.all {
+        outputs.push(Output {
+          output,
+          amount: amount.to_sat(),
+          excluded,
+        });
+      }
+    }
 
-  print_json(outputs)?;
+    print_json(outputs)?;
 
-  Ok(())
+    Ok(())
+  }
 }
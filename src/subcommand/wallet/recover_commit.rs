@@ -0,0 +1,220 @@
+use {
+  super::*,
+  bitcoin::{
+    key::{KeyPair, PrivateKey, TweakedPublicKey},
+    locktime::absolute::LockTime,
+    secp256k1::{constants::SCHNORR_SIGNATURE_SIZE, Message},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    Witness,
+  },
+  key_journal::KeyJournal,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct RecoverCommit {
+  #[clap(help = "Sweep unspent outputs of commit transaction <COMMIT> back to the wallet.")]
+  commit: Txid,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+  pub recovered: Vec<OutPoint>,
+  pub value: u64,
+}
+
+impl RecoverCommit {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let journal = KeyJournal::load(&options, &client, self.commit)?;
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut inputs = Vec::new();
+
+    for recovery_key in &journal.keys {
+      let Some(output) = client.get_tx_out(&self.commit, recovery_key.vout, Some(true))? else {
+        continue;
+      };
+
+      let outpoint = OutPoint {
+        txid: self.commit,
+        vout: recovery_key.vout,
+      };
+
+      let key_pair =
+        KeyPair::from_secret_key(&secp256k1, &PrivateKey::from_wif(&recovery_key.key)?.inner);
+
+      let script_pubkey = output.script_pub_key.script()?;
+
+      if script_pubkey
+        != ScriptBuf::new_v1_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(
+          key_pair.x_only_public_key().0,
+        ))
+      {
+        bail!("recovery key for {outpoint} does not match its script pubkey");
+      }
+
+      inputs.push((
+        outpoint,
+        TxOut {
+          value: output.value.to_sat(),
+          script_pubkey,
+        },
+        key_pair,
+      ));
+    }
+
+    if inputs.is_empty() {
+      bail!("commit {} has no unspent outputs to recover", self.commit);
+    }
+
+    let recovered = inputs.iter().map(|(outpoint, ..)| *outpoint).collect();
+
+    let tx = Self::sweep(
+      &inputs,
+      &get_change_address(&client, &options)?,
+      self.fee_rate,
+    )?;
+
+    let transaction = client
+      .send_raw_transaction(&tx)
+      .context("Failed to send recovery transaction")?;
+
+    print_json(Output {
+      transaction,
+      recovered,
+      value: tx.output[0].value,
+    })
+  }
+
+  fn sweep(
+    inputs: &[(OutPoint, TxOut, KeyPair)],
+    destination: &Address,
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    let total = inputs
+      .iter()
+      .map(|(_, output, _)| output.value)
+      .sum::<u64>();
+
+    let mut tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: inputs
+        .iter()
+        .map(|(outpoint, ..)| TxIn {
+          previous_output: *outpoint,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]),
+        })
+        .collect(),
+      output: vec![TxOut {
+        value: total,
+        script_pubkey: destination.script_pubkey(),
+      }],
+    };
+
+    let fee = fee_rate.fee(tx.vsize()).to_sat();
+
+    tx.output[0].value = total
+      .checked_sub(fee)
+      .filter(|value| *value >= tx.output[0].script_pubkey.dust_value().to_sat())
+      .ok_or_else(|| {
+        anyhow!("commit outputs worth {total} sats cannot pay recovery fee of {fee} sats")
+      })?;
+
+    let prevouts = inputs
+      .iter()
+      .map(|(_, output, _)| output.clone())
+      .collect::<Vec<TxOut>>();
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut sighash_cache = SighashCache::new(&mut tx);
+
+    for (i, (.., key_pair)) in inputs.iter().enumerate() {
+      let sighash = sighash_cache
+        .taproot_key_spend_signature_hash(i, &Prevouts::All(&prevouts), TapSighashType::Default)
+        .expect("signature hash should compute");
+
+      let signature = secp256k1.sign_schnorr(
+        &Message::from_slice(sighash.as_ref()).expect("should be cryptographically secure hash"),
+        key_pair,
+      );
+
+      *sighash_cache.witness_mut(i).unwrap() = Witness::from_slice(&[signature.as_ref()]);
+    }
+
+    Ok(tx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::secp256k1::schnorr::Signature};
+
+  #[test]
+  fn sweep_is_signed_with_recovery_keys() {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
+
+    let output = TxOut {
+      value: 10_000,
+      script_pubkey: ScriptBuf::new_v1_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(
+        key_pair.x_only_public_key().0,
+      )),
+    };
+
+    let tx = RecoverCommit::sweep(
+      &[(outpoint(1), output.clone(), key_pair)],
+      &recipient(),
+      FeeRate::try_from(1.0).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(tx.output[0].value, 10_000 - tx.vsize() as u64);
+    assert_eq!(tx.output[0].script_pubkey, recipient().script_pubkey());
+
+    let sighash = SighashCache::new(&tx)
+      .taproot_key_spend_signature_hash(0, &Prevouts::All(&[output]), TapSighashType::Default)
+      .unwrap();
+
+    secp256k1
+      .verify_schnorr(
+        &Signature::from_slice(&tx.input[0].witness[0]).unwrap(),
+        &Message::from_slice(sighash.as_ref()).unwrap(),
+        &key_pair.x_only_public_key().0,
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn sweep_fails_if_fee_exceeds_value() {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
+
+    assert_regex_match!(
+      RecoverCommit::sweep(
+        &[(
+          outpoint(1),
+          TxOut {
+            value: 200,
+            script_pubkey: ScriptBuf::new_v1_p2tr_tweaked(
+              TweakedPublicKey::dangerous_assume_tweaked(key_pair.x_only_public_key().0),
+            ),
+          },
+          key_pair
+        )],
+        &recipient(),
+        FeeRate::try_from(1.0).unwrap(),
+      )
+      .unwrap_err(),
+      "commit outputs worth 200 sats cannot pay recovery fee of .* sats"
+    );
+  }
+}
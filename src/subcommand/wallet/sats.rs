@@ -67,9 +67,12 @@ fn rare_sats(utxos: Vec<(OutPoint, Vec<(u64, u64)>)>) -> Vec<(OutPoint, Sat, u64
       let mut offset = 0;
       sat_ranges.into_iter().filter_map(move |(start, end)| {
         let sat = Sat(start);
-        let rarity = sat.rarity();
         let start_offset = offset;
         offset += end - start;
+        if !is_tracked_sat_range(&(start, end)) {
+          return None;
+        }
+        let rarity = sat.rarity();
         if rarity > Rarity::Common {
           Some((outpoint, sat, start_offset, rarity))
         } else {
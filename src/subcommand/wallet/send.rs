@@ -1,4 +1,7 @@
-use {super::*, crate::wallet::Wallet};
+use {
+  super::*, crate::wallet::Wallet, base64::Engine, bitcoin::psbt::PartiallySignedTransaction,
+  std::collections::HashMap,
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Send {
@@ -6,6 +9,13 @@ pub(crate) struct Send {
   outgoing: Outgoing,
   #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
   fee_rate: FeeRate,
+  #[clap(
+    long,
+    help = "Write unsigned transaction to <PSBT_OUT> for an external signer instead of signing and broadcasting it."
+  )]
+  psbt_out: Option<PathBuf>,
+  #[clap(flatten)]
+  coin_control: CoinControl,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -54,14 +64,41 @@ impl Send {
           bail!("failed to lock ordinal UTXOs");
         }
 
-        let txid = client.send_to_address(&address, amount, None, None, None, None, None, None)?;
+        // bitcoind selects the inputs of amount sends, so outputs held back by
+        // coin control are locked for the duration of the send
+        let spendable = self
+          .coin_control
+          .filter(&client, unspent_outputs.clone(), &[])?;
 
-        print_json(Output { transaction: txid })?;
+        let held_back = unspent_outputs
+          .keys()
+          .filter(|utxo| !all_inscription_outputs.contains(utxo) && !spendable.contains_key(utxo))
+          .cloned()
+          .collect::<Vec<OutPoint>>();
+
+        if !held_back.is_empty() && !client.lock_unspent(&held_back)? {
+          bail!("failed to lock UTXOs held back by coin control");
+        }
+
+        let transaction = Self::send_amount(&client, self.psbt_out.as_deref(), &address, amount);
+
+        if !held_back.is_empty() {
+          client.unlock_unspent(&held_back)?;
+        }
+
+        print_json(Output {
+          transaction: transaction?,
+        })?;
 
         return Ok(());
       }
     };
 
+    let unspent_outputs =
+      self
+        .coin_control
+        .filter(&client, unspent_outputs, &[satpoint.outpoint])?;
+
     let change = [
       get_change_address(&client, &options)?,
       get_change_address(&client, &options)?,
@@ -74,8 +111,15 @@ impl Send {
       address,
       change,
       self.fee_rate,
+      self.coin_control.coin_selection,
     )?;
 
+    if let Some(psbt_out) = &self.psbt_out {
+      broadcast::write_psbt(&client, &unsigned_transaction, psbt_out)?;
+      println!("{}", unsigned_transaction.txid());
+      return Ok(());
+    }
+
     let signed_tx = client
       .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
       .hex;
@@ -86,4 +130,32 @@ impl Send {
 
     Ok(())
   }
+
+  fn send_amount(
+    client: &Client,
+    psbt_out: Option<&Path>,
+    address: &Address,
+    amount: Amount,
+  ) -> Result<Txid> {
+    if let Some(psbt_out) = psbt_out {
+      let psbt = client.wallet_create_funded_psbt(
+        &[],
+        &HashMap::from([(address.to_string(), amount)]),
+        None,
+        None,
+        Some(true),
+      )?;
+
+      fs::write(psbt_out, &psbt.psbt)
+        .with_context(|| format!("failed to write PSBT to {}", psbt_out.display()))?;
+
+      let psbt = PartiallySignedTransaction::deserialize(
+        &base64::engine::general_purpose::STANDARD.decode(psbt.psbt)?,
+      )?;
+
+      return Ok(psbt.unsigned_tx.txid());
+    }
+
+    Ok(client.send_to_address(address, amount, None, None, None, None, None, None)?)
+  }
 }
@@ -0,0 +1,190 @@
+use {super::*, crate::wallet::Wallet};
+
+#[derive(Debug, Parser)]
+pub(crate) struct SendMany {
+  #[clap(
+    help = "Send sats and inscriptions listed in <CSV>, one `<ADDRESS>,<INSCRIPTION_ID or SATPOINT>` pair per line."
+  )]
+  csv: PathBuf,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[clap(
+    long,
+    help = "Write unsigned transaction to <PSBT_OUT> for an external signer instead of signing and broadcasting it."
+  )]
+  psbt_out: Option<PathBuf>,
+  #[clap(flatten)]
+  coin_control: CoinControl,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+}
+
+impl SendMany {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let lines = sends_from_csv(
+      &fs::read_to_string(&self.csv)
+        .with_context(|| format!("I/O error reading `{}`", self.csv.display()))?,
+    )?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+
+    let inscriptions = index.get_inscriptions(None)?;
+
+    let mut sends = Vec::new();
+    for (address, outgoing) in lines {
+      let address = address.require_network(options.chain().network())?;
+
+      let satpoint = match outgoing {
+        Outgoing::SatPoint(satpoint) => {
+          if inscriptions.contains_key(&satpoint) {
+            bail!("inscriptions must be sent by inscription ID");
+          }
+          satpoint
+        }
+        Outgoing::InscriptionId(id) => index
+          .get_inscription_satpoint_by_id(id)?
+          .ok_or_else(|| anyhow!("Inscription {id} not found"))?,
+        Outgoing::Amount(_) => unreachable!("amounts are rejected when parsing"),
+      };
+
+      sends.push((satpoint, address));
+    }
+
+    let unspent_outputs = self.coin_control.filter(
+      &client,
+      unspent_outputs,
+      &sends
+        .iter()
+        .map(|(satpoint, _address)| satpoint.outpoint)
+        .collect::<Vec<OutPoint>>(),
+    )?;
+
+    let change = (0..=sends.len())
+      .map(|_| get_change_address(&client, &options))
+      .collect::<Result<Vec<Address>>>()?;
+
+    let unsigned_transaction = TransactionBuilder::build_transaction_with_postages(
+      sends,
+      inscriptions,
+      unspent_outputs,
+      change,
+      self.fee_rate,
+      self.coin_control.coin_selection,
+    )?;
+
+    let transaction = if let Some(psbt_out) = &self.psbt_out {
+      broadcast::write_psbt(&client, &unsigned_transaction, psbt_out)?;
+      unsigned_transaction.txid()
+    } else {
+      let signed_tx = client
+        .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+        .hex;
+
+      client.send_raw_transaction(&signed_tx)?
+    };
+
+    print_json(Output { transaction })?;
+
+    Ok(())
+  }
+}
+
+fn sends_from_csv(csv: &str) -> Result<Vec<(Address<NetworkUnchecked>, Outgoing)>> {
+  let mut sends = Vec::new();
+  for (i, line) in csv.lines().enumerate() {
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let (address, outgoing) = line
+      .split_once(',')
+      .ok_or_else(|| anyhow!("expected `<ADDRESS>,<OUTGOING>` on line {}", i + 1))?;
+
+    let (address, outgoing) = (address.trim(), outgoing.trim());
+
+    let address = address.parse().map_err(|err| {
+      anyhow!(
+        "failed to parse address from string \"{address}\" on line {}: {err}",
+        i + 1,
+      )
+    })?;
+
+    let outgoing = match outgoing.parse() {
+      Ok(Outgoing::Amount(_)) => bail!(
+        "cannot send amount \"{outgoing}\" on line {}: only sats and inscriptions may be sent",
+        i + 1,
+      ),
+      Ok(outgoing) => outgoing,
+      Err(err) => bail!(
+        "failed to parse outgoing from string \"{outgoing}\" on line {}: {err}",
+        i + 1,
+      ),
+    };
+
+    sends.push((address, outgoing));
+  }
+
+  Ok(sends)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sends_from_csv_skips_comments_and_empty_lines() {
+    let sends = sends_from_csv(&format!(
+      "# recipients\n\n{},{}\n{}, {}\n",
+      recipient(),
+      inscription_id(1),
+      address(),
+      satpoint(1, 0),
+    ))
+    .unwrap();
+
+    assert_eq!(sends.len(), 2);
+    assert_eq!(sends[0].0.clone().assume_checked(), recipient());
+    assert_eq!(sends[0].1, Outgoing::InscriptionId(inscription_id(1)));
+    assert_eq!(sends[1].0.clone().assume_checked(), address());
+    assert_eq!(sends[1].1, Outgoing::SatPoint(satpoint(1, 0)));
+  }
+
+  #[test]
+  fn sends_from_csv_reports_line_of_error() {
+    assert_eq!(
+      sends_from_csv(&format!(
+        "{},{}\n{}",
+        recipient(),
+        inscription_id(1),
+        address()
+      ))
+      .unwrap_err()
+      .to_string(),
+      "expected `<ADDRESS>,<OUTGOING>` on line 2",
+    );
+
+    assert_eq!(
+      sends_from_csv(&format!("# comment\nfoo,{}", inscription_id(1)))
+        .unwrap_err()
+        .to_string(),
+      "failed to parse address from string \"foo\" on line 2: base58 address encoding error",
+    );
+
+    assert_eq!(
+      sends_from_csv(&format!("{},1 btc", recipient()))
+        .unwrap_err()
+        .to_string(),
+      "cannot send amount \"1 btc\" on line 1: only sats and inscriptions may be sent",
+    );
+  }
+}
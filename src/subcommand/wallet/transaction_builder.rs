@@ -9,16 +9,22 @@
 //! conditions into account.
 //!
 //! The external interfaces are
-//! `TransactionBuilder::build_transaction_with_postage`, and
-//! `TransactionBuilder::build_transaction_with_value`. Both return a
-//! constructed transaction given the arguments, which include the outgoing sat
-//! to send, the wallets current UTXOs and their sat ranges, and the
-//! recipient's address.
+//! `TransactionBuilder::build_transaction_with_postage`,
+//! `TransactionBuilder::build_transaction_with_postages`, and
+//! `TransactionBuilder::build_transaction_with_value`. All return a
+//! constructed transaction given the arguments, which include the outgoing
+//! sats to send, the wallets current UTXOs and their sat ranges, and the
+//! recipients' addresses.
 //!
 //! `TransactionBuilder::build_transaction_with_postage` ensures that the
 //! outgoing value is at most 20,000 sats, reducing it to 10,000 sats if coin
 //! selection requires adding excess value.
 //!
+//! `TransactionBuilder::build_transaction_with_postages` sends multiple sats,
+//! each to its own recipient output holding at most 20,000 sats of postage.
+//! Sats between outgoing sats go to change outputs, and the fee is paid from
+//! cardinal inputs only.
+//!
 //! `TransactionBuilder::build_transaction_with_value` ensures that the
 //! outgoing value is exactly the requested amount,
 //!
@@ -44,11 +50,13 @@ use {
 #[derive(Debug, PartialEq)]
 pub enum Error {
   DuplicateAddress(Address),
+  DuplicateOutgoing(OutPoint),
   Dust {
     output_value: Amount,
     dust_value: Amount,
   },
   NotEnoughCardinalUtxos,
+  NoOutgoing,
   NotInWallet(SatPoint),
   OutOfRange(SatPoint, u64),
   UtxoContainsAdditionalInscription {
@@ -88,6 +96,8 @@ impl fmt::Display for Error {
       ),
       Error::ValueOverflow => write!(f, "arithmetic overflow calculating value"),
       Error::DuplicateAddress(address) => write!(f, "duplicate input address: {address}"),
+      Error::DuplicateOutgoing(outpoint) => write!(f, "cannot send more than one sat from outpoint {outpoint}"),
+      Error::NoOutgoing => write!(f, "no outgoing sats to send"),
     }
   }
 }
@@ -98,12 +108,14 @@ impl std::error::Error for Error {}
 pub struct TransactionBuilder {
   amounts: BTreeMap<OutPoint, Amount>,
   change_addresses: BTreeSet<Address>,
+  coin_selection: CoinSelection,
   fee_rate: FeeRate,
   inputs: Vec<OutPoint>,
   inscriptions: BTreeMap<SatPoint, InscriptionId>,
   outgoing: SatPoint,
   outputs: Vec<(Address, Amount)>,
   recipient: Address,
+  sends: Vec<(SatPoint, Address)>,
   unused_change_addresses: Vec<Address>,
   utxos: BTreeSet<OutPoint>,
   target: Target,
@@ -112,7 +124,7 @@ pub struct TransactionBuilder {
 type Result<T> = std::result::Result<T, Error>;
 
 impl TransactionBuilder {
-  const ADDITIONAL_INPUT_VBYTES: usize = 58;
+  pub(crate) const ADDITIONAL_INPUT_VBYTES: usize = 58;
   const ADDITIONAL_OUTPUT_VBYTES: usize = 43;
   const MAX_POSTAGE: Amount = Amount::from_sat(2 * 10_000);
   const SCHNORR_SIGNATURE_SIZE: usize = 64;
@@ -126,6 +138,7 @@ impl TransactionBuilder {
     recipient: Address,
     change: [Address; 2],
     fee_rate: FeeRate,
+    coin_selection: CoinSelection,
   ) -> Result<Transaction> {
     Self::new(
       outgoing,
@@ -135,10 +148,36 @@ impl TransactionBuilder {
       change,
       fee_rate,
       Target::Postage,
+      coin_selection,
     )?
     .build_transaction()
   }
 
+  /// Send each sat in `sends` to its recipient. Every outgoing sat needs its
+  /// own outpoint, and `change` must hold one more address than `sends`, since
+  /// the sats between outgoing sats go to change outputs.
+  pub fn build_transaction_with_postages(
+    sends: Vec<(SatPoint, Address)>,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    amounts: BTreeMap<OutPoint, Amount>,
+    change: Vec<Address>,
+    fee_rate: FeeRate,
+    coin_selection: CoinSelection,
+  ) -> Result<Transaction> {
+    Self::new_many(
+      sends,
+      inscriptions,
+      amounts,
+      change,
+      fee_rate,
+      coin_selection,
+    )?
+    .select_outgoings()?
+    .align_sends()?
+    .pay_fee()?
+    .build_many()
+  }
+
   pub fn build_transaction_with_value(
     outgoing: SatPoint,
     inscriptions: BTreeMap<SatPoint, InscriptionId>,
@@ -147,6 +186,7 @@ impl TransactionBuilder {
     change: [Address; 2],
     fee_rate: FeeRate,
     output_value: Amount,
+    coin_selection: CoinSelection,
   ) -> Result<Transaction> {
     let dust_value = recipient.script_pubkey().dust_value();
 
@@ -165,6 +205,7 @@ impl TransactionBuilder {
       change,
       fee_rate,
       Target::Value(output_value),
+      coin_selection,
     )?
     .build_transaction()
   }
@@ -188,6 +229,7 @@ impl TransactionBuilder {
     change: [Address; 2],
     fee_rate: FeeRate,
     target: Target,
+    coin_selection: CoinSelection,
   ) -> Result<Self> {
     if change.contains(&recipient) {
       return Err(Error::DuplicateAddress(recipient));
@@ -201,17 +243,58 @@ impl TransactionBuilder {
       utxos: amounts.keys().cloned().collect(),
       amounts,
       change_addresses: change.iter().cloned().collect(),
+      coin_selection,
       fee_rate,
       inputs: Vec::new(),
       inscriptions,
       outgoing,
       outputs: Vec::new(),
+      sends: vec![(outgoing, recipient.clone())],
       recipient,
       unused_change_addresses: change.to_vec(),
       target,
     })
   }
 
+  fn new_many(
+    sends: Vec<(SatPoint, Address)>,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    amounts: BTreeMap<OutPoint, Amount>,
+    change: Vec<Address>,
+    fee_rate: FeeRate,
+    coin_selection: CoinSelection,
+  ) -> Result<Self> {
+    let (outgoing, recipient) = sends.first().cloned().ok_or(Error::NoOutgoing)?;
+
+    for (_outgoing, recipient) in &sends {
+      if change.contains(recipient) {
+        return Err(Error::DuplicateAddress(recipient.clone()));
+      }
+    }
+
+    for (i, address) in change.iter().enumerate() {
+      if change[..i].contains(address) {
+        return Err(Error::DuplicateAddress(address.clone()));
+      }
+    }
+
+    Ok(Self {
+      utxos: amounts.keys().cloned().collect(),
+      amounts,
+      change_addresses: change.iter().cloned().collect(),
+      coin_selection,
+      fee_rate,
+      inputs: Vec::new(),
+      inscriptions,
+      outgoing,
+      outputs: Vec::new(),
+      recipient,
+      sends,
+      unused_change_addresses: change,
+      target: Target::Postage,
+    })
+  }
+
   fn select_outgoing(mut self) -> Result<Self> {
     for (inscribed_satpoint, inscription_id) in &self.inscriptions {
       if self.outgoing.outpoint == inscribed_satpoint.outpoint
@@ -247,6 +330,138 @@ impl TransactionBuilder {
     Ok(self)
   }
 
+  fn select_outgoings(mut self) -> Result<Self> {
+    for (outgoing, _recipient) in &self.sends {
+      for (inscribed_satpoint, inscription_id) in &self.inscriptions {
+        if outgoing.outpoint == inscribed_satpoint.outpoint
+          && outgoing.offset != inscribed_satpoint.offset
+        {
+          return Err(Error::UtxoContainsAdditionalInscription {
+            outgoing_satpoint: *outgoing,
+            inscribed_satpoint: *inscribed_satpoint,
+            inscription_id: *inscription_id,
+          });
+        }
+      }
+
+      let amount = *self
+        .amounts
+        .get(&outgoing.outpoint)
+        .ok_or(Error::NotInWallet(*outgoing))?;
+
+      if outgoing.offset >= amount.to_sat() {
+        return Err(Error::OutOfRange(*outgoing, amount.to_sat() - 1));
+      }
+
+      if !self.utxos.remove(&outgoing.outpoint) {
+        return Err(Error::DuplicateOutgoing(outgoing.outpoint));
+      }
+    }
+
+    tprintln!("selected {} outgoing outpoints", self.sends.len());
+
+    Ok(self)
+  }
+
+  /// Lay out inputs and outputs so that each outgoing sat is the first sat of
+  /// its recipient output. Sats preceding an outgoing sat, including postage
+  /// stripped from the previous recipient output, go to a change output,
+  /// which is padded with a cardinal input if it would be dust.
+  fn align_sends(mut self) -> Result<Self> {
+    let mut excess = Amount::ZERO;
+
+    for (outgoing, recipient) in self.sends.clone() {
+      let mut gap = excess
+        .checked_add(Amount::from_sat(outgoing.offset))
+        .ok_or(Error::ValueOverflow)?;
+
+      if gap > Amount::ZERO {
+        let change = self
+          .unused_change_addresses
+          .pop()
+          .expect("not enough change addresses");
+
+        let dust_limit = change.script_pubkey().dust_value();
+        if gap < dust_limit {
+          let (utxo, size) = self.select_cardinal_utxo(dust_limit - gap)?;
+          self.inputs.push(utxo);
+          gap = gap.checked_add(size).ok_or(Error::ValueOverflow)?;
+          tprintln!(
+            "padded alignment output to {} with additional {} sat input",
+            gap.to_sat(),
+            size.to_sat()
+          );
+        }
+
+        self.outputs.push((change, gap));
+      }
+
+      self.inputs.push(outgoing.outpoint);
+
+      let mut postage = self.amounts[&outgoing.outpoint] - Amount::from_sat(outgoing.offset);
+
+      let dust_limit = recipient.script_pubkey().dust_value();
+      if postage < dust_limit {
+        let (utxo, size) = self.select_cardinal_utxo(dust_limit - postage)?;
+        self.inputs.push(utxo);
+        postage = postage.checked_add(size).ok_or(Error::ValueOverflow)?;
+        tprintln!("added {} sat input to cover postage", size.to_sat());
+      }
+
+      excess = Amount::ZERO;
+      if postage > Self::MAX_POSTAGE {
+        let target = Self::TARGET_POSTAGE.max(dust_limit);
+        excess = postage - target;
+        postage = target;
+        tprintln!("stripped {} sats", excess.to_sat());
+      }
+
+      self.outputs.push((recipient, postage));
+    }
+
+    self.outputs.push((
+      self
+        .unused_change_addresses
+        .pop()
+        .expect("not enough change addresses"),
+      excess,
+    ));
+
+    Ok(self)
+  }
+
+  /// Pay the fee from the final change output, adding a cardinal input if it
+  /// cannot cover both the fee and its own dust limit. Recipient outputs never
+  /// pay fees, so postage is never spent on them.
+  fn pay_fee(mut self) -> Result<Self> {
+    loop {
+      let fee = self.estimate_fee();
+
+      let (change, value) = self.outputs.last_mut().expect("no change output");
+
+      let total = fee
+        .checked_add(change.script_pubkey().dust_value())
+        .ok_or(Error::ValueOverflow)?;
+
+      if *value >= total {
+        *value -= fee;
+        return Ok(self);
+      }
+
+      let needed = (total - *value)
+        .checked_add(self.fee_rate.fee(Self::ADDITIONAL_INPUT_VBYTES))
+        .ok_or(Error::ValueOverflow)?;
+
+      let (utxo, size) = self.select_cardinal_utxo(needed)?;
+      self.inputs.push(utxo);
+
+      let (_change, value) = self.outputs.last_mut().expect("no change output");
+      *value = value.checked_add(size).ok_or(Error::ValueOverflow)?;
+
+      tprintln!("added {} sat input to pay fee", size.to_sat());
+    }
+  }
+
   fn align_outgoing(mut self) -> Self {
     assert_eq!(self.outputs.len(), 1, "invariant: only one output");
 
@@ -450,9 +665,8 @@ impl TransactionBuilder {
     self.fee_rate.fee(self.estimate_vbytes())
   }
 
-  fn build(self) -> Result<Transaction> {
-    let recipient = self.recipient.script_pubkey();
-    let transaction = Transaction {
+  fn unsigned_transaction(&self) -> Transaction {
+    Transaction {
       version: 1,
       lock_time: LockTime::ZERO,
       input: self
@@ -473,7 +687,12 @@ impl TransactionBuilder {
           script_pubkey: address.script_pubkey(),
         })
         .collect(),
-    };
+    }
+  }
+
+  fn build(self) -> Result<Transaction> {
+    let recipient = self.recipient.script_pubkey();
+    let transaction = self.unsigned_transaction();
 
     assert_eq!(
       self
@@ -590,6 +809,116 @@ impl TransactionBuilder {
       offset += output.value;
     }
 
+    self.assert_fee_and_dust(&transaction);
+
+    Ok(transaction)
+  }
+
+  fn build_many(self) -> Result<Transaction> {
+    let transaction = self.unsigned_transaction();
+
+    let mut input_offsets = BTreeMap::new();
+    let mut offset = 0;
+    for outpoint in &self.inputs {
+      assert!(
+        input_offsets.insert(*outpoint, offset).is_none(),
+        "invariant: inputs are spent at most once"
+      );
+      offset += self.amounts[outpoint].to_sat();
+    }
+
+    let mut recipient_outputs = BTreeSet::new();
+    for (outgoing, recipient) in &self.sends {
+      assert!(
+        self
+          .amounts
+          .get(&outgoing.outpoint)
+          .map(|amount| outgoing.offset < amount.to_sat())
+          .unwrap_or_default(),
+        "invariant: outgoing sat is contained in utxos"
+      );
+
+      let sat_offset = input_offsets
+        .get(&outgoing.outpoint)
+        .expect("invariant: inputs spend outgoing sat")
+        + outgoing.offset;
+
+      let mut output_end = 0;
+      let (vout, output) = transaction
+        .output
+        .iter()
+        .enumerate()
+        .find(|(_vout, output)| {
+          output_end += output.value;
+          output_end > sat_offset
+        })
+        .expect("invariant: outgoing sat is found in outputs");
+
+      assert_eq!(
+        output.script_pubkey,
+        recipient.script_pubkey(),
+        "invariant: outgoing sat is sent to recipient"
+      );
+
+      assert_eq!(
+        output_end - output.value,
+        sat_offset,
+        "invariant: sat is at first position in recipient output"
+      );
+
+      assert!(
+        Amount::from_sat(output.value) <= Self::MAX_POSTAGE,
+        "invariant: excess postage is stripped"
+      );
+
+      assert!(
+        recipient_outputs.insert(vout),
+        "invariant: recipient outputs hold one outgoing sat each"
+      );
+    }
+
+    for (vout, output) in transaction.output.iter().enumerate() {
+      assert!(
+        recipient_outputs.contains(&vout)
+          || self
+            .change_addresses
+            .iter()
+            .any(|change_address| change_address.script_pubkey() == output.script_pubkey),
+        "invariant: all outputs are either change or recipient: unrecognized output {}",
+        output.script_pubkey
+      );
+    }
+
+    assert!(
+      self
+        .change_addresses
+        .iter()
+        .map(|change_address| transaction
+          .output
+          .iter()
+          .filter(|tx_out| tx_out.script_pubkey == change_address.script_pubkey())
+          .count())
+        .all(|count| count <= 1),
+      "invariant: change addresses appear at most once in outputs",
+    );
+
+    for inscribed_satpoint in self.inscriptions.keys() {
+      assert!(
+        !input_offsets.contains_key(&inscribed_satpoint.outpoint)
+          || self
+            .sends
+            .iter()
+            .any(|(outgoing, _recipient)| outgoing == inscribed_satpoint),
+        "invariant: inscriptions are only spent as outgoing sats"
+      );
+    }
+
+    self.assert_fee_and_dust(&transaction);
+
+    Ok(transaction)
+  }
+
+  fn assert_fee_and_dust(&self, transaction: &Transaction) {
     let mut actual_fee = Amount::ZERO;
     for input in &transaction.input {
       actual_fee += self.amounts[&input.previous_output];
@@ -615,8 +944,6 @@ impl TransactionBuilder {
         "invariant: all outputs are above dust limit",
       );
     }
-
-    Ok(transaction)
   }
 
   fn calculate_sat_offset(&self) -> u64 {
@@ -633,28 +960,23 @@ impl TransactionBuilder {
   }
 
   fn select_cardinal_utxo(&mut self, minimum_value: Amount) -> Result<(OutPoint, Amount)> {
-    let mut found = None;
-
     let inscribed_utxos = self
       .inscriptions
       .keys()
       .map(|satpoint| satpoint.outpoint)
       .collect::<BTreeSet<OutPoint>>();
 
-    for utxo in &self.utxos {
-      if inscribed_utxos.contains(utxo) {
-        continue;
-      }
-
-      let value = self.amounts[utxo];
-
-      if value >= minimum_value {
-        found = Some((*utxo, value));
-        break;
-      }
-    }
-
-    let (utxo, value) = found.ok_or(Error::NotEnoughCardinalUtxos)?;
+    let (utxo, value) = self
+      .coin_selection
+      .select_one(
+        self
+          .utxos
+          .iter()
+          .filter(|utxo| !inscribed_utxos.contains(utxo))
+          .map(|utxo| (*utxo, self.amounts[utxo]))
+          .filter(|(_, value)| *value >= minimum_value),
+      )
+      .ok_or(Error::NotEnoughCardinalUtxos)?;
 
     self.utxos.remove(&utxo);
 
@@ -682,6 +1004,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -716,8 +1039,10 @@ mod tests {
       outgoing: satpoint(1, 0),
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
+      sends: vec![(satpoint(1, 0), recipient())],
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      coin_selection: CoinSelection::default(),
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -753,6 +1078,7 @@ mod tests {
       recipient(),
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
+      CoinSelection::default(),
     )
     .unwrap()
     .is_explicitly_rbf())
@@ -770,6 +1096,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -793,6 +1120,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -817,6 +1145,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -827,6 +1156,43 @@ mod tests {
     )
   }
 
+  #[test]
+  fn cardinal_utxo_is_selected_according_to_coin_selection() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(20_000)),
+      (outpoint(3), Amount::from_sat(10_000)),
+    ];
+
+    for (coin_selection, selected) in [
+      (
+        CoinSelection::BranchAndBound,
+        (outpoint(3), Amount::from_sat(10_000)),
+      ),
+      (
+        CoinSelection::LargestFirst,
+        (outpoint(2), Amount::from_sat(20_000)),
+      ),
+    ] {
+      let mut tx_builder = TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.clone().into_iter().collect(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Postage,
+        coin_selection,
+      )
+      .unwrap();
+
+      pretty_assert_eq!(
+        tx_builder.select_cardinal_utxo(Amount::from_sat(8_000)),
+        Ok(selected)
+      );
+    }
+  }
+
   #[test]
   fn insufficient_padding_to_add_postage_no_utxos() {
     let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
@@ -839,6 +1205,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Err(Error::NotEnoughCardinalUtxos),
     )
@@ -859,6 +1226,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Err(Error::NotEnoughCardinalUtxos),
     )
@@ -879,6 +1247,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -906,6 +1275,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .build()
@@ -925,6 +1295,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .build()
@@ -944,6 +1315,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .build()
@@ -963,6 +1335,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -989,6 +1362,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -1011,6 +1385,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1037,6 +1412,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -1057,6 +1433,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1082,6 +1459,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1105,6 +1483,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -1133,6 +1512,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -1159,6 +1539,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -1182,6 +1563,7 @@ mod tests {
       [change(0), change(1)],
       FeeRate::try_from(1.0).unwrap(),
       Target::Postage,
+      CoinSelection::default(),
     )
     .unwrap()
     .select_outgoing()
@@ -1206,8 +1588,10 @@ mod tests {
       outgoing: satpoint(1, 0),
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
+      sends: vec![(satpoint(1, 0), recipient())],
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      coin_selection: CoinSelection::default(),
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1235,8 +1619,10 @@ mod tests {
       outgoing: satpoint(1, 0),
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
+      sends: vec![(satpoint(1, 0), recipient())],
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      coin_selection: CoinSelection::default(),
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1264,6 +1650,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Err(Error::NotEnoughCardinalUtxos)
     )
@@ -1281,6 +1668,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Err(Error::UtxoContainsAdditionalInscription {
         outgoing_satpoint: satpoint(1, 0),
@@ -1303,6 +1691,7 @@ mod tests {
       recipient(),
       [change(0), change(1)],
       fee_rate,
+      CoinSelection::default(),
     )
     .unwrap();
 
@@ -1332,7 +1721,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1358,7 +1748,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
-        Amount::from_sat(1500)
+        Amount::from_sat(1500),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1381,7 +1772,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
-        Amount::from_sat(1)
+        Amount::from_sat(1),
+        CoinSelection::default(),
       ),
       Err(Error::Dust {
         output_value: Amount::from_sat(1),
@@ -1405,7 +1797,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Err(Error::NotEnoughCardinalUtxos),
     )
@@ -1426,7 +1819,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(4.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Err(Error::NotEnoughCardinalUtxos),
     )
@@ -1466,7 +1860,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
-        Amount::from_sat(707)
+        Amount::from_sat(707),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1489,6 +1884,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1511,7 +1907,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(5.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1534,7 +1931,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(6.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Err(Error::NotEnoughCardinalUtxos)
     );
@@ -1552,7 +1950,8 @@ mod tests {
         recipient(),
         [recipient(), change(1)],
         FeeRate::try_from(0.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Err(Error::DuplicateAddress(recipient()))
     );
@@ -1570,7 +1969,8 @@ mod tests {
         recipient(),
         [change(0), change(0)],
         FeeRate::try_from(0.0).unwrap(),
-        Amount::from_sat(1000)
+        Amount::from_sat(1000),
+        CoinSelection::default(),
       ),
       Err(Error::DuplicateAddress(change(0)))
     );
@@ -1588,7 +1988,8 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(2.0).unwrap(),
-        Amount::from_sat(1500)
+        Amount::from_sat(1500),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1611,6 +2012,7 @@ mod tests {
         recipient(),
         [change(0), change(1)],
         FeeRate::try_from(250.0).unwrap(),
+        CoinSelection::default(),
       ),
       Ok(Transaction {
         version: 1,
@@ -1620,4 +2022,191 @@ mod tests {
       }),
     );
   }
+
+  #[test]
+  fn send_many_sends_each_sat_to_its_recipient() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(10_000)),
+      (outpoint(2), Amount::from_sat(10_000)),
+      (outpoint(3), Amount::from_sat(20_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        vec![(satpoint(1, 0), recipient()), (satpoint(2, 0), address())],
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        vec![change(0), change(1), change(2)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2)), tx_in(outpoint(3))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(10_000, address()),
+          tx_out(19_724, change(2)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn send_many_aligns_and_strips_each_sat() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(10_000)),
+      (outpoint(2), Amount::from_sat(100_000)),
+      (outpoint(3), Amount::from_sat(20_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        vec![
+          (satpoint(1, 3_333), recipient()),
+          (satpoint(2, 0), address())
+        ],
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        vec![change(0), change(1), change(2)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(3_333, change(2)),
+          tx_out(6_667, recipient()),
+          tx_out(546, address()),
+          tx_out(99_204, change(1)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn send_many_pays_fee_from_cardinal_inputs() {
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        vec![(satpoint(1, 0), recipient()), (satpoint(2, 0), address())],
+        BTreeMap::new(),
+        vec![
+          (outpoint(1), Amount::from_sat(10_000)),
+          (outpoint(2), Amount::from_sat(10_000)),
+        ]
+        .into_iter()
+        .collect(),
+        vec![change(0), change(1), change(2)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
+      ),
+      Err(Error::NotEnoughCardinalUtxos),
+    );
+  }
+
+  #[test]
+  fn send_many_does_not_spend_other_inscriptions() {
+    let mut inscriptions = BTreeMap::new();
+    inscriptions.insert(satpoint(1, 0), inscription_id(1));
+    inscriptions.insert(satpoint(3, 0), inscription_id(3));
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        vec![(satpoint(1, 0), recipient())],
+        inscriptions,
+        vec![
+          (outpoint(1), Amount::from_sat(10_000)),
+          (outpoint(2), Amount::from_sat(1_000)),
+          (outpoint(3), Amount::from_sat(100_000)),
+        ]
+        .into_iter()
+        .collect(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::LargestFirst,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![tx_out(10_000, recipient()), tx_out(812, change(1))],
+      }),
+    );
+  }
+
+  #[test]
+  fn send_many_rejects_invalid_sends() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(10_000))]
+      .into_iter()
+      .collect::<BTreeMap<OutPoint, Amount>>();
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        Vec::new(),
+        BTreeMap::new(),
+        utxos.clone(),
+        vec![change(0)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
+      ),
+      Err(Error::NoOutgoing),
+    );
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        vec![(satpoint(1, 0), recipient()), (satpoint(1, 1), address())],
+        BTreeMap::new(),
+        utxos.clone(),
+        vec![change(0), change(1), change(2)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
+      ),
+      Err(Error::DuplicateOutgoing(outpoint(1))),
+    );
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_postages(
+        vec![(satpoint(1, 0), change(1))],
+        BTreeMap::new(),
+        utxos,
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        CoinSelection::default(),
+      ),
+      Err(Error::DuplicateAddress(change(1))),
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: outgoing sat is sent to recipient")]
+  fn invariant_send_many_sats_are_sent_to_their_recipients() {
+    let mut builder = TransactionBuilder::new_many(
+      vec![(satpoint(1, 0), recipient()), (satpoint(2, 0), address())],
+      BTreeMap::new(),
+      vec![
+        (outpoint(1), Amount::from_sat(10_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+        (outpoint(3), Amount::from_sat(20_000)),
+      ]
+      .into_iter()
+      .collect(),
+      vec![change(0), change(1), change(2)],
+      FeeRate::try_from(1.0).unwrap(),
+      CoinSelection::default(),
+    )
+    .unwrap()
+    .select_outgoings()
+    .unwrap()
+    .align_sends()
+    .unwrap()
+    .pay_fee()
+    .unwrap();
+
+    builder.outputs.swap(0, 1);
+
+    builder.build_many().unwrap();
+  }
 }
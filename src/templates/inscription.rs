@@ -3,6 +3,7 @@ use super::*;
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionHtml {
   pub(crate) chain: Chain,
+  pub(crate) children: Vec<InscriptionId>,
   pub(crate) genesis_fee: u64,
   pub(crate) genesis_height: u64,
   pub(crate) inscription: Inscription,
@@ -10,6 +11,7 @@ pub(crate) struct InscriptionHtml {
   pub(crate) next: Option<InscriptionId>,
   pub(crate) number: i64,
   pub(crate) output: Option<TxOut>,
+  pub(crate) parent: Option<InscriptionId>,
   pub(crate) previous: Option<InscriptionId>,
   pub(crate) sat: Option<Sat>,
   pub(crate) satpoint: SatPoint,
@@ -35,6 +37,7 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        children: Vec::new(),
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -42,6 +45,7 @@ mod tests {
         next: None,
         number: 1,
         output: None,
+        parent: None,
         previous: None,
         sat: None,
         satpoint: satpoint(1, 0),
@@ -90,6 +94,7 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        children: Vec::new(),
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -97,6 +102,7 @@ mod tests {
         next: None,
         number: 1,
         output: Some(tx_out(1, address())),
+        parent: None,
         previous: None,
         sat: None,
         satpoint: satpoint(1, 0),
@@ -127,6 +133,7 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        children: Vec::new(),
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -134,6 +141,7 @@ mod tests {
         next: None,
         number: 1,
         output: Some(tx_out(1, address())),
+        parent: None,
         previous: None,
         sat: Some(Sat(1)),
         satpoint: satpoint(1, 0),
@@ -159,6 +167,7 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        children: Vec::new(),
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -166,6 +175,7 @@ mod tests {
         next: Some(inscription_id(3)),
         number: 1,
         output: Some(tx_out(1, address())),
+        parent: None,
         previous: Some(inscription_id(1)),
         sat: None,
         satpoint: satpoint(1, 0),
@@ -189,6 +199,7 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        children: Vec::new(),
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -196,6 +207,7 @@ mod tests {
         next: None,
         number: -1,
         output: Some(tx_out(1, address())),
+        parent: None,
         previous: None,
         sat: None,
         satpoint: SatPoint {
@@ -219,4 +231,42 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn with_parent_and_children() {
+    assert_regex_match!(
+      InscriptionHtml {
+        chain: Chain::Mainnet,
+        children: vec![inscription_id(2), inscription_id(3)],
+        genesis_fee: 1,
+        genesis_height: 0,
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        inscription_id: inscription_id(1),
+        next: None,
+        number: 1,
+        output: None,
+        parent: Some(inscription_id(4)),
+        previous: None,
+        sat: None,
+        satpoint: satpoint(1, 0),
+        timestamp: timestamp(0),
+      },
+      "
+        .*
+        <dl>
+          <dt>id</dt>
+          <dd class=monospace>1{64}i1</dd>
+          <dt>parent</dt>
+          <dd><a class=monospace href=/inscription/4{64}i4>4{64}i4</a></dd>
+          <dt>children</dt>
+          <dd class=thumbnails>
+            <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+            <a href=/inscription/3{64}i3><iframe .* src=/preview/3{64}i3></iframe></a>
+          </dd>
+          .*
+        </dl>
+      "
+      .unindent()
+    );
+  }
 }
@@ -1,13 +1,68 @@
-use super::*;
+use {
+  super::*,
+  miniscript::descriptor::{Descriptor, DescriptorPublicKey},
+  std::collections::BTreeSet,
+};
+
+/// Addresses derived from each ranged watch descriptor, matching the default
+/// range Bitcoin Core uses for imported descriptors.
+const WATCH_RANGE: u32 = 1000;
 
 pub(crate) struct Wallet {
-  _private: (),
+  min_cardinal_value: u64,
+  watch_only: Option<BTreeSet<ScriptBuf>>,
 }
 
 impl Wallet {
   pub(crate) fn load(options: &Options) -> Result<Self> {
-    options.bitcoin_rpc_client_for_wallet_command(false)?;
+    if options.watch_address.is_empty() && options.watch_descriptor.is_empty() {
+      options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+      return Ok(Self {
+        min_cardinal_value: options.min_cardinal_value,
+        watch_only: None,
+      });
+    }
+
+    let mut script_pubkeys = BTreeSet::new();
+
+    for address in &options.watch_address {
+      script_pubkeys.insert(
+        address
+          .clone()
+          .require_network(options.chain().network())?
+          .script_pubkey(),
+      );
+    }
+
+    for descriptor in &options.watch_descriptor {
+      let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor)
+        .with_context(|| format!("invalid watch descriptor `{descriptor}`"))?;
+
+      for descriptor in descriptor.into_single_descriptors()? {
+        let range = if descriptor.has_wildcard() {
+          0..WATCH_RANGE
+        } else {
+          0..1
+        };
+
+        for i in range {
+          script_pubkeys.insert(descriptor.at_derivation_index(i)?.script_pubkey());
+        }
+      }
+    }
+
+    Ok(Self {
+      min_cardinal_value: options.min_cardinal_value,
+      watch_only: Some(script_pubkeys),
+    })
+  }
+
+  pub(crate) fn min_cardinal_value(&self) -> u64 {
+    self.min_cardinal_value
+  }
 
-    Ok(Self { _private: () })
+  pub(crate) fn watch_only(&self) -> Option<&BTreeSet<ScriptBuf>> {
+    self.watch_only.as_ref()
   }
 }
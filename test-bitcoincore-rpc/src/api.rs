@@ -58,6 +58,22 @@ pub trait Api {
     sighash_type: Option<()>,
   ) -> Result<Value, jsonrpc_core::Error>;
 
+  #[rpc(name = "walletprocesspsbt")]
+  fn wallet_process_psbt(
+    &self,
+    psbt: String,
+    sign: Option<bool>,
+    sighash_type: Option<String>,
+    bip32derivs: Option<bool>,
+  ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error>;
+
+  #[rpc(name = "finalizepsbt")]
+  fn finalize_psbt(
+    &self,
+    psbt: String,
+    extract: Option<bool>,
+  ) -> Result<Value, jsonrpc_core::Error>;
+
   #[rpc(name = "sendrawtransaction")]
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error>;
 
@@ -89,6 +105,17 @@ pub trait Api {
     blockhash: Option<BlockHash>,
   ) -> Result<Value, jsonrpc_core::Error>;
 
+  #[rpc(name = "gettxout")]
+  fn get_tx_out(
+    &self,
+    txid: Txid,
+    vout: u32,
+    include_mempool: Option<bool>,
+  ) -> Result<Option<GetTxOutResult>, jsonrpc_core::Error>;
+
+  #[rpc(name = "getmempoolentry")]
+  fn get_mempool_entry(&self, txid: Txid) -> Result<GetMempoolEntryResult, jsonrpc_core::Error>;
+
   #[rpc(name = "listunspent")]
   fn list_unspent(
     &self,
@@ -144,7 +171,10 @@ pub trait Api {
   ) -> Result<bool, jsonrpc_core::Error>;
 
   #[rpc(name = "listdescriptors")]
-  fn list_descriptors(&self) -> Result<ListDescriptorsResult, jsonrpc_core::Error>;
+  fn list_descriptors(
+    &self,
+    private: Option<bool>,
+  ) -> Result<ListDescriptorsResult, jsonrpc_core::Error>;
 
   #[rpc(name = "loadwallet")]
   fn load_wallet(&self, wallet: String) -> Result<LoadWalletResult, jsonrpc_core::Error>;
@@ -2,6 +2,7 @@
 
 use {
   api::Api,
+  base64::Engine,
   bitcoin::{
     address::{Address, NetworkUnchecked},
     amount::SignedAmount,
@@ -13,16 +14,19 @@ use {
     hashes::Hash,
     locktime::absolute::LockTime,
     pow::CompactTarget,
+    psbt::PartiallySignedTransaction,
     Amount, Block, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
     Wtxid,
   },
   bitcoincore_rpc::json::{
     Bip125Replaceable, CreateRawTransactionInput, Descriptor, EstimateMode, GetBalancesResult,
     GetBalancesResultEntry, GetBlockHeaderResult, GetBlockchainInfoResult, GetDescriptorInfoResult,
-    GetNetworkInfoResult, GetRawTransactionResult, GetTransactionResult,
-    GetTransactionResultDetail, GetTransactionResultDetailCategory, GetWalletInfoResult,
-    ImportDescriptors, ImportMultiResult, ListDescriptorsResult, ListTransactionResult,
-    ListUnspentResultEntry, LoadWalletResult, SignRawTransactionResult, Timestamp, WalletTxInfo,
+    GetMempoolEntryResult, GetMempoolEntryResultFees, GetNetworkInfoResult,
+    GetRawTransactionResult, GetRawTransactionResultVoutScriptPubKey, GetTransactionResult,
+    GetTransactionResultDetail, GetTransactionResultDetailCategory, GetTxOutResult,
+    GetWalletInfoResult, ImportDescriptors, ImportMultiResult, ListDescriptorsResult,
+    ListTransactionResult, ListUnspentResultEntry, LoadWalletResult, SignRawTransactionResult,
+    Timestamp, WalletProcessPsbtResult, WalletTxInfo,
   },
   jsonrpc_core::{IoHandler, Value},
   jsonrpc_http_server::{CloseHandle, ServerBuilder},
@@ -44,14 +48,18 @@ mod state;
 pub fn builder() -> Builder {
   Builder {
     fail_lock_unspent: false,
+    mempool_chain_limit: None,
     network: Network::Bitcoin,
+    txindex: true,
     version: 240000,
   }
 }
 
 pub struct Builder {
   fail_lock_unspent: bool,
+  mempool_chain_limit: Option<usize>,
   network: Network,
+  txindex: bool,
   version: usize,
 }
 
@@ -63,10 +71,21 @@ impl Builder {
     }
   }
 
+  pub fn mempool_chain_limit(self, mempool_chain_limit: usize) -> Self {
+    Self {
+      mempool_chain_limit: Some(mempool_chain_limit),
+      ..self
+    }
+  }
+
   pub fn network(self, network: Network) -> Self {
     Self { network, ..self }
   }
 
+  pub fn txindex(self, txindex: bool) -> Self {
+    Self { txindex, ..self }
+  }
+
   pub fn version(self, version: usize) -> Self {
     Self { version, ..self }
   }
@@ -76,6 +95,8 @@ impl Builder {
       self.network,
       self.version,
       self.fail_lock_unspent,
+      self.mempool_chain_limit,
+      self.txindex,
     )));
     let server = Server::new(state.clone());
     let mut io = IoHandler::default();
@@ -122,6 +143,7 @@ pub struct TransactionTemplate<'a> {
   pub inputs: &'a [(usize, usize, usize)],
   pub output_values: &'a [u64],
   pub outputs: usize,
+  pub recipient: Option<Address>,
   pub witness: Witness,
 }
 
@@ -154,6 +176,7 @@ impl<'a> Default for TransactionTemplate<'a> {
       inputs: &[],
       output_values: &[],
       outputs: 1,
+      recipient: None,
       witness: Witness::default(),
     }
   }
@@ -210,6 +233,10 @@ impl Handle {
     self.state().mempool().to_vec()
   }
 
+  pub fn clear_mempool(&self) {
+    self.state().mempool.clear();
+  }
+
   pub fn descriptors(&self) -> Vec<String> {
     self.state().descriptors.clone()
   }
@@ -25,6 +25,14 @@ impl Server {
   fn not_found() -> jsonrpc_core::Error {
     jsonrpc_core::Error::new(jsonrpc_core::types::error::ErrorCode::ServerError(-8))
   }
+
+  fn rejected(reason: String) -> jsonrpc_core::Error {
+    jsonrpc_core::Error {
+      code: jsonrpc_core::types::error::ErrorCode::ServerError(-26),
+      message: reason,
+      data: None,
+    }
+  }
 }
 
 impl Api for Server {
@@ -249,7 +257,9 @@ impl Api for Server {
 
     let mut transaction: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
     for input in &mut transaction.input {
-      input.witness = Witness::from_slice(&[&[0; 64]]);
+      if input.witness.is_empty() {
+        input.witness = Witness::from_slice(&[&[0; 64]]);
+      }
     }
 
     Ok(
@@ -262,9 +272,105 @@ impl Api for Server {
     )
   }
 
+  fn wallet_process_psbt(
+    &self,
+    psbt: String,
+    sign: Option<bool>,
+    _sighash_type: Option<String>,
+    _bip32derivs: Option<bool>,
+  ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error> {
+    assert_eq!(sign, Some(false), "signing PSBTs not supported");
+
+    let mut psbt = PartiallySignedTransaction::deserialize(
+      &base64::engine::general_purpose::STANDARD
+        .decode(psbt)
+        .unwrap(),
+    )
+    .unwrap();
+
+    let state = self.state();
+
+    for (input, txin) in psbt.inputs.iter_mut().zip(&psbt.unsigned_tx.input) {
+      let outpoint = txin.previous_output;
+
+      input.witness_utxo = state
+        .transactions
+        .get(&outpoint.txid)
+        .or_else(|| state.mempool().iter().find(|tx| tx.txid() == outpoint.txid))
+        .map(|tx| tx.output[outpoint.vout as usize].clone());
+    }
+
+    Ok(WalletProcessPsbtResult {
+      psbt: base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+      complete: false,
+    })
+  }
+
+  fn finalize_psbt(
+    &self,
+    psbt: String,
+    extract: Option<bool>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    assert_eq!(
+      extract,
+      Some(true),
+      "only extracting finalized PSBTs is supported"
+    );
+
+    let psbt = PartiallySignedTransaction::deserialize(
+      &base64::engine::general_purpose::STANDARD
+        .decode(&psbt)
+        .unwrap(),
+    )
+    .unwrap();
+
+    let mut tx = psbt.unsigned_tx.clone();
+
+    for (txin, input) in tx.input.iter_mut().zip(&psbt.inputs) {
+      txin.witness = match (&input.final_script_witness, input.tap_key_sig) {
+        (Some(witness), _) => witness.clone(),
+        (None, Some(signature)) => Witness::from_slice(&[signature.to_vec()]),
+        (None, None) => {
+          return Ok(serde_json::json!({
+            "psbt": base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+            "complete": false,
+          }))
+        }
+      };
+    }
+
+    Ok(serde_json::json!({
+      "hex": hex::encode(serialize(&tx)),
+      "complete": true,
+    }))
+  }
+
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error> {
     let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
-    self.state.lock().unwrap().mempool.push(tx.clone());
+
+    let mut state = self.state();
+
+    if let Some(limit) = state.mempool_chain_limit {
+      let ancestors = state.mempool_ancestors(&tx);
+
+      if ancestors.len() + 1 > limit {
+        return Err(Self::rejected(format!(
+          "too-long-mempool-chain, too many unconfirmed ancestors [limit: {limit}]"
+        )));
+      }
+
+      for ancestor in ancestors {
+        if state.mempool_descendants(ancestor).len() + 2 > limit {
+          return Err(Self::rejected(format!(
+            "too-long-mempool-chain, too many descendants for tx {ancestor} [limit: {limit}]"
+          )));
+        }
+      }
+    }
+
+    state.evict_conflicts(&tx);
+
+    state.mempool.push(tx.clone());
 
     Ok(tx.txid().to_string())
   }
@@ -308,12 +414,14 @@ impl Api for Server {
     txid: Txid,
     _include_watchonly: Option<bool>,
   ) -> Result<Value, jsonrpc_core::Error> {
-    match self.state.lock().unwrap().transactions.get(&txid) {
+    let state = self.state();
+
+    match state.transactions.get(&txid) {
       Some(tx) => Ok(
         serde_json::to_value(GetTransactionResult {
           info: WalletTxInfo {
             txid,
-            confirmations: 0,
+            confirmations: state.get_confirmations(tx),
             time: 0,
             timereceived: 0,
             blockhash: None,
@@ -343,37 +451,193 @@ impl Api for Server {
     blockhash: Option<BlockHash>,
   ) -> Result<Value, jsonrpc_core::Error> {
     assert_eq!(blockhash, None, "Blockhash param is unsupported");
+
+    let state = self.state();
+
+    // without -txindex, only mempool transactions can be looked up
+    if !state.txindex && !state.mempool().iter().any(|tx| tx.txid() == txid) {
+      return Err(jsonrpc_core::Error::new(
+        jsonrpc_core::types::error::ErrorCode::ServerError(-5),
+      ));
+    }
+
     if verbose.unwrap_or(false) {
-      match self.state().transactions.get(&txid) {
-        Some(_) => Ok(
-          serde_json::to_value(GetRawTransactionResult {
-            in_active_chain: Some(true),
-            hex: Vec::new(),
-            txid: Txid::all_zeros(),
-            hash: Wtxid::all_zeros(),
-            size: 0,
-            vsize: 0,
-            version: 0,
-            locktime: 0,
-            vin: Vec::new(),
-            vout: Vec::new(),
-            blockhash: None,
-            confirmations: Some(1),
-            time: None,
-            blocktime: None,
-          })
-          .unwrap(),
+      let (in_active_chain, confirmations) = match state.transactions.get(&txid) {
+        Some(tx) => (
+          Some(true),
+          Some(state.get_confirmations(tx).try_into().unwrap()),
         ),
-        None => Err(Self::not_found()),
-      }
+        None if state.mempool().iter().any(|tx| tx.txid() == txid) => (None, None),
+        None => return Err(Self::not_found()),
+      };
+
+      Ok(
+        serde_json::to_value(GetRawTransactionResult {
+          in_active_chain,
+          hex: Vec::new(),
+          txid: Txid::all_zeros(),
+          hash: Wtxid::all_zeros(),
+          size: 0,
+          vsize: 0,
+          version: 0,
+          locktime: 0,
+          vin: Vec::new(),
+          vout: Vec::new(),
+          blockhash: None,
+          confirmations,
+          time: None,
+          blocktime: None,
+        })
+        .unwrap(),
+      )
     } else {
-      match self.state().transactions.get(&txid) {
+      match state
+        .transactions
+        .get(&txid)
+        .or_else(|| state.mempool().iter().find(|tx| tx.txid() == txid))
+      {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
     }
   }
 
+  fn get_tx_out(
+    &self,
+    txid: Txid,
+    vout: u32,
+    include_mempool: Option<bool>,
+  ) -> Result<Option<GetTxOutResult>, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let outpoint = OutPoint { txid, vout };
+
+    let include_mempool = include_mempool.unwrap_or(true);
+
+    if include_mempool
+      && state
+        .mempool()
+        .iter()
+        .flat_map(|tx| &tx.input)
+        .any(|input| input.previous_output == outpoint)
+    {
+      return Ok(None);
+    }
+
+    let (tx, confirmations) = match state.transactions.get(&txid) {
+      Some(tx) if state.utxos.contains_key(&outpoint) => {
+        (tx, state.get_confirmations(tx).try_into().unwrap())
+      }
+      Some(_) => return Ok(None),
+      None if include_mempool => match state.mempool().iter().find(|tx| tx.txid() == txid) {
+        Some(tx) => (tx, 0),
+        None => return Ok(None),
+      },
+      None => return Ok(None),
+    };
+
+    let Some(output) = tx.output.get(usize::try_from(vout).unwrap()) else {
+      return Ok(None);
+    };
+
+    Ok(Some(GetTxOutResult {
+      bestblock: *state.hashes.last().unwrap(),
+      confirmations,
+      value: Amount::from_sat(output.value),
+      script_pub_key: GetRawTransactionResultVoutScriptPubKey {
+        asm: output.script_pubkey.to_asm_string(),
+        hex: output.script_pubkey.to_bytes(),
+        req_sigs: None,
+        type_: None,
+        addresses: Vec::new(),
+        address: None,
+      },
+      coinbase: tx.is_coin_base(),
+    }))
+  }
+
+  fn get_mempool_entry(&self, txid: Txid) -> Result<GetMempoolEntryResult, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let Some(tx) = state.mempool().iter().find(|tx| tx.txid() == txid) else {
+      return Err(jsonrpc_core::Error::new(
+        jsonrpc_core::types::error::ErrorCode::ServerError(-5),
+      ));
+    };
+
+    let ancestors = state.mempool_ancestors(tx);
+    let descendants = state.mempool_descendants(txid);
+
+    let get = |txid: &Txid| {
+      state
+        .mempool()
+        .iter()
+        .find(|tx| tx.txid() == *txid)
+        .unwrap()
+    };
+
+    let fee = state.mempool_fee(tx);
+
+    Ok(GetMempoolEntryResult {
+      vsize: tx.vsize().try_into().unwrap(),
+      weight: Some(tx.weight().to_wu()),
+      time: 0,
+      height: (state.hashes.len() - 1).try_into().unwrap(),
+      descendant_count: (descendants.len() + 1).try_into().unwrap(),
+      descendant_size: (tx.vsize()
+        + descendants
+          .iter()
+          .map(|txid| get(txid).vsize())
+          .sum::<usize>())
+      .try_into()
+      .unwrap(),
+      ancestor_count: (ancestors.len() + 1).try_into().unwrap(),
+      ancestor_size: (tx.vsize()
+        + ancestors
+          .iter()
+          .map(|txid| get(txid).vsize())
+          .sum::<usize>())
+      .try_into()
+      .unwrap(),
+      wtxid: Txid::from_raw_hash(tx.wtxid().to_raw_hash()),
+      fees: GetMempoolEntryResultFees {
+        base: fee,
+        modified: fee,
+        ancestor: ancestors
+          .iter()
+          .map(|txid| state.mempool_fee(get(txid)))
+          .sum::<Amount>()
+          + fee,
+        descendant: descendants
+          .iter()
+          .map(|txid| state.mempool_fee(get(txid)))
+          .sum::<Amount>()
+          + fee,
+      },
+      depends: tx
+        .input
+        .iter()
+        .map(|input| input.previous_output.txid)
+        .filter(|txid| ancestors.contains(txid))
+        .collect::<BTreeSet<Txid>>()
+        .into_iter()
+        .collect(),
+      spent_by: state
+        .mempool()
+        .iter()
+        .filter(|child| {
+          child
+            .input
+            .iter()
+            .any(|input| input.previous_output.txid == txid)
+        })
+        .map(|child| child.txid())
+        .collect(),
+      bip125_replaceable: true,
+      unbroadcast: None,
+    })
+  }
+
   fn list_unspent(
     &self,
     minconf: Option<usize>,
@@ -395,6 +659,13 @@ impl Api for Server {
         .utxos
         .iter()
         .filter(|(outpoint, _amount)| !state.locked.contains(outpoint))
+        .filter(|(outpoint, _amount)| {
+          !state
+            .mempool()
+            .iter()
+            .flat_map(|tx| &tx.input)
+            .any(|input| input.previous_output == **outpoint)
+        })
         .map(|(outpoint, &amount)| ListUnspentResultEntry {
           txid: outpoint.txid,
           vout: outpoint.vout,
@@ -454,16 +725,21 @@ impl Api for Server {
     &self,
     req: Vec<ImportDescriptors>,
   ) -> Result<Vec<ImportMultiResult>, jsonrpc_core::Error> {
+    let results = req
+      .iter()
+      .map(|_| ImportMultiResult {
+        success: true,
+        warnings: Vec::new(),
+        error: None,
+      })
+      .collect();
+
     self
       .state()
       .descriptors
       .extend(req.into_iter().map(|params| params.descriptor));
 
-    Ok(vec![ImportMultiResult {
-      success: true,
-      warnings: Vec::new(),
-      error: None,
-    }])
+    Ok(results)
   }
 
   fn get_new_address(
@@ -528,8 +804,6 @@ impl Api for Server {
     unlock: bool,
     outputs: Vec<JsonOutPoint>,
   ) -> Result<bool, jsonrpc_core::Error> {
-    assert!(!unlock);
-
     let mut state = self.state();
 
     if state.fail_lock_unspent {
@@ -542,13 +816,20 @@ impl Api for Server {
         txid: output.txid,
       };
       assert!(state.utxos.contains_key(&output));
-      state.locked.insert(output);
+      if unlock {
+        state.locked.remove(&output);
+      } else {
+        state.locked.insert(output);
+      }
     }
 
     Ok(true)
   }
 
-  fn list_descriptors(&self) -> Result<ListDescriptorsResult, jsonrpc_core::Error> {
+  fn list_descriptors(
+    &self,
+    _private: Option<bool>,
+  ) -> Result<ListDescriptorsResult, jsonrpc_core::Error> {
     Ok(ListDescriptorsResult {
       wallet_name: "ord".into(),
       descriptors: self
@@ -558,7 +839,7 @@ impl Api for Server {
         .map(|desc| Descriptor {
           desc: desc.to_string(),
           timestamp: Timestamp::Now,
-          active: true,
+          active: !desc.starts_with("rawtr("),
           internal: None,
           range: None,
           next: None,
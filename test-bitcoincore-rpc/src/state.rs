@@ -8,17 +8,25 @@ pub(crate) struct State {
   pub(crate) loaded_wallets: BTreeSet<String>,
   pub(crate) locked: BTreeSet<OutPoint>,
   pub(crate) mempool: Vec<Transaction>,
+  pub(crate) mempool_chain_limit: Option<usize>,
   pub(crate) network: Network,
   pub(crate) nonce: u32,
   pub(crate) sent: Vec<Sent>,
   pub(crate) transactions: BTreeMap<Txid, Transaction>,
+  pub(crate) txindex: bool,
   pub(crate) utxos: BTreeMap<OutPoint, Amount>,
   pub(crate) version: usize,
   pub(crate) wallets: BTreeSet<String>,
 }
 
 impl State {
-  pub(crate) fn new(network: Network, version: usize, fail_lock_unspent: bool) -> Self {
+  pub(crate) fn new(
+    network: Network,
+    version: usize,
+    fail_lock_unspent: bool,
+    mempool_chain_limit: Option<usize>,
+    txindex: bool,
+  ) -> Self {
     let mut hashes = Vec::new();
     let mut blocks = BTreeMap::new();
 
@@ -34,10 +42,12 @@ impl State {
       hashes,
       locked: BTreeSet::new(),
       mempool: Vec::new(),
+      mempool_chain_limit,
       network,
       nonce: 0,
       sent: Vec::new(),
       transactions: BTreeMap::new(),
+      txindex,
       utxos: BTreeMap::new(),
       version,
       wallets: BTreeSet::new(),
@@ -159,7 +169,11 @@ impl State {
             .get(i)
             .cloned()
             .unwrap_or(value_per_output),
-          script_pubkey: script::Builder::new().into_script(),
+          script_pubkey: template
+            .recipient
+            .as_ref()
+            .map(|recipient| recipient.script_pubkey())
+            .unwrap_or_else(|| script::Builder::new().into_script()),
         })
         .collect(),
     };
@@ -172,6 +186,89 @@ impl State {
     &self.mempool
   }
 
+  pub(crate) fn mempool_ancestors(&self, tx: &Transaction) -> BTreeSet<Txid> {
+    let mut ancestors = BTreeSet::new();
+    let mut stack = vec![tx];
+
+    while let Some(tx) = stack.pop() {
+      for input in &tx.input {
+        if let Some(parent) = self
+          .mempool
+          .iter()
+          .find(|parent| parent.txid() == input.previous_output.txid)
+        {
+          if ancestors.insert(parent.txid()) {
+            stack.push(parent);
+          }
+        }
+      }
+    }
+
+    ancestors
+  }
+
+  pub(crate) fn mempool_descendants(&self, txid: Txid) -> BTreeSet<Txid> {
+    let mut descendants = BTreeSet::new();
+    let mut stack = vec![txid];
+
+    while let Some(txid) = stack.pop() {
+      for child in &self.mempool {
+        if child
+          .input
+          .iter()
+          .any(|input| input.previous_output.txid == txid)
+          && descendants.insert(child.txid())
+        {
+          stack.push(child.txid());
+        }
+      }
+    }
+
+    descendants
+  }
+
+  /// Remove mempool transactions that spend any input of `tx`, along with
+  /// their descendants, as bitcoind does when a replacement is accepted.
+  pub(crate) fn evict_conflicts(&mut self, tx: &Transaction) {
+    let mut evicted = self
+      .mempool
+      .iter()
+      .filter(|conflict| {
+        conflict.input.iter().any(|conflict_input| {
+          tx.input
+            .iter()
+            .any(|input| input.previous_output == conflict_input.previous_output)
+        })
+      })
+      .map(Transaction::txid)
+      .collect::<BTreeSet<Txid>>();
+
+    for txid in evicted.clone() {
+      evicted.extend(self.mempool_descendants(txid));
+    }
+
+    self.mempool.retain(|tx| !evicted.contains(&tx.txid()));
+  }
+
+  pub(crate) fn mempool_fee(&self, tx: &Transaction) -> Amount {
+    let input_value = tx
+      .input
+      .iter()
+      .map(|input| {
+        let outpoint = input.previous_output;
+        self
+          .transactions
+          .get(&outpoint.txid)
+          .or_else(|| self.mempool.iter().find(|tx| tx.txid() == outpoint.txid))
+          .unwrap()
+          .output[usize::try_from(outpoint.vout).unwrap()]
+        .value
+      })
+      .sum::<u64>();
+
+    Amount::from_sat(input_value - tx.output.iter().map(|output| output.value).sum::<u64>())
+  }
+
   pub(crate) fn get_confirmations(&self, tx: &Transaction) -> i32 {
     for (confirmations, hash) in self.hashes.iter().rev().enumerate() {
       if self.blocks.get(hash).unwrap().txdata.contains(tx) {
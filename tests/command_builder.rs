@@ -34,7 +34,7 @@ pub(crate) struct CommandBuilder {
   expected_stderr: Expected,
   expected_stdout: Expected,
   rpc_server_url: Option<String>,
-  tempdir: TempDir,
+  tempdir: Arc<TempDir>,
 }
 
 impl CommandBuilder {
@@ -45,12 +45,14 @@ impl CommandBuilder {
       expected_stderr: Expected::String(String::new()),
       expected_stdout: Expected::String(String::new()),
       rpc_server_url: None,
-      tempdir: TempDir::new().unwrap(),
+      tempdir: Arc::new(TempDir::new().unwrap()),
     }
   }
 
   pub(crate) fn write(self, path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Self {
-    fs::write(self.tempdir.path().join(path), contents).unwrap();
+    let path = self.tempdir.path().join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
     self
   }
 
@@ -89,7 +91,7 @@ impl CommandBuilder {
     }
   }
 
-  pub(crate) fn temp_dir(self, tempdir: TempDir) -> Self {
+  pub(crate) fn temp_dir(self, tempdir: Arc<TempDir>) -> Self {
     Self { tempdir, ..self }
   }
 
@@ -112,7 +114,7 @@ impl CommandBuilder {
       .stdin(Stdio::null())
       .stdout(Stdio::piped())
       .stderr(Stdio::piped())
-      .current_dir(&self.tempdir)
+      .current_dir(self.tempdir.path())
       .arg("--data-dir")
       .arg(self.tempdir.path())
       .args(&self.args);
@@ -0,0 +1,66 @@
+use {super::*, bitcoin::consensus, ord::subcommand::decode::Output};
+
+#[test]
+fn decode_raw_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribe { reveal, .. } = CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  let hex = consensus::encode::serialize_hex(&rpc_server.mempool()[1]);
+
+  let output = CommandBuilder::new(format!("decode {hex}")).run_and_check_output::<Output>();
+
+  assert_eq!(output.txid, reveal);
+  assert_eq!(output.envelopes.len(), 1);
+
+  let envelope = &output.envelopes[0];
+  assert!(envelope.valid);
+  assert_eq!(envelope.input, 0);
+  assert_eq!(envelope.offset, 0);
+  assert_eq!(envelope.inscription_id, Some(format!("{reveal}i0")));
+  assert_eq!(
+    envelope.content_type,
+    Some("text/plain;charset=utf-8".into())
+  );
+  assert_eq!(envelope.content_length, Some(3));
+  assert_eq!(envelope.curse, None);
+  assert_eq!(envelope.number, None);
+  assert_eq!(envelope.unbound, None);
+
+  let output = CommandBuilder::new("decode tx.hex")
+    .write("tx.hex", &hex)
+    .run_and_check_output::<Output>();
+
+  assert_eq!(output.txid, reveal);
+  assert_eq!(output.envelopes.len(), 1);
+}
+
+#[test]
+fn decode_indexed_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let Inscribe { reveal, .. } = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new(format!("decode {reveal}"))
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  assert_eq!(output.envelopes.len(), 1);
+  assert_eq!(output.envelopes[0].number, Some(0));
+  assert_eq!(output.envelopes[0].unbound, Some(false));
+  assert_eq!(output.envelopes[0].curse, None);
+}
+
+#[test]
+fn decode_invalid_argument() {
+  CommandBuilder::new("decode foo")
+    .stderr_regex("error: `foo` is neither a txid, raw transaction hex, nor a readable file\n.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
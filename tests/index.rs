@@ -68,7 +68,7 @@ fn index_runs_with_rpc_user_and_pass_as_env_vars() {
 #[test]
 fn export_inscription_number_to_id_tsv() {
   let rpc_server = test_bitcoincore_rpc::spawn();
-  let temp_dir = TempDir::new().unwrap();
+  let temp_dir = Arc::new(TempDir::new().unwrap());
   create_wallet(&rpc_server);
 
   inscribe(&rpc_server);
@@ -99,3 +99,74 @@ fn export_inscription_number_to_id_tsv() {
     &ord::Object::from_str(&inscription).unwrap()
   )
 }
+
+#[test]
+fn verify_reports_no_discrepancies() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("foo.redb");
+
+  CommandBuilder::new(format!(
+    "--index-sats --index {} index run",
+    index_path.display()
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "--index-sats --index {} index verify",
+    index_path.display()
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(r#"\{\s*"discrepancies": \[\]\s*\}\s*"#)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn stats_lists_tables() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("foo.redb");
+
+  CommandBuilder::new(format!(
+    "--index-sats --index {} index run",
+    index_path.display()
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "--index-sats --index {} index stats --tables",
+    index_path.display()
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(
+    r#".*"tables": \[.*"name": "outpoint_to_sat_ranges",\s*"rows": 3,\s*"key_bytes": 108,\s*"value_bytes": 22\s*\}.*"#,
+  )
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn compact() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("foo.redb");
+
+  CommandBuilder::new(format!("--index {} index run", index_path.display()))
+    .rpc_server(&rpc_server)
+    .run_and_extract_stdout();
+
+  CommandBuilder::new(format!("--index {} index compact", index_path.display()))
+    .rpc_server(&rpc_server)
+    .stdout_regex(r#"\{\s*"compacted": (true|false),\s*"index_file_size_before": \d+,\s*"index_file_size_after": \d+\s*\}\s*"#)
+    .run_and_extract_stdout();
+}
@@ -19,6 +19,7 @@ use {
     path::Path,
     process::{Child, Command, Stdio},
     str::{self, FromStr},
+    sync::Arc,
     thread,
     time::Duration,
   },
@@ -75,6 +76,7 @@ fn create_wallet(rpc_server: &test_bitcoincore_rpc::Handle) {
 
 mod command_builder;
 mod core;
+mod decode;
 mod epochs;
 mod expected;
 mod find;
@@ -42,7 +42,9 @@ fn no_satoshi_index() {
   let rpc_server = test_bitcoincore_rpc::spawn();
   CommandBuilder::new("list 4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0")
     .rpc_server(&rpc_server)
-    .expected_stderr("error: list requires index created with `--index-sats` flag\n")
+    .expected_stderr(
+      "error: list requires index created with `--index-sats` flag, but sats are not indexed\n",
+    )
     .expected_exit_code(1)
     .run_and_extract_stdout();
 }
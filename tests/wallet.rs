@@ -1,6 +1,8 @@
 use super::*;
 
 mod balance;
+mod broadcast;
+mod bump_fee;
 mod cardinals;
 mod create;
 mod inscribe;
@@ -10,4 +12,5 @@ mod receive;
 mod restore;
 mod sats;
 mod send;
+mod send_many;
 mod transactions;
@@ -47,3 +47,34 @@ fn wallet_balance_only_counts_cardinal_utxos() {
     100 * COIN_VALUE - 10_000
   );
 }
+
+#[test]
+fn watch_only_wallet_balance_uses_address_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  rpc_server.mine_blocks(1);
+
+  rpc_server.broadcast_tx(test_bitcoincore_rpc::TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    outputs: 2,
+    recipient: Some(
+      "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .assume_checked(),
+    ),
+    ..Default::default()
+  });
+
+  rpc_server.mine_blocks(1);
+
+  assert_eq!(
+    CommandBuilder::new(
+      "--index-addresses --watch-address bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 wallet balance"
+    )
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>()
+    .cardinal,
+    50 * COIN_VALUE
+  );
+}
@@ -0,0 +1,214 @@
+use {
+  super::*,
+  base64::Engine,
+  bitcoin::{
+    psbt::PartiallySignedTransaction, secp256k1::schnorr, sighash::TapSighashType,
+    taproot::Signature,
+  },
+};
+
+#[derive(Deserialize)]
+struct Inscribes {
+  commit: Txid,
+  inscription: Vec<String>,
+  reveal: Vec<Txid>,
+}
+
+#[derive(Deserialize)]
+struct Broadcast {
+  transaction: Txid,
+  reveals: Vec<Txid>,
+  inscriptions: Vec<String>,
+}
+
+fn read_psbt(path: &Path) -> PartiallySignedTransaction {
+  PartiallySignedTransaction::deserialize(
+    &base64::engine::general_purpose::STANDARD
+      .decode(fs::read_to_string(path).unwrap())
+      .unwrap(),
+  )
+  .unwrap()
+}
+
+fn sign_psbt(path: &Path) {
+  let mut psbt = read_psbt(path);
+
+  for input in &mut psbt.inputs {
+    input.tap_key_sig = Some(Signature {
+      sig: schnorr::Signature::from_slice(&[1; 64]).unwrap(),
+      hash_ty: TapSighashType::Default,
+    });
+  }
+
+  fs::write(
+    path,
+    base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+  )
+  .unwrap();
+}
+
+#[test]
+fn inscribe_with_psbt_out_is_broadcast_after_external_signing() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribe {
+    commit,
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribe --fee-rate 1 --psbt-out commit.psbt foo.txt")
+    .temp_dir(tempdir.clone())
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  let path = tempdir.path().join("commit.psbt");
+
+  let psbt = read_psbt(&path);
+  assert_eq!(psbt.unsigned_tx.txid(), commit);
+  assert!(psbt.inputs.iter().all(|input| input.witness_utxo.is_some()));
+
+  CommandBuilder::new("wallet broadcast --psbt commit.psbt")
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: PSBT commit.psbt is not fully signed\n.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  sign_psbt(&path);
+
+  let Broadcast {
+    transaction,
+    reveals,
+    inscriptions,
+  } = CommandBuilder::new("wallet broadcast --psbt commit.psbt")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(transaction, commit);
+  assert_eq!(reveals, [reveal]);
+  assert_eq!(inscriptions, [inscription.clone()]);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[0].txid(), commit);
+  assert_eq!(mempool[0].input[0].witness.to_vec(), [vec![1; 64]]);
+  assert_eq!(mempool[1].txid(), reveal);
+  assert_eq!(mempool[1].input[0].witness.len(), 3);
+
+  rpc_server.mine_blocks(1);
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  ord_server.assert_response_regex(
+    format!("/inscription/{inscription}"),
+    ".*<h1>Inscription 0</h1>.*",
+  );
+}
+
+#[test]
+fn inscribes_with_psbt_out_is_broadcast_after_external_signing() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes {
+    commit,
+    inscription,
+    reveal,
+  } = CommandBuilder::new(
+    "wallet inscribes --fee-rate 1 --mint-size 2 --psbt-out commit.psbt foo.txt",
+  )
+  .temp_dir(tempdir.clone())
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_check_output();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  sign_psbt(&tempdir.path().join("commit.psbt"));
+
+  let Broadcast {
+    transaction,
+    reveals,
+    inscriptions,
+  } = CommandBuilder::new("wallet broadcast --psbt commit.psbt")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(transaction, commit);
+  assert_eq!(reveals, reveal);
+  assert_eq!(inscriptions, inscription);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+  assert_eq!(mempool[0].txid(), commit);
+  assert_eq!(mempool[1].txid(), reveal[0]);
+  assert_eq!(mempool[2].txid(), reveal[1]);
+}
+
+#[test]
+fn inscribes_with_psbt_out_requires_single_commit() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(2);
+
+  CommandBuilder::new(
+    "wallet inscribes --fee-rate 1 --mint-size 3 --reveals-per-commit 2 --psbt-out commit.psbt foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stderr_regex(
+    "error: --psbt-out needs a single commit but inscriptions are split across 2 commits\n.*",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn send_with_psbt_out_is_broadcast_after_external_signing() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  let coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let txid = CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --psbt-out send.psbt bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {coinbase}:0:0"
+  ))
+  .temp_dir(tempdir.clone())
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  sign_psbt(&tempdir.path().join("send.psbt"));
+
+  let Broadcast {
+    transaction,
+    reveals,
+    inscriptions,
+  } = CommandBuilder::new("wallet broadcast --psbt send.psbt")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(transaction.to_string(), txid.trim());
+  assert!(reveals.is_empty());
+  assert!(inscriptions.is_empty());
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid(), transaction);
+}
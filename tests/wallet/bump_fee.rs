@@ -0,0 +1,271 @@
+use super::*;
+
+#[derive(Deserialize)]
+struct Inscribes {
+  commit: Txid,
+  inscription: Vec<String>,
+  reveal: Vec<Txid>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "method")]
+enum BumpFee {
+  Rbf {
+    original: Txid,
+    commit: Txid,
+    reveals: Vec<Txid>,
+    inscriptions: Vec<String>,
+    fee: u64,
+  },
+  Cpfp {
+    parent: Txid,
+    child: Txid,
+    fee: u64,
+  },
+}
+
+#[test]
+fn bump_fee_replaces_commit_and_resigns_reveals() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes {
+    commit,
+    inscription,
+    reveal,
+  } = CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 2 foo.txt")
+    .temp_dir(tempdir.clone())
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  let original = rpc_server.mempool()[0].clone();
+  assert_eq!(original.txid(), commit);
+
+  let BumpFee::Rbf {
+    original: replaced,
+    commit: replacement,
+    reveals,
+    inscriptions,
+    fee,
+  } = CommandBuilder::new(format!("wallet bump-fee --fee-rate 5 {commit}"))
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .run_and_check_output()
+  else {
+    panic!("expected commit to be replaced");
+  };
+
+  assert_eq!(replaced, commit);
+  assert_ne!(replacement, commit);
+  assert_eq!(reveals.len(), 2);
+  assert_ne!(reveals, reveal);
+  assert_ne!(inscriptions, inscription);
+  assert_eq!(fee, 5 * original.vsize() as u64);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+  assert_eq!(mempool[0].txid(), replacement);
+  assert_eq!(mempool[0].input, {
+    let mut input = original.input.clone();
+    for input in &mut input {
+      input.witness = mempool[0].input[0].witness.clone();
+    }
+    input
+  });
+  assert_eq!(
+    mempool[0].output[..2],
+    original.output[..2],
+    "commit outputs must be unchanged"
+  );
+
+  for (tx, reveal) in mempool[1..].iter().zip(&reveals) {
+    assert_eq!(tx.txid(), *reveal);
+    assert_eq!(tx.input[0].previous_output.txid, replacement);
+  }
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for inscription in &inscriptions {
+    server.assert_response_regex(format!("/content/{inscription}"), "FOO");
+  }
+
+  CommandBuilder::new(format!("wallet inscribes status {replacement}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .stdout_regex(r#".*"commit_state": "confirmed".*"#)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bump_fee_below_replacement_fee_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  CommandBuilder::new(format!("wallet bump-fee --fee-rate 1 {commit}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .stderr_regex(format!(
+      "error: fee of .* sats too low to replace commit {commit} and its reveals paying .* sats\n.*"
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert_eq!(rpc_server.mempool()[0].txid(), commit);
+}
+
+#[test]
+fn bump_fee_must_pay_for_evicted_reveals() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  // enough to replace the commit alone, but not the reveal it evicts
+  CommandBuilder::new(format!("wallet bump-fee --fee-rate 2.5 {commit}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .stderr_regex(format!(
+      "error: fee of .* sats too low to replace commit {commit} and its reveals paying .* sats\n.*"
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert_eq!(rpc_server.mempool()[0].txid(), commit);
+}
+
+#[test]
+fn bump_fee_refuses_to_drop_descendants_of_reveals() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(2);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, reveal, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  CommandBuilder::new(format!("wallet bump-fee --fee-rate 10 {}", reveal[0]))
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<BumpFee>();
+
+  CommandBuilder::new(format!("wallet bump-fee --fee-rate 50 {commit}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .stderr_regex(format!(
+      "error: commit {commit} has unconfirmed descendants other than its reveals, which replacing it would drop\n.*"
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert_eq!(rpc_server.mempool().len(), 3);
+  assert_eq!(rpc_server.mempool()[0].txid(), commit);
+}
+
+#[test]
+fn bump_fee_attaches_child_to_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(2);
+
+  let Inscribe {
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  let parent = rpc_server.mempool()[1].clone();
+  assert_eq!(parent.txid(), reveal);
+
+  let BumpFee::Cpfp {
+    parent: txid,
+    child,
+    fee,
+  } = CommandBuilder::new(format!("wallet bump-fee --fee-rate 10 {reveal}"))
+    .rpc_server(&rpc_server)
+    .run_and_check_output()
+  else {
+    panic!("expected child to be attached");
+  };
+
+  assert_eq!(txid, reveal);
+  assert!(fee > 0);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+
+  let child_tx = &mempool[2];
+  assert_eq!(child_tx.txid(), child);
+  assert_eq!(
+    child_tx.input[0].previous_output,
+    OutPoint {
+      txid: reveal,
+      vout: 0
+    }
+  );
+  assert_eq!(child_tx.output[0], parent.output[0]);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(
+    format!("/api/v1/inscription/{inscription}"),
+    format!(r#".*"satpoint":"{child}:0:0".*"#),
+  );
+}
+
+#[test]
+fn bump_fee_on_confirmed_transaction_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribes { reveal, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 foo.txt")
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("wallet bump-fee --fee-rate 10 {}", reveal[0]))
+    .rpc_server(&rpc_server)
+    .stderr_regex(format!(
+      "error: transaction {} is already confirmed\n.*",
+      reveal[0]
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
@@ -417,3 +417,1042 @@ fn inscribe_with_no_limit() {
     .write("degenerate.png", four_megger)
     .rpc_server(&rpc_server);
 }
+
+#[test]
+fn inscribe_with_json_metadata() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribe { inscription, .. } =
+    CommandBuilder::new("wallet inscribe foo.txt --metadata metadata.json --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .write("metadata.json", r#"{"description": "bar"}"#)
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  rpc_server.mine_blocks(1);
+
+  TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+    format!("/api/v1/inscription/{inscription}/metadata"),
+    r#"\{"description":"bar"\}"#,
+  );
+}
+
+#[test]
+fn inscribe_with_invalid_cbor_metadata_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe foo.txt --metadata metadata.cbor --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .write("metadata.cbor", [0xff, 0xff])
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: failed to parse CBOR metadata in .*metadata.cbor\n.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_parent() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let Inscribe {
+    inscription: parent,
+    ..
+  } = inscribe(&rpc_server);
+
+  let Inscribe {
+    inscription: child,
+    reveal,
+    ..
+  } = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --parent {parent} child.txt"
+  ))
+  .write("child.txt", "CHILD")
+  .rpc_server(&rpc_server)
+  .run_and_check_output();
+
+  let reveal_tx = &rpc_server.mempool()[1];
+  assert_eq!(reveal_tx.txid(), reveal);
+  assert_eq!(reveal_tx.input.len(), 3);
+  assert_eq!(reveal_tx.output.len(), 3);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(
+    format!("/inscription/{parent}"),
+    format!(
+      ".*<dt>children</dt>\n  <dd class=thumbnails>\n    <a href=/inscription/{child}>.*<dt>location</dt>\n  <dd class=monospace>{reveal}:1:0</dd>.*"
+    ),
+  );
+
+  server.assert_response_regex(
+    format!("/inscription/{child}"),
+    format!(
+      ".*<dt>parent</dt>\n  <dd><a class=monospace href=/inscription/{parent}>{parent}</a></dd>.*"
+    ),
+  );
+}
+
+#[test]
+fn inscribe_with_nonexistent_parent_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --parent 0000000000000000000000000000000000000000000000000000000000000000i0 child.txt",
+  )
+  .write("child.txt", "CHILD")
+  .rpc_server(&rpc_server)
+  .stderr_regex("error: parent 0{64}i0 does not exist\n.*")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_compress() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let content = "FOO".repeat(1000);
+
+  let Inscribe { inscription, .. } =
+    CommandBuilder::new("wallet inscribe --compress --fee-rate 1 foo.txt")
+      .write("foo.txt", &content)
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  let reveal_tx = &rpc_server.mempool()[1];
+  assert!(
+    reveal_tx.input[0]
+      .witness
+      .iter()
+      .map(<[u8]>::len)
+      .sum::<usize>()
+      < content.len()
+  );
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(format!("/content/{inscription}"), &content);
+
+  server.assert_response_regex(
+    format!("/inscription/{inscription}"),
+    ".*<dt>content encoding</dt>\n  <dd>br</dd>.*",
+  );
+}
+
+#[test]
+fn inscribe_with_metaprotocol() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribe { inscription, .. } =
+    CommandBuilder::new("wallet inscribe --metaprotocol foo --fee-rate 1 foo.txt")
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(
+    format!("/inscription/{inscription}"),
+    ".*<dt>metaprotocol</dt>\n  <dd>foo</dd>.*",
+  );
+
+  server.assert_response_regex(
+    "/api/v1/inscriptions?metaprotocol=foo",
+    format!(r#"\{{"inscriptions":\["{inscription}"\]\}}"#),
+  );
+}
+
+#[derive(Deserialize)]
+struct Inscribes {
+  #[allow(dead_code)]
+  commit: Txid,
+  #[allow(dead_code)]
+  commits: Vec<Txid>,
+  inscription: Vec<String>,
+  reveal: Vec<Txid>,
+  #[allow(dead_code)]
+  fees: u64,
+}
+
+#[test]
+fn inscribes_with_delegate() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let Inscribe { inscription, .. } = inscribe(&rpc_server);
+
+  let Inscribes {
+    inscription: delegated,
+    ..
+  } = CommandBuilder::new(format!(
+    "wallet inscribes --delegate {inscription} --mint-size 1 --fee-rate 1"
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_check_output();
+
+  let reveal_tx = rpc_server.mempool().last().unwrap().clone();
+  assert_eq!(reveal_tx.input[0].witness.len(), 3);
+  assert!(reveal_tx.input[0].witness.iter().nth(1).unwrap().len() < 100);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(format!("/content/{}", delegated[0]), "FOO");
+
+  server.assert_response_regex(
+    format!("/inscription/{}", delegated[0]),
+    format!(".*<dt>delegate</dt>\n  <dd><a class=monospace href=/inscription/{inscription}>{inscription}</a></dd>.*"),
+  );
+}
+
+#[test]
+fn inscribes_with_nonexistent_delegate_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let delegate = "0000000000000000000000000000000000000000000000000000000000000000i0";
+
+  CommandBuilder::new(format!(
+    "wallet inscribes --delegate {delegate} --mint-size 1 --fee-rate 1"
+  ))
+  .rpc_server(&rpc_server)
+  .stderr_regex(format!("error: delegate {delegate} does not exist\n.*"))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribes_with_single_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribes {
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribes --single-reveal --mint-size 3 --fee-rate 1 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(reveal.len(), 1);
+  assert_eq!(inscription.len(), 3);
+
+  let reveal_tx = rpc_server.mempool().last().unwrap().clone();
+  assert_eq!(reveal_tx.input.len(), 1);
+  assert_eq!(reveal_tx.output.len(), 3);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for (vout, inscription) in inscription.iter().enumerate() {
+    assert_eq!(inscription, &format!("{}i{vout}", reveal[0]));
+
+    server.assert_response_regex(
+      format!("/api/v1/inscription/{inscription}"),
+      format!(
+        r#".*"number":{},.*"satpoint":"{}:{vout}:0".*"#,
+        -i64::try_from(vout).unwrap(),
+        reveal[0]
+      ),
+    );
+
+    server.assert_response_regex(format!("/content/{inscription}"), "FOO");
+  }
+}
+
+#[test]
+fn inscribes_commit_with_many_inputs_pays_fee_rate() {
+  for args in ["--single-reveal --mint-size 2", "--mint-size 2"] {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks_with_subsidy(10, 3_000);
+
+    CommandBuilder::new(format!("wallet inscribes {args} --fee-rate 10 foo.txt"))
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output::<Inscribes>();
+
+    let commit_tx = &rpc_server.mempool()[0];
+    assert!(commit_tx.input.len() > 1);
+
+    let mut fee = 0;
+    for input in &commit_tx.input {
+      fee += rpc_server
+        .get_utxo_amount(&input.previous_output)
+        .unwrap()
+        .to_sat();
+    }
+    for output in &commit_tx.output {
+      fee -= output.value;
+    }
+
+    assert!(fee as f64 / commit_tx.vsize() as f64 >= 10.0, "{args}");
+  }
+}
+
+#[test]
+fn inscribes_batch_directory_in_single_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribes {
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribes --fee-rate 1 --batch batch")
+    .write("batch/a.txt", "AAA")
+    .write("batch/b.txt", "BBB")
+    .write("batch/c.txt", "CCC")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(reveal.len(), 1);
+  assert_eq!(inscription.len(), 3);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for (vout, (inscription, content)) in inscription.iter().zip(["AAA", "BBB", "CCC"]).enumerate() {
+    server.assert_response_regex(
+      format!("/api/v1/inscription/{inscription}"),
+      format!(
+        r#".*"number":{},.*"satpoint":"{}:{vout}:0".*"#,
+        -i64::try_from(vout).unwrap(),
+        reveal[0]
+      ),
+    );
+
+    server.assert_response_regex(format!("/content/{inscription}"), content);
+  }
+}
+
+#[test]
+fn inscribes_batch_manifest() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribes {
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribes --fee-rate 1 --batch manifest.txt")
+    .write("manifest.txt", "# comment\nfiles/b.txt\n\nfiles/a.txt\n")
+    .write("files/a.txt", "AAA")
+    .write("files/b.txt", "BBB")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(reveal.len(), 1);
+  assert_eq!(inscription.len(), 2);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(format!("/content/{}", inscription[0]), "BBB");
+  server.assert_response_regex(format!("/content/{}", inscription[1]), "AAA");
+}
+
+#[test]
+fn inscribes_batch_splits_reveals_at_max_standard_weight() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let content = vec![0; 150_000];
+
+  let Inscribes {
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribes --fee-rate 1 --batch batch")
+    .write("batch/a.txt", &content)
+    .write("batch/b.txt", &content)
+    .write("batch/c.txt", &content)
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(reveal.len(), 2);
+  assert_eq!(inscription.len(), 3);
+  assert_eq!(inscription[0], format!("{}i0", reveal[0]));
+  assert_eq!(inscription[1], format!("{}i1", reveal[0]));
+  assert_eq!(inscription[2], format!("{}i0", reveal[1]));
+}
+
+#[test]
+fn inscribes_manifest_with_per_item_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribes {
+    commits,
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribes --fee-rate 1 --manifest manifest.yaml")
+    .write(
+      "manifest.yaml",
+      "postage: 1000
+inscriptions:
+- text: foo.btc
+  destination: bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4
+- file: bar.html
+  destination: bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297
+  postage: 2000
+  metadata:
+    title: bar
+",
+    )
+    .write("bar.html", "<h1>BAR</h1>")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(commits.len(), 1);
+  assert_eq!(reveal.len(), 2);
+  assert_eq!(inscription.len(), 2);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+
+  assert_eq!(mempool[1].output.len(), 1);
+  assert_eq!(mempool[1].output[0].value, 1000);
+  assert_eq!(
+    mempool[1].output[0].script_pubkey,
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey()
+  );
+  assert_eq!(mempool[2].output[0].value, 2000);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  server.assert_response_regex(format!("/content/{}", inscription[0]), "foo.btc");
+  server.assert_response_regex(format!("/content/{}", inscription[1]), "<h1>BAR</h1>");
+  server.assert_response_regex(
+    format!("/inscription/{}", inscription[1]),
+    ".*<dt>metadata</dt>.*title.*bar.*",
+  );
+}
+
+#[test]
+fn inscribes_manifest_is_validated_before_signing() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribes --fee-rate 1 --manifest manifest.json")
+    .write(
+      "manifest.json",
+      r#"{"inscriptions": [
+  {"text": "foo.btc", "destination": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"},
+  {"text": "foo.btc", "destination": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"}
+]}"#,
+    )
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: manifest inscriptions 0 and 1 both inscribe name foo.btc\n.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet inscribes --fee-rate 1 --manifest manifest.yaml")
+    .write(
+      "manifest.yaml",
+      "inscriptions:
+- text: foo.btc
+  destination: tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm
+",
+    )
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: manifest inscription 0 has destination for wrong network\n.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn inscribes_empty_batch_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribes --fee-rate 1 --batch manifest.txt")
+    .write("manifest.txt", "# nothing\n")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex("error: batch manifest.txt contains no files\n.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_content_type_override() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribe { inscription, .. } =
+    CommandBuilder::new("wallet inscribe --fee-rate 1 --content-type text/x-foo foo.bar")
+      .write("foo.bar", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  rpc_server.mine_blocks(1);
+
+  TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+    format!("/api/v1/inscription/{inscription}"),
+    r#".*"content_type":"text/x-foo".*"#,
+  );
+}
+
+#[test]
+fn inscribe_with_mismatched_extension_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
+    .write("foo.txt", b"\x89PNG\r\n\x1a\n")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(
+      "error: .*foo.txt looks like image/png but its extension implies text/plain;charset=utf-8, use --content-type to override\n.*",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_exceeding_max_body_size_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --max-body-size 2 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex("error: content size of 3 bytes exceeds 2 byte maximum body size\n.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribes_malformed_name_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 name.txt")
+    .write("name.txt", "Foo.btc")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(
+      "error: refusing to inscribe malformed .btc name \"Foo.btc\": contains uppercase characters\n.*",
+    )
+    .run_and_extract_stdout();
+}
+
+#[derive(Deserialize)]
+struct JobReveal {
+  txid: Txid,
+  state: String,
+  inscriptions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct JobStatus {
+  job: Txid,
+  commit_state: String,
+  reveals: Vec<JobReveal>,
+}
+
+fn job_status(
+  rpc_server: &test_bitcoincore_rpc::Handle,
+  tempdir: &Arc<TempDir>,
+  command: &str,
+  job: Txid,
+) -> JobStatus {
+  CommandBuilder::new(format!("wallet inscribes {command} {job}"))
+    .temp_dir(tempdir.clone())
+    .rpc_server(rpc_server)
+    .run_and_check_output()
+}
+
+#[test]
+fn inscribes_only_commit_job_can_be_resumed() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes {
+    commit,
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 2 --only-commit true foo.txt")
+    .temp_dir(tempdir.clone())
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert!(reveal.is_empty());
+  assert!(inscription.is_empty());
+  assert_eq!(rpc_server.mempool().len(), 1);
+
+  let status = job_status(&rpc_server, &tempdir, "status", commit);
+  assert_eq!(status.job, commit);
+  assert_eq!(status.commit_state, "broadcast");
+  assert_eq!(status.reveals.len(), 2);
+  assert!(status.reveals.iter().all(|reveal| reveal.state == "unsent"));
+
+  let status = job_status(&rpc_server, &tempdir, "resume", commit);
+  assert!(status
+    .reveals
+    .iter()
+    .all(|reveal| reveal.state == "broadcast"));
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+  for reveal in &status.reveals {
+    assert!(mempool.iter().any(|tx| tx.txid() == reveal.txid));
+  }
+
+  rpc_server.mine_blocks(1);
+
+  let status = job_status(&rpc_server, &tempdir, "status", commit);
+  assert_eq!(status.commit_state, "confirmed");
+  assert!(status
+    .reveals
+    .iter()
+    .all(|reveal| reveal.state == "confirmed"));
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for reveal in &status.reveals {
+    server.assert_response_regex(format!("/content/{}", reveal.inscriptions[0]), "FOO");
+  }
+}
+
+#[test]
+fn inscribes_job_rebroadcasts_evicted_transactions() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, reveal, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+
+  rpc_server.clear_mempool();
+
+  let status = job_status(&rpc_server, &tempdir, "status", commit);
+  assert_eq!(status.commit_state, "unsent");
+  assert_eq!(status.reveals[0].state, "unsent");
+
+  let status = job_status(&rpc_server, &tempdir, "resume", commit);
+  assert_eq!(status.commit_state, "broadcast");
+  assert_eq!(status.reveals[0].state, "broadcast");
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[0].txid(), commit);
+  assert_eq!(mempool[1].txid(), reveal[0]);
+}
+
+#[test]
+fn inscribes_job_finds_confirmed_transactions_without_txindex() {
+  let rpc_server = test_bitcoincore_rpc::builder().txindex(false).build();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 1 --only-commit true foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  rpc_server.mine_blocks(1);
+
+  let status = job_status(&rpc_server, &tempdir, "status", commit);
+  assert_eq!(status.commit_state, "confirmed");
+  assert_eq!(status.reveals[0].state, "unsent");
+
+  let status = job_status(&rpc_server, &tempdir, "resume", commit);
+  assert_eq!(status.commit_state, "confirmed");
+  assert_eq!(status.reveals[0].state, "broadcast");
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid(), status.reveals[0].txid);
+}
+
+#[test]
+fn inscribes_splits_mint_size_across_commits() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(2);
+
+  let Inscribes {
+    commits,
+    inscription,
+    reveal,
+    ..
+  } = CommandBuilder::new(
+    "wallet inscribes --fee-rate 1 --mint-size 3 --reveals-per-commit 2 foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_check_output();
+
+  assert_eq!(commits.len(), 2);
+  assert_eq!(reveal.len(), 3);
+  assert_eq!(inscription.len(), 3);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 5);
+  assert_eq!(mempool[0].txid(), commits[0]);
+  assert_eq!(mempool[3].txid(), commits[1]);
+  assert_eq!(mempool[3].input.len(), 1);
+  assert_ne!(mempool[3].input[0].previous_output.txid, commits[0]);
+  assert_ne!(
+    mempool[3].input[0].previous_output,
+    mempool[0].input[0].previous_output
+  );
+  assert_eq!(mempool[4].input[0].previous_output.txid, commits[1]);
+
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for inscription in inscription {
+    server.assert_response_regex(format!("/content/{inscription}"), "FOO");
+  }
+}
+
+#[test]
+fn inscribes_does_not_chain_commits_on_unconfirmed_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet inscribes --fee-rate 1 --mint-size 3 --reveals-per-commit 2 foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex(
+    "error: wallet does not contain enough confirmed cardinal UTXOs to fund 2 commit transactions separately, please add additional funds to wallet.\n.*",
+  )
+  .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn inscribes_waits_for_block_when_mempool_chain_is_too_long() {
+  let rpc_server = test_bitcoincore_rpc::builder()
+    .mempool_chain_limit(3)
+    .build();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let command = CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 3 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .stderr_regex(
+      "reveal transaction .* rejected with `too-long-mempool-chain, .*`, waiting for block 2\n",
+    );
+
+  let inscribes = thread::spawn(move || command.run_and_check_output::<Inscribes>());
+
+  // the commit and two reveals fill the chain, the third reveal waits for a block
+  for attempt in 0.. {
+    if rpc_server.mempool().len() == 3 {
+      break;
+    }
+
+    assert!(attempt < 100, "reveals were not broadcast");
+
+    thread::sleep(Duration::from_millis(50));
+  }
+
+  thread::sleep(Duration::from_millis(250));
+  assert_eq!(rpc_server.mempool().len(), 3);
+
+  rpc_server.mine_blocks(1);
+
+  let Inscribes { reveal, .. } = inscribes.join().unwrap();
+
+  assert_eq!(reveal.len(), 3);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid(), reveal[2]);
+}
+
+#[test]
+fn inscribes_unknown_job_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new(
+    "wallet inscribes status 0000000000000000000000000000000000000000000000000000000000000000",
+  )
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex("error: no batch inscribe job 0{64} in .*\n.*")
+  .run_and_extract_stdout();
+}
+
+#[derive(Deserialize)]
+struct RecoverCommit {
+  transaction: Txid,
+  recovered: Vec<OutPoint>,
+  value: u64,
+}
+
+#[test]
+fn inscribes_journals_recovery_keys_under_data_dir() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 2 foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  assert!(fs::read_dir(tempdir.path()).unwrap().all(|entry| !entry
+    .unwrap()
+    .file_name()
+    .to_str()
+    .unwrap()
+    .starts_with("output_")));
+
+  let recovery_descriptors = rpc_server
+    .descriptors()
+    .into_iter()
+    .filter(|descriptor| descriptor.starts_with("rawtr("))
+    .collect::<Vec<String>>();
+
+  assert_eq!(recovery_descriptors.len(), 2);
+
+  let journal =
+    fs::read_to_string(tempdir.path().join("keys").join(format!("{commit}.json"))).unwrap();
+
+  for descriptor in recovery_descriptors {
+    let wif = descriptor
+      .strip_prefix("rawtr(")
+      .unwrap()
+      .split(')')
+      .next()
+      .unwrap();
+    assert!(!journal.contains(wif));
+  }
+}
+
+#[test]
+fn recover_commit_sweeps_unrevealed_commit_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let Inscribes { commit, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 2 --only-commit true foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  rpc_server.mine_blocks(1);
+
+  let RecoverCommit {
+    transaction,
+    recovered,
+    value,
+  } = CommandBuilder::new(format!("wallet recover-commit --fee-rate 1 {commit}"))
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .run_and_check_output();
+
+  assert_eq!(recovered.len(), 2);
+  assert!(recovered.iter().all(|outpoint| outpoint.txid == commit));
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+
+  let sweep = &mempool[0];
+  assert_eq!(sweep.txid(), transaction);
+  assert_eq!(
+    sweep
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>(),
+    recovered
+  );
+  assert_eq!(sweep.output.len(), 1);
+  assert_eq!(sweep.output[0].value, value);
+
+  CommandBuilder::new(format!("wallet recover-commit --fee-rate 1 {commit}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(format!(
+      "error: commit {commit} has no unspent outputs to recover\n.*"
+    ))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn recover_commit_without_journal_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new(
+    "wallet recover-commit --fee-rate 1 0000000000000000000000000000000000000000000000000000000000000000",
+  )
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex("error: no recovery key journal for 0{64} in .*\n.*")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribes_only_spends_inputs_needed_for_commit() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(3);
+
+  let Inscribes { commit, .. } =
+    CommandBuilder::new("wallet inscribes --fee-rate 1 --mint-size 2 foo.txt")
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_check_output();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool[0].txid(), commit);
+  assert_eq!(mempool[0].input.len(), 1);
+}
+
+#[test]
+fn inscribes_with_coin_control() {
+  let cases: [(fn(&[OutPoint]) -> String, usize); 3] = [
+    (|coinbases| format!("--utxo {}", coinbases[1]), 1),
+    (
+      |coinbases| {
+        format!(
+          "--exclude-utxo {} --exclude-utxo {}",
+          coinbases[0], coinbases[1]
+        )
+      },
+      2,
+    ),
+    (|_| "--min-conf 3".into(), 0),
+  ];
+
+  for (args, spent) in cases {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    let coinbases = rpc_server
+      .mine_blocks(3)
+      .iter()
+      .map(|block| OutPoint {
+        txid: block.txdata[0].txid(),
+        vout: 0,
+      })
+      .collect::<Vec<OutPoint>>();
+
+    CommandBuilder::new(format!(
+      "wallet inscribes --fee-rate 1 --mint-size 1 {} foo.txt",
+      args(&coinbases)
+    ))
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Inscribes>();
+
+    assert_eq!(
+      rpc_server.mempool()[0]
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [coinbases[spent]],
+    );
+  }
+}
+
+#[test]
+fn inscribes_utxo_must_be_in_wallet() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let outpoint = "0000000000000000000000000000000000000000000000000000000000000000:0";
+
+  CommandBuilder::new(format!(
+    "wallet inscribes --fee-rate 1 --mint-size 1 --utxo {outpoint} foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex(format!(
+    "error: --utxo {outpoint} is not an unspent output in wallet\n.*"
+  ))
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_min_conf_fails_without_confirmed_utxos() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --min-conf 2 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex("error: wallet contains no cardinal utxos\n.*")
+    .run_and_extract_stdout();
+}
@@ -35,3 +35,57 @@ fn outputs_includes_locked_outputs() {
   assert_eq!(output[0].output, outpoint);
   assert_eq!(output[0].amount, amount);
 }
+
+#[test]
+fn outputs_includes_small_inscription_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let Inscribe { reveal, .. } = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("wallet outputs")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<Output>>();
+
+  let inscription_output = output
+    .iter()
+    .find(|output| output.output == OutPoint::new(reveal, 0))
+    .unwrap();
+
+  assert!(inscription_output.amount <= 2000);
+  assert_eq!(inscription_output.excluded, None);
+}
+
+#[test]
+fn outputs_all_lists_excluded_outputs_with_reason() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let coinbase_tx = &rpc_server.mine_blocks_with_subsidy(1, 1_000)[0].txdata[0];
+  let outpoint = OutPoint::new(coinbase_tx.txid(), 0);
+
+  assert!(CommandBuilder::new("wallet outputs")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<Output>>()
+    .is_empty());
+
+  let output = CommandBuilder::new("wallet outputs --all")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].output, outpoint);
+  assert_eq!(output[0].amount, 1_000);
+  assert_eq!(
+    output[0].excluded.as_deref(),
+    Some("cardinal value of 1000 sats is not above --min-cardinal-value of 2000 sats"),
+  );
+
+  let output = CommandBuilder::new("--min-cardinal-value 999 wallet outputs")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].output, outpoint);
+  assert_eq!(output[0].excluded, None);
+}
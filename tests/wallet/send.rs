@@ -279,6 +279,50 @@ fn send_btc_locks_inscriptions() {
   )
 }
 
+#[test]
+fn send_btc_holds_back_utxos_excluded_by_coin_control() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let coinbase = OutPoint {
+    txid: rpc_server.mine_blocks(1)[0].txdata[0].txid(),
+    vout: 0,
+  };
+
+  rpc_server.mine_blocks(1);
+
+  let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    .parse::<Address<NetworkUnchecked>>()
+    .unwrap()
+    .assume_checked();
+
+  CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --exclude-utxo {coinbase} {address} 1btc"
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_check_output::<Output>();
+
+  CommandBuilder::new(format!("wallet send --fee-rate 1 {address} 1btc"))
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  assert_eq!(
+    rpc_server.sent(),
+    &[
+      Sent {
+        amount: 1.0,
+        address: address.clone(),
+        locked: vec![coinbase],
+      },
+      Sent {
+        amount: 1.0,
+        address,
+        locked: Vec::new(),
+      },
+    ]
+  )
+}
+
 #[test]
 fn send_btc_fails_if_lock_unspent_fails() {
   let rpc_server = test_bitcoincore_rpc::builder()
@@ -0,0 +1,92 @@
+use {super::*, ord::subcommand::wallet::send_many::Output};
+
+#[test]
+fn inscriptions_can_be_sent_in_one_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let Inscribe { inscription: a, .. } = inscribe(&rpc_server);
+  let Inscribe { inscription: b, .. } = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 sends.csv")
+    .write(
+      "sends.csv",
+      format!(
+        "# recipients\nbc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,{a}\n\nbc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv,{b}\n"
+      ),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid(), output.transaction);
+
+  for (vout, address) in [
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+    "bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv",
+  ]
+  .into_iter()
+  .enumerate()
+  {
+    assert_eq!(
+      mempool[0].output[vout].script_pubkey,
+      address
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .assume_checked()
+        .script_pubkey()
+    );
+  }
+
+  rpc_server.mine_blocks(1);
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for (inscription, vout) in [(a, 0), (b, 1)] {
+    ord_server.assert_response_regex(
+      format!("/inscription/{inscription}"),
+      format!(
+        ".*<dt>location</dt>\n  <dd class=monospace>{}:{vout}:0</dd>.*",
+        output.transaction
+      ),
+    );
+  }
+}
+
+#[test]
+fn send_many_rejects_amounts() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet send-many --fee-rate 1 sends.csv")
+    .write(
+      "sends.csv",
+      "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,1btc\n",
+    )
+    .rpc_server(&rpc_server)
+    .stderr_regex(
+      "error: cannot send amount \"1btc\" on line 1: only sats and inscriptions may be sent\n.*",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn send_many_requires_cardinal_utxo_for_fee() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let txid = rpc_server.mine_blocks_with_subsidy(1, 10_000)[0].txdata[0].txid();
+
+  CommandBuilder::new("wallet send-many --fee-rate 1 sends.csv")
+    .write(
+      "sends.csv",
+      format!("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,{txid}:0:0\n"),
+    )
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: wallet does not contain enough cardinal UTXOs.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}